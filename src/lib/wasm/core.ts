@@ -1,12 +1,17 @@
 import type { StarMemoryPointers, FrameUpdateResult } from './starfield'
-import type { ScatterTextPointers } from './scatter-text'
+import type { ScatterTextPointers, ScatterTextStatus } from './scatter-text'
+import type { ColorFormat, ColorSpace, ParticleBuffers } from './types'
 
 let wasmModule: WASMModule | null = null
 let loadPromise: Promise<WASMModule> | null = null
 
 export interface WASMModule {
   memory: WebAssembly.Memory
-  initialize_star_memory_pool: (count: number) => StarMemoryPointers
+  initialize_star_memory_pool: (
+    count: number,
+    color_space: ColorSpace,
+    color_format: ColorFormat
+  ) => StarMemoryPointers
   update_frame_simd: (
     time: number,
     delta_time: number,
@@ -27,6 +32,7 @@ export interface WASMModule {
     speed_multiplier: number,
     delta_time: number
   ) => number
+  get_star_buffers: () => ParticleBuffers | undefined
   destroy_star_memory_pool: () => void
   set_text_pixels: (
     pixel_data: Uint8Array,
@@ -36,9 +42,13 @@ export interface WASMModule {
     canvas_height: number,
     skip: number
   ) => number
-  get_scatter_text_pointers: () => ScatterTextPointers
+  get_scatter_text_pointers: () => ScatterTextPointers | undefined
+  get_scatter_text_buffers: () => ParticleBuffers | undefined
   start_forming: () => void
-  update_particles: (delta_time: number) => void
+  /** Returns only the idle flag, so the per-frame call allocates no JS object. */
+  update_particles: (delta_time: number) => boolean
+  /** Settled fraction, mean distance and transparent count of the last update. */
+  get_scatter_text_status: () => ScatterTextStatus
 }
 
 type WasmFunctions = Omit<WASMModule, 'memory'>
@@ -73,11 +83,14 @@ export async function loadWASM(): Promise<WASMModule> {
         update_frame_simd: wasmImport.update_frame_simd,
         calculate_speed_multiplier: wasmImport.calculate_speed_multiplier,
         calculate_rotation_delta: wasmImport.calculate_rotation_delta,
+        get_star_buffers: wasmImport.get_star_buffers,
         destroy_star_memory_pool: wasmImport.destroy_star_memory_pool,
         set_text_pixels: wasmImport.set_text_pixels,
         get_scatter_text_pointers: wasmImport.get_scatter_text_pointers,
+        get_scatter_text_buffers: wasmImport.get_scatter_text_buffers,
         start_forming: wasmImport.start_forming,
         update_particles: wasmImport.update_particles,
+        get_scatter_text_status: wasmImport.get_scatter_text_status,
       }
 
       return wasmModule
@@ -7,10 +7,29 @@ export interface ScatterTextPointers extends PointerBase {
   scatter_vx_ptr: number
   scatter_vy_ptr: number
   opacity_ptr: number
+  sizes_ptr: number
+  rotations_ptr: number
   scattered_flags_ptr: number
+  phases_ptr: number
+  trail_x_ptr: number
+  trail_y_ptr: number
+  trail_length: number
+  trail_stride: number
   particle_count: number
 }
 
+/** Heap-allocated by `get_scatter_text_status`; call `free()` when done. */
+export interface ScatterTextStatus {
+  particle_count: number
+  settled_fraction: number
+  mean_distance: number
+  transparent_count: number
+  idle: boolean
+  trail_head: number
+  trail_filled: number
+  free(): void
+}
+
 export class ScatterTextSharedMemory {
   private static instance: ScatterTextSharedMemory | null = null
   private wasmMemory: WebAssembly.Memory
@@ -24,6 +43,10 @@ export class ScatterTextSharedMemory {
   public colors_g: Float32Array
   public colors_b: Float32Array
   public opacity: Float32Array
+  public sizes: Float32Array
+  public rotations: Float32Array
+  public trail_x: Float32Array
+  public trail_y: Float32Array
 
   private constructor(wasmModule: WASMModule, pointers: ScatterTextPointers) {
     this.wasmMemory = wasmModule.memory
@@ -68,6 +91,22 @@ export class ScatterTextSharedMemory {
       alignedCount
     )
     this.opacity = new Float32Array(this.wasmMemory.buffer, this.pointers.opacity_ptr, alignedCount)
+    this.sizes = new Float32Array(this.wasmMemory.buffer, this.pointers.sizes_ptr, alignedCount)
+    this.rotations = new Float32Array(
+      this.wasmMemory.buffer,
+      this.pointers.rotations_ptr,
+      alignedCount
+    )
+    this.trail_x = new Float32Array(
+      this.wasmMemory.buffer,
+      this.pointers.trail_x_ptr,
+      pointers.trail_length * pointers.trail_stride
+    )
+    this.trail_y = new Float32Array(
+      this.wasmMemory.buffer,
+      this.pointers.trail_y_ptr,
+      pointers.trail_length * pointers.trail_stride
+    )
   }
 
   get particleCount(): number {
@@ -83,6 +122,9 @@ export class ScatterTextSharedMemory {
 
   static setInstance(wasmModule: WASMModule): void {
     const pointers = wasmModule.get_scatter_text_pointers()
+    if (!pointers) {
+      throw new Error('ScatterText has no text. Call set_text_pixels() before setInstance().')
+    }
     ScatterTextSharedMemory.instance = new ScatterTextSharedMemory(wasmModule, pointers)
   }
 
@@ -90,8 +132,18 @@ export class ScatterTextSharedMemory {
     ScatterTextSharedMemory.instance = null
   }
 
-  updateFrame(wasmModule: WASMModule, deltaTime: number): void {
-    wasmModule.update_particles(deltaTime)
+  /**
+   * Advances one frame; true once the current transition has finished. The
+   * progress metrics are not returned here: call `getStatus` (or
+   * `get_scatter_text_status`) when they are needed, and free the result.
+   */
+  updateFrame(wasmModule: WASMModule, deltaTime: number): boolean {
+    return wasmModule.update_particles(deltaTime)
+  }
+
+  /** Progress of the last `updateFrame`; call `free()` on the result. */
+  getStatus(wasmModule: WASMModule): ScatterTextStatus {
+    return wasmModule.get_scatter_text_status()
   }
 
   snapToFinalPositions(): void {
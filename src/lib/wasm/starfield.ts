@@ -1,5 +1,5 @@
 import type { WASMModule } from './core'
-import type { PointerBase } from './types'
+import { ColorFormat, ColorSpace, type PointerBase } from './types'
 
 export interface StarMemoryPointers extends PointerBase {
   positions_z_ptr: number
@@ -47,7 +47,11 @@ export class StarFieldSharedMemory {
   constructor(wasmModule: WASMModule, starCount: number) {
     this.wasmModule = wasmModule
     this.wasmMemory = wasmModule.memory
-    this.pointers = wasmModule.initialize_star_memory_pool(starCount)
+    this.pointers = wasmModule.initialize_star_memory_pool(
+      starCount,
+      ColorSpace.Srgb,
+      ColorFormat.None
+    )
     this.cameraMatrixPtr = 0
 
     this.positions_x = null
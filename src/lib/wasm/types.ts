@@ -1,3 +1,25 @@
+/**
+ * Encoding of a particle colour buffer; mirrors `ColorSpace` in wasm/src/color.rs.
+ * Shaders that bypass three.js colour management want `Srgb`.
+ */
+export const ColorSpace = {
+  Srgb: 0,
+  LinearSrgb: 1,
+} as const
+export type ColorSpace = (typeof ColorSpace)[keyof typeof ColorSpace]
+
+/**
+ * Optional packed colour copy; mirrors `ColorFormat` in wasm/src/color.rs.
+ * `Rgba8` is viewed as a Uint8Array, `Rgb565` and `HalfRgba` as Uint16Arrays.
+ */
+export const ColorFormat = {
+  None: 0,
+  Rgba8: 1,
+  Rgb565: 2,
+  HalfRgba: 3,
+} as const
+export type ColorFormat = (typeof ColorFormat)[keyof typeof ColorFormat]
+
 /**
  * Shared pointer fields common to both StarMemoryPointers and ScatterTextPointers.
  * Every WASM memory layout that represents positioned, colored particles
@@ -9,4 +31,77 @@ export interface PointerBase {
   colors_r_ptr: number
   colors_g_ptr: number
   colors_b_ptr: number
+  color_space: ColorSpace
+  color_format: ColorFormat
+  packed_colors_ptr: number
+  packed_colors_length: number
+}
+
+/** View over the packed colour buffer, or null when `color_format` is `None`. */
+export function packedColorView(
+  memory: WebAssembly.Memory,
+  pointers: PointerBase
+): Uint8Array | Uint16Array | null {
+  switch (pointers.color_format) {
+    case ColorFormat.Rgba8:
+      return new Uint8Array(
+        memory.buffer,
+        pointers.packed_colors_ptr,
+        pointers.packed_colors_length
+      )
+    case ColorFormat.Rgb565:
+    case ColorFormat.HalfRgba:
+      return new Uint16Array(
+        memory.buffer,
+        pointers.packed_colors_ptr,
+        pointers.packed_colors_length
+      )
+    default:
+      return null
+  }
+}
+
+/** Element type of a particle column; mirrors `AttributeKind` in wasm/src/engine.rs. */
+export const AttributeKind = {
+  F32: 0,
+  U8: 1,
+  Flags: 2,
+} as const
+export type AttributeKind = (typeof AttributeKind)[keyof typeof AttributeKind]
+
+/** Every column of a particle store, in declaration order. Stale once the store grows. */
+export interface ParticleBuffers {
+  readonly count: number
+  readonly attribute_count: number
+  name(index: number): string | undefined
+  kind(index: number): AttributeKind | undefined
+  ptr(index: number): number
+  length(index: number): number
+  index_of(name: string): number | undefined
+  free(): void
+}
+
+/** Typed-array view of every column, keyed by attribute name. */
+export function attributeViews(
+  memory: WebAssembly.Memory,
+  buffers: ParticleBuffers
+): Record<string, Float32Array | Uint8Array | BigUint64Array> {
+  const views: Record<string, Float32Array | Uint8Array | BigUint64Array> = {}
+  for (let i = 0; i < buffers.attribute_count; i++) {
+    const name = buffers.name(i)!
+    const ptr = buffers.ptr(i)
+    const length = buffers.length(i)
+    switch (buffers.kind(i)) {
+      case AttributeKind.F32:
+        views[name] = new Float32Array(memory.buffer, ptr, length)
+        break
+      case AttributeKind.U8:
+        views[name] = new Uint8Array(memory.buffer, ptr, length)
+        break
+      case AttributeKind.Flags:
+        views[name] = new BigUint64Array(memory.buffer, ptr, length)
+        break
+    }
+  }
+  return views
 }
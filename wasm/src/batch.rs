@@ -0,0 +1,271 @@
+// Lane-width-agnostic driver for structure-of-arrays kernels.
+//
+// Kernels are written once against `F32s`/`U32s`/`LaneMask` and run through
+// `for_each_batch`, which hands out one `Batch` per LANES elements. The last
+// batch is masked instead of falling back to a scalar tail, so vector and
+// remainder results can never drift apart.
+//
+// LANES defaults to 16; build with `--features lanes-8` or `lanes-4` to compare
+// widths against WASM SIMD128 (4 x f32 per native register).
+
+use std::simd::cmp::SimdPartialOrd;
+use std::simd::num::SimdFloat;
+use std::simd::{Mask, Select, Simd};
+
+#[cfg(feature = "lanes-4")]
+pub(crate) const LANES: usize = 4;
+#[cfg(all(feature = "lanes-8", not(feature = "lanes-4")))]
+pub(crate) const LANES: usize = 8;
+#[cfg(not(any(feature = "lanes-4", feature = "lanes-8")))]
+pub(crate) const LANES: usize = 16;
+
+// Buffers shared with JS are padded to this regardless of LANES, so the
+// exported lengths don't change with the benchmark feature.
+pub(crate) const SIMD_BATCH_SIZE: usize = 16;
+
+const _: () = assert!(SIMD_BATCH_SIZE.is_multiple_of(LANES));
+
+pub(crate) type F32s = Simd<f32, LANES>;
+pub(crate) type U32s = Simd<u32, LANES>;
+pub(crate) type U16s = Simd<u16, LANES>;
+pub(crate) type U8s = Simd<u8, LANES>;
+pub(crate) type LaneMask = Mask<i32, LANES>;
+
+/// Element count rounded up to a whole number of SIMD batches.
+#[inline]
+pub(crate) fn padded_len(count: usize) -> usize {
+    count.div_ceil(SIMD_BATCH_SIZE) * SIMD_BATCH_SIZE
+}
+
+/// One LANES-wide window `start..start + LANES` of a SoA buffer.
+#[derive(Clone, Copy)]
+pub(crate) struct Batch {
+    pub start: usize,
+    /// Lanes below the kernel's element count
+    pub active: LaneMask,
+}
+
+impl Batch {
+    /// Loads this window, reading 0.0 past the end of `slice`.
+    #[inline]
+    pub fn load(&self, slice: &[f32]) -> F32s {
+        F32s::load_or_default(&slice[self.start.min(slice.len())..])
+    }
+
+    /// Writes the active lanes of `value`, leaving the rest of `slice` untouched.
+    #[inline]
+    pub fn store(&self, slice: &mut [f32], value: F32s) {
+        let start = self.start.min(slice.len());
+        value.store_select(&mut slice[start..], self.active);
+    }
+
+    #[inline]
+    pub fn store_u8(&self, slice: &mut [u8], value: U8s) {
+        let start = self.start.min(slice.len());
+        value.store_select(&mut slice[start..], self.active.cast());
+    }
+
+    #[inline]
+    pub fn store_u16(&self, slice: &mut [u16], value: U16s) {
+        let start = self.start.min(slice.len());
+        value.store_select(&mut slice[start..], self.active.cast());
+    }
+
+    #[inline]
+    pub fn store_u32(&self, slice: &mut [u32], value: U32s) {
+        let start = self.start.min(slice.len());
+        value.store_select(&mut slice[start..], self.active);
+    }
+
+    /// Writes two words per element, `even` at 2i and `odd` at 2i + 1.
+    #[inline]
+    pub fn store_u32_pairs(&self, slice: &mut [u32], even: U32s, odd: U32s) {
+        let (low, high) = even.interleave(odd);
+        let active = self.active.to_simd();
+        let (active_low, active_high) = active.interleave(active);
+        let start = (2 * self.start).min(slice.len());
+        low.store_select(&mut slice[start..], LaneMask::from_simd(active_low));
+        let start = (2 * self.start + LANES).min(slice.len());
+        high.store_select(&mut slice[start..], LaneMask::from_simd(active_high));
+    }
+
+    /// Global element index of every lane, for counter-based random streams.
+    #[inline]
+    pub fn indices(&self) -> U32s {
+        U32s::splat(self.start as u32) + LANE_OFFSETS
+    }
+
+    /// Number of active lanes set in `mask`.
+    #[inline]
+    pub fn count(&self, mask: LaneMask) -> usize {
+        (mask & self.active).to_bitmask().count_ones() as usize
+    }
+
+    /// Sum of `value` over the active lanes.
+    #[inline]
+    pub fn sum(&self, value: F32s) -> f32 {
+        self.active.select(value, F32s::splat(0.0)).reduce_sum()
+    }
+
+    /// Index into a 64-per-word bitset holding this batch.
+    #[inline]
+    pub fn flag_word(&self) -> usize {
+        self.start / 64
+    }
+
+    /// Active lanes of `mask`, shifted into position within `flag_word`.
+    #[inline]
+    pub fn flag_bits(&self, mask: LaneMask) -> u64 {
+        (mask & self.active).to_bitmask() << (self.start % 64)
+    }
+}
+
+const LANE_OFFSETS: U32s = {
+    let mut offsets = [0u32; LANES];
+    let mut lane = 0;
+    while lane < LANES {
+        offsets[lane] = lane as u32;
+        lane += 1;
+    }
+    U32s::from_array(offsets)
+};
+
+/// Runs `kernel` over `0..count` in LANES-wide batches, masking the last one.
+#[inline]
+pub(crate) fn for_each_batch(count: usize, mut kernel: impl FnMut(Batch)) {
+    let full = count / LANES * LANES;
+    let all = LaneMask::splat(true);
+
+    let mut start = 0;
+    while start < full {
+        kernel(Batch { start, active: all });
+        start += LANES;
+    }
+
+    if full < count {
+        let remaining = (count - full) as u32;
+        let active = LANE_OFFSETS.simd_lt(U32s::splat(remaining));
+        kernel(Batch {
+            start: full,
+            active,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::simd::cmp::SimdPartialEq;
+    use std::simd::num::SimdUint;
+
+    const TAIL_COUNTS: [usize; 8] = [0, 1, LANES - 1, LANES, LANES + 1, 17, 64, 100];
+
+    fn batches(count: usize) -> Vec<Batch> {
+        let mut batches = Vec::new();
+        for_each_batch(count, |batch| batches.push(batch));
+        batches
+    }
+
+    #[test]
+    fn batches_cover_exactly_count_elements() {
+        for count in TAIL_COUNTS {
+            let batches = batches(count);
+            assert_eq!(batches.len(), count.div_ceil(LANES), "count {count}");
+            for (n, batch) in batches.iter().enumerate() {
+                assert_eq!(batch.start, n * LANES);
+                let active = batch.active.to_array();
+                for (lane, &on) in active.iter().enumerate() {
+                    assert_eq!(on, batch.start + lane < count, "count {count} lane {lane}");
+                }
+                let indices = batch.indices().to_array();
+                for (lane, &index) in indices.iter().enumerate() {
+                    assert_eq!(index as usize, batch.start + lane);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn masked_stores_leave_padding_untouched() {
+        for count in TAIL_COUNTS {
+            let padded = padded_len(count);
+            let mut f = vec![-1.0f32; padded];
+            let mut bytes = vec![0xaau8; padded];
+            let mut halves = vec![0xaaaau16; padded];
+            let mut words = vec![u32::MAX; padded];
+            let mut pairs = vec![u32::MAX; 2 * padded];
+            for_each_batch(count, |batch| {
+                let index = batch.indices();
+                let value = batch.load(&f) + F32s::splat(2.0);
+                batch.store(&mut f, value);
+                batch.store_u8(&mut bytes, index.cast());
+                batch.store_u16(&mut halves, index.cast());
+                batch.store_u32(&mut words, index);
+                batch.store_u32_pairs(&mut pairs, index, index + U32s::splat(1000));
+            });
+
+            for i in 0..padded {
+                let live = i < count;
+                assert_eq!(f[i], if live { 1.0 } else { -1.0 }, "count {count} at {i}");
+                assert_eq!(bytes[i], if live { i as u8 } else { 0xaa });
+                assert_eq!(halves[i], if live { i as u16 } else { 0xaaaa });
+                assert_eq!(words[i], if live { i as u32 } else { u32::MAX });
+                let (even, odd) = (pairs[2 * i], pairs[2 * i + 1]);
+                if live {
+                    assert_eq!((even, odd), (i as u32, i as u32 + 1000));
+                } else {
+                    assert_eq!((even, odd), (u32::MAX, u32::MAX));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn loads_read_zero_past_the_slice() {
+        let short = [1.0f32, 2.0, 3.0];
+        let mut seen = Vec::new();
+        for_each_batch(short.len(), |batch| {
+            seen.extend(batch.load(&short).to_array())
+        });
+        assert_eq!(&seen[..3], &short);
+        assert!(seen[3..].iter().all(|&v| v == 0.0));
+    }
+
+    #[test]
+    fn reductions_ignore_inactive_lanes() {
+        for count in TAIL_COUNTS {
+            let (mut counted, mut sum) = (0, 0.0);
+            let mut even = 0;
+            for_each_batch(count, |batch| {
+                counted += batch.count(LaneMask::splat(true));
+                sum += batch.sum(F32s::splat(1.5));
+                let is_even = (batch.indices() % U32s::splat(2)).simd_eq(U32s::splat(0));
+                even += batch.count(is_even);
+            });
+            assert_eq!(counted, count);
+            assert_eq!(sum, count as f32 * 1.5);
+            assert_eq!(even, count.div_ceil(2));
+        }
+    }
+
+    #[test]
+    fn flag_bits_land_on_their_element_index() {
+        for count in TAIL_COUNTS {
+            let mut all = vec![0u64; count.div_ceil(64) + 1];
+            let mut odd = all.clone();
+            for_each_batch(count, |batch| {
+                // Batches never straddle a word
+                assert_eq!(batch.flag_word(), (batch.start + LANES - 1) / 64);
+                all[batch.flag_word()] |= batch.flag_bits(LaneMask::splat(true));
+                let is_odd = (batch.indices() % U32s::splat(2)).simd_eq(U32s::splat(1));
+                odd[batch.flag_word()] |= batch.flag_bits(is_odd);
+            });
+
+            for i in 0..all.len() * 64 {
+                let bit = |words: &[u64]| words[i / 64] >> (i % 64) & 1 == 1;
+                assert_eq!(bit(&all), i < count, "count {count} bit {i}");
+                assert_eq!(bit(&odd), i < count && i % 2 == 1, "count {count} bit {i}");
+            }
+        }
+    }
+}
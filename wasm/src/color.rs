@@ -0,0 +1,921 @@
+// Colour spaces, conversions and gradients.
+//
+// Colours arriving from CSS, canvas pixels and palettes are gamma-encoded sRGB
+// in [0, 1]. Blending belongs in linear sRGB, which is also what three.js
+// expects when colour management is on, and perceptual interpolation in OKLab
+// or OKLCH (Björn Ottosson, 2020). Like the noise and tween modules, every
+// conversion is written once over N lanes and the scalar forms run it on one
+// lane. Colours travel as `[r, g, b]` (or `[L, a, b]`, ...) channel arrays.
+
+use std::f32::consts::TAU;
+use std::simd::cmp::{SimdPartialEq, SimdPartialOrd};
+use std::simd::num::{SimdFloat, SimdUint};
+use std::simd::{f32x16, u16x16, u32x16, Select, Simd, StdFloat};
+
+use wasm_bindgen::prelude::*;
+
+use crate::batch::{for_each_batch, F32s};
+use crate::math::{atan2_approx_simd, exp_approx_simd, ln_approx_simd, sincos_approx_simd};
+
+// sRGB transfer function (IEC 61966-2-1)
+const SRGB_DECODE_KNEE: f32 = 0.040_45;
+const SRGB_ENCODE_KNEE: f32 = 0.003_130_8;
+const SRGB_LINEAR_SLOPE: f32 = 12.92;
+const SRGB_OFFSET: f32 = 0.055;
+const SRGB_GAMMA: f32 = 2.4;
+
+// Rec. 709 luma weights for linear sRGB
+const LUMINANCE: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+// Linear sRGB to cone response, and cube-rooted cone response to OKLab
+const LINEAR_TO_LMS: [[f32; 3]; 3] = [
+    [0.412_221_47, 0.536_332_55, 0.051_445_995],
+    [0.211_903_5, 0.680_699_5, 0.107_396_96],
+    [0.088_302_46, 0.281_718_84, 0.629_978_7],
+];
+const LMS_TO_OKLAB: [[f32; 3]; 3] = [
+    [0.210_454_26, 0.793_617_8, -0.004_072_047],
+    [1.977_998_5, -2.428_592_2, 0.450_593_7],
+    [0.025_904_037, 0.782_771_77, -0.808_675_77],
+];
+
+// And back again
+const OKLAB_TO_LMS: [[f32; 3]; 3] = [
+    [1.0, 0.396_337_78, 0.215_803_76],
+    [1.0, -0.105_561_346, -0.063_854_17],
+    [1.0, -0.089_484_18, -1.291_485_5],
+];
+const LMS_TO_LINEAR: [[f32; 3]; 3] = [
+    [4.076_741_7, -3.307_711_6, 0.230_969_94],
+    [-1.268_438, 2.609_757_4, -0.341_319_38],
+    [-0.004_196_086_3, -0.703_418_6, 1.707_614_7],
+];
+
+// f32 -> f16 bit tricks (Fabian Giesen's float_to_half_fast3_rtne): the
+// first f32 too large for a half, the smallest half normal, the magic
+// addend that rounds subnormals, and the exponent rebias for normals
+const F16_OVERFLOW: u32 = (127 + 16) << 23;
+const F16_MIN_NORMAL: u32 = 113 << 23;
+const F16_SUBNORMAL_MAGIC: u32 = ((127 - 15) + (23 - 10) + 1) << 23;
+const F16_REBIAS: u32 = ((15 - 127) << 23) as u32;
+const F16_INFINITY: u32 = 0x7c00;
+const F16_NAN: u32 = 0x7e00;
+
+// Below this OKLCH chroma a colour is grey and its hue is meaningless
+const ACHROMATIC_CHROMA: f32 = 1e-4;
+
+type Lanes<const N: usize> = Simd<f32, N>;
+
+/// Three colour channels, one lane per colour.
+pub type Channels<const N: usize> = [Lanes<N>; 3];
+
+#[inline]
+fn one(c: [f32; 3]) -> Channels<1> {
+    c.map(Simd::splat)
+}
+
+#[inline]
+fn first(c: Channels<1>) -> [f32; 3] {
+    c.map(|channel| channel[0])
+}
+
+#[inline]
+fn transform<const N: usize>(m: &[[f32; 3]; 3], v: Channels<N>) -> Channels<N> {
+    m.map(|row| {
+        v[0] * Simd::splat(row[0]) + v[1] * Simd::splat(row[1]) + v[2] * Simd::splat(row[2])
+    })
+}
+
+// |x|^p carrying the sign of x, so out-of-gamut negatives survive round trips
+#[inline]
+fn signed_pow<const N: usize>(x: Lanes<N>, p: f32) -> Lanes<N> {
+    exp_approx_simd(ln_approx_simd(x.abs()) * Simd::splat(p)).copysign(x)
+}
+
+#[inline]
+fn unit<const N: usize>(c: Channels<N>) -> Channels<N> {
+    c.map(|channel| channel.simd_clamp(Simd::splat(0.0), Simd::splat(1.0)))
+}
+
+/// Encoding of a colour buffer shared with JS.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// Gamma-encoded, as CSS and canvas pixels
+    #[default]
+    Srgb = 0,
+    /// Linear light, as three.js shaders expect under colour management
+    LinearSrgb = 1,
+}
+
+impl ColorSpace {
+    /// Converts an sRGB colour into this space.
+    pub fn encode(self, srgb: [f32; 3]) -> [f32; 3] {
+        first(self.encode_lanes(one(srgb)))
+    }
+
+    /// Converts a colour in this space back to sRGB.
+    pub fn decode(self, color: [f32; 3]) -> [f32; 3] {
+        first(self.decode_lanes(one(color)))
+    }
+
+    pub fn encode_x16(self, srgb: Channels<16>) -> Channels<16> {
+        self.encode_lanes(srgb)
+    }
+
+    pub fn decode_x16(self, color: Channels<16>) -> Channels<16> {
+        self.decode_lanes(color)
+    }
+
+    pub fn encode_lanes<const N: usize>(self, srgb: Channels<N>) -> Channels<N> {
+        match self {
+            Self::Srgb => srgb,
+            Self::LinearSrgb => srgb.map(srgb_to_linear_lanes),
+        }
+    }
+
+    pub fn decode_lanes<const N: usize>(self, color: Channels<N>) -> Channels<N> {
+        match self {
+            Self::Srgb => color,
+            Self::LinearSrgb => color.map(linear_to_srgb_lanes),
+        }
+    }
+}
+
+/// Re-encodes the first `count` colours of SoA channel buffers from one space
+/// to another in place.
+pub(crate) fn convert_buffers(
+    from: ColorSpace,
+    to: ColorSpace,
+    [r, g, b]: [&mut [f32]; 3],
+    count: usize,
+) {
+    if from == to {
+        return;
+    }
+    for_each_batch(count, |batch| {
+        let srgb = from.decode_lanes([batch.load(r), batch.load(g), batch.load(b)]);
+        let [out_r, out_g, out_b] = to.encode_lanes(srgb);
+        batch.store(r, out_r);
+        batch.store(g, out_g);
+        batch.store(b, out_b);
+    });
+}
+
+/// Space in which gradients and mixes interpolate. Endpoints are always given
+/// and returned as sRGB.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InterpolationSpace {
+    /// Straight lerp of the encoded values, as CSS gradients by default
+    #[default]
+    Srgb = 0,
+    /// Physically even light mixing; midpoints look bright
+    LinearSrgb = 1,
+    /// Perceptually even lightness, no hue drift through grey
+    Oklab = 2,
+    /// OKLab in polar form; hue travels the shorter way round
+    Oklch = 3,
+}
+
+impl InterpolationSpace {
+    /// Coordinates of an sRGB colour in this space.
+    pub fn from_srgb_lanes<const N: usize>(self, srgb: Channels<N>) -> Channels<N> {
+        match self {
+            Self::Srgb => srgb,
+            Self::LinearSrgb => srgb.map(srgb_to_linear_lanes),
+            Self::Oklab => linear_srgb_to_oklab_lanes(srgb.map(srgb_to_linear_lanes)),
+            Self::Oklch => {
+                oklab_to_oklch_lanes(linear_srgb_to_oklab_lanes(srgb.map(srgb_to_linear_lanes)))
+            }
+        }
+    }
+
+    /// sRGB colour at coordinates in this space, clipped to the sRGB gamut for
+    /// the OK spaces.
+    pub fn to_srgb_lanes<const N: usize>(self, coords: Channels<N>) -> Channels<N> {
+        match self {
+            Self::Srgb => coords,
+            Self::LinearSrgb => coords.map(linear_to_srgb_lanes),
+            Self::Oklab => unit(oklab_to_linear_srgb_lanes(coords).map(linear_to_srgb_lanes)),
+            Self::Oklch => unit(
+                oklab_to_linear_srgb_lanes(oklch_to_oklab_lanes(coords)).map(linear_to_srgb_lanes),
+            ),
+        }
+    }
+
+    /// Interpolates between coordinates already in this space.
+    pub fn lerp_lanes<const N: usize>(
+        self,
+        from: Channels<N>,
+        to: Channels<N>,
+        t: Lanes<N>,
+    ) -> Channels<N> {
+        let lerp = |a: Lanes<N>, b: Lanes<N>| a + (b - a) * t;
+        if self != Self::Oklch {
+            return [
+                lerp(from[0], to[0]),
+                lerp(from[1], to[1]),
+                lerp(from[2], to[2]),
+            ];
+        }
+
+        // A grey endpoint takes the other end's hue, so fades to white keep
+        // their colour instead of sweeping through an arbitrary one
+        let achromatic = Simd::splat(ACHROMATIC_CHROMA);
+        let from_hue = from[1].simd_lt(achromatic).select(to[2], from[2]);
+        let to_hue = to[1].simd_lt(achromatic).select(from_hue, to[2]);
+
+        let mut delta = to_hue - from_hue;
+        delta -= Simd::splat(TAU) * (delta * Simd::splat(1.0 / TAU)).round();
+        [
+            lerp(from[0], to[0]),
+            lerp(from[1], to[1]),
+            from_hue + delta * t,
+        ]
+    }
+}
+
+/// Decodes one sRGB channel to linear light.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    srgb_to_linear_lanes(Simd::<f32, 1>::splat(c))[0]
+}
+
+/// Encodes one linear channel as sRGB.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    linear_to_srgb_lanes(Simd::<f32, 1>::splat(c))[0]
+}
+
+pub fn srgb_to_linear_x16(c: f32x16) -> f32x16 {
+    srgb_to_linear_lanes(c)
+}
+
+pub fn linear_to_srgb_x16(c: f32x16) -> f32x16 {
+    linear_to_srgb_lanes(c)
+}
+
+/// `srgb_to_linear` for each lane. Negative inputs mirror the curve, as CSS
+/// Color 4 extends it.
+///
+/// Max abs error vs the exact curve: 1e-6.
+pub fn srgb_to_linear_lanes<const N: usize>(c: Lanes<N>) -> Lanes<N> {
+    let curve = signed_pow(
+        (c.abs() + Simd::splat(SRGB_OFFSET)) * Simd::splat(1.0 / (1.0 + SRGB_OFFSET)),
+        SRGB_GAMMA,
+    )
+    .copysign(c);
+    c.abs()
+        .simd_le(Simd::splat(SRGB_DECODE_KNEE))
+        .select(c * Simd::splat(1.0 / SRGB_LINEAR_SLOPE), curve)
+}
+
+/// `linear_to_srgb` for each lane, mirrored for negative inputs.
+///
+/// Max abs error vs the exact curve: 1e-6.
+pub fn linear_to_srgb_lanes<const N: usize>(c: Lanes<N>) -> Lanes<N> {
+    let curve = (signed_pow(c.abs(), 1.0 / SRGB_GAMMA) * Simd::splat(1.0 + SRGB_OFFSET)
+        - Simd::splat(SRGB_OFFSET))
+    .copysign(c);
+    c.abs()
+        .simd_le(Simd::splat(SRGB_ENCODE_KNEE))
+        .select(c * Simd::splat(SRGB_LINEAR_SLOPE), curve)
+}
+
+/// Relative luminance (Y) of a linear sRGB colour.
+pub fn relative_luminance(linear: [f32; 3]) -> f32 {
+    relative_luminance_lanes(one(linear))[0]
+}
+
+pub fn relative_luminance_x16(linear: Channels<16>) -> f32x16 {
+    relative_luminance_lanes(linear)
+}
+
+pub fn relative_luminance_lanes<const N: usize>(linear: Channels<N>) -> Lanes<N> {
+    linear[0] * Simd::splat(LUMINANCE[0])
+        + linear[1] * Simd::splat(LUMINANCE[1])
+        + linear[2] * Simd::splat(LUMINANCE[2])
+}
+
+/// Linear sRGB to OKLab `[L, a, b]`, L being 0 for black and 1 for white.
+pub fn linear_srgb_to_oklab(linear: [f32; 3]) -> [f32; 3] {
+    first(linear_srgb_to_oklab_lanes(one(linear)))
+}
+
+pub fn oklab_to_linear_srgb(lab: [f32; 3]) -> [f32; 3] {
+    first(oklab_to_linear_srgb_lanes(one(lab)))
+}
+
+pub fn linear_srgb_to_oklab_x16(linear: Channels<16>) -> Channels<16> {
+    linear_srgb_to_oklab_lanes(linear)
+}
+
+pub fn oklab_to_linear_srgb_x16(lab: Channels<16>) -> Channels<16> {
+    oklab_to_linear_srgb_lanes(lab)
+}
+
+pub fn linear_srgb_to_oklab_lanes<const N: usize>(linear: Channels<N>) -> Channels<N> {
+    let lms = transform(&LINEAR_TO_LMS, linear).map(|c| signed_pow(c, 1.0 / 3.0));
+    transform(&LMS_TO_OKLAB, lms)
+}
+
+pub fn oklab_to_linear_srgb_lanes<const N: usize>(lab: Channels<N>) -> Channels<N> {
+    let lms = transform(&OKLAB_TO_LMS, lab).map(|c| c * c * c);
+    transform(&LMS_TO_LINEAR, lms)
+}
+
+/// OKLab to OKLCH `[L, C, h]`, hue in radians on [-pi, pi].
+pub fn oklab_to_oklch(lab: [f32; 3]) -> [f32; 3] {
+    first(oklab_to_oklch_lanes(one(lab)))
+}
+
+pub fn oklch_to_oklab(lch: [f32; 3]) -> [f32; 3] {
+    first(oklch_to_oklab_lanes(one(lch)))
+}
+
+pub fn oklab_to_oklch_x16(lab: Channels<16>) -> Channels<16> {
+    oklab_to_oklch_lanes(lab)
+}
+
+pub fn oklch_to_oklab_x16(lch: Channels<16>) -> Channels<16> {
+    oklch_to_oklab_lanes(lch)
+}
+
+pub fn oklab_to_oklch_lanes<const N: usize>([l, a, b]: Channels<N>) -> Channels<N> {
+    [l, (a * a + b * b).sqrt(), atan2_approx_simd(b, a)]
+}
+
+pub fn oklch_to_oklab_lanes<const N: usize>([l, c, h]: Channels<N>) -> Channels<N> {
+    let (sin, cos) = sincos_approx_simd(h);
+    [l, c * cos, c * sin]
+}
+
+/// sRGB to HSL `[h, s, l]`, hue in turns on [0, 1) as three.js `Color.getHSL`.
+pub fn srgb_to_hsl(srgb: [f32; 3]) -> [f32; 3] {
+    first(srgb_to_hsl_lanes(one(srgb)))
+}
+
+pub fn hsl_to_srgb(hsl: [f32; 3]) -> [f32; 3] {
+    first(hsl_to_srgb_lanes(one(hsl)))
+}
+
+pub fn srgb_to_hsl_x16(srgb: Channels<16>) -> Channels<16> {
+    srgb_to_hsl_lanes(srgb)
+}
+
+pub fn hsl_to_srgb_x16(hsl: Channels<16>) -> Channels<16> {
+    hsl_to_srgb_lanes(hsl)
+}
+
+pub fn srgb_to_hsl_lanes<const N: usize>([r, g, b]: Channels<N>) -> Channels<N> {
+    let max = r.simd_max(g).simd_max(b);
+    let min = r.simd_min(g).simd_min(b);
+    let lightness = (max + min) * Simd::splat(0.5);
+    let chroma = max - min;
+    let grey = chroma.simd_le(Simd::splat(0.0));
+
+    let spread = Simd::splat(1.0) - (lightness + lightness - Simd::splat(1.0)).abs();
+    let saturation = grey.select(Simd::splat(0.0), chroma / spread);
+
+    // Sextant of the hue wheel, from whichever channel is largest
+    let red_max = max.simd_eq(r);
+    let green_max = max.simd_eq(g) & !red_max;
+    let sextant = red_max.select(
+        (g - b) / chroma,
+        green_max.select(
+            (b - r) / chroma + Simd::splat(2.0),
+            (r - g) / chroma + Simd::splat(4.0),
+        ),
+    );
+    let hue = sextant * Simd::splat(1.0 / 6.0);
+    let hue = grey.select(Simd::splat(0.0), hue - hue.floor());
+
+    [hue, saturation, lightness]
+}
+
+pub fn hsl_to_srgb_lanes<const N: usize>([h, s, l]: Channels<N>) -> Channels<N> {
+    let amplitude = s * l.simd_min(Simd::splat(1.0) - l);
+    let channel = |n: f32| {
+        let k = Simd::splat(n) + h * Simd::splat(12.0);
+        let k = k - (k * Simd::splat(1.0 / 12.0)).floor() * Simd::splat(12.0);
+        let ramp = (k - Simd::splat(3.0))
+            .simd_min(Simd::splat(9.0) - k)
+            .simd_clamp(Simd::splat(-1.0), Simd::splat(1.0));
+        l - amplitude * ramp
+    };
+    [channel(0.0), channel(8.0), channel(4.0)]
+}
+
+/// Colour `t` of the way from `from` to `to` (both sRGB), interpolated in `space`.
+pub fn mix(from: [f32; 3], to: [f32; 3], t: f32, space: InterpolationSpace) -> [f32; 3] {
+    first(mix_lanes(one(from), one(to), Simd::splat(t), space))
+}
+
+pub fn mix_x16(
+    from: Channels<16>,
+    to: Channels<16>,
+    t: f32x16,
+    space: InterpolationSpace,
+) -> Channels<16> {
+    mix_lanes(from, to, t, space)
+}
+
+pub fn mix_lanes<const N: usize>(
+    from: Channels<N>,
+    to: Channels<N>,
+    t: Lanes<N>,
+    space: InterpolationSpace,
+) -> Channels<N> {
+    let coords = space.lerp_lanes(space.from_srgb_lanes(from), space.from_srgb_lanes(to), t);
+    space.to_srgb_lanes(coords)
+}
+
+/// Multi-stop gradient over [0, 1] with sRGB stops.
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    // (offset, sRGB) sorted by offset
+    stops: Vec<(f32, [f32; 3])>,
+    space: InterpolationSpace,
+    // Stop colours converted to `space`
+    coords: Vec<[f32; 3]>,
+}
+
+impl Gradient {
+    /// Sorts the stops by offset; returns `None` when there are none.
+    pub fn new(stops: &[(f32, [f32; 3])], space: InterpolationSpace) -> Option<Self> {
+        if stops.is_empty() || stops.iter().any(|(offset, _)| !offset.is_finite()) {
+            return None;
+        }
+        let mut stops = stops.to_vec();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut gradient = Self {
+            stops,
+            space,
+            coords: Vec::new(),
+        };
+        gradient.set_space(space);
+        Some(gradient)
+    }
+
+    /// Builds a gradient from flat (offset, r, g, b) stops, as passed from JS.
+    pub fn from_flat(values: &[f32], space: InterpolationSpace) -> Option<Self> {
+        let stops: Vec<(f32, [f32; 3])> = values
+            .as_chunks::<4>()
+            .0
+            .iter()
+            .map(|&[offset, r, g, b]| (offset, [r, g, b]))
+            .collect();
+        Self::new(&stops, space)
+    }
+
+    pub fn space(&self) -> InterpolationSpace {
+        self.space
+    }
+
+    pub fn set_space(&mut self, space: InterpolationSpace) {
+        self.space = space;
+        self.coords = self
+            .stops
+            .iter()
+            .map(|&(_, srgb)| first(space.from_srgb_lanes(one(srgb))))
+            .collect();
+    }
+
+    /// sRGB colour at `t`, clamped to [0, 1]. Before the first stop and after
+    /// the last the end colours hold.
+    pub fn sample(&self, t: f32) -> [f32; 3] {
+        first(self.sample_lanes(Simd::splat(t)))
+    }
+
+    pub fn sample_x16(&self, t: f32x16) -> Channels<16> {
+        self.sample_lanes(t)
+    }
+
+    /// `sample` for each lane. Like `Track`, every segment is evaluated and
+    /// lanes pick theirs by mask.
+    pub fn sample_lanes<const N: usize>(&self, t: Lanes<N>) -> Channels<N> {
+        let t = t.simd_clamp(Simd::splat(0.0), Simd::splat(1.0));
+        let mut result = self.coords[0].map(Simd::splat);
+
+        for (offsets, coords) in self.stops.windows(2).zip(self.coords.windows(2)) {
+            let (start, end) = (offsets[0].0, offsets[1].0);
+            let span = end - start;
+            let past = t.simd_gt(Simd::splat(start));
+            let f = if span > 0.0 {
+                ((t - Simd::splat(start)) * Simd::splat(1.0 / span)).simd_min(Simd::splat(1.0))
+            } else {
+                // Coincident stops make a hard edge
+                Simd::splat(1.0)
+            };
+            let value =
+                self.space
+                    .lerp_lanes(coords[0].map(Simd::splat), coords[1].map(Simd::splat), f);
+            for (channel, value) in result.iter_mut().zip(value) {
+                *channel = past.select(value, *channel);
+            }
+        }
+        self.space.to_srgb_lanes(result)
+    }
+}
+
+/// Optional packed copy of a particle colour buffer, cutting upload size
+/// against the three f32 channels. Channels are packed as stored, so 8-bit
+/// formats band in the darks when the buffer is `LinearSrgb`.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorFormat {
+    /// Float channels only
+    #[default]
+    None = 0,
+    /// 4 bytes per particle: R, G, B, opacity; a normalised Uint8Array, item size 4
+    Rgba8 = 1,
+    /// One u16 per particle, R in the top 5 bits, G in 6, B in 5; no opacity
+    Rgb565 = 2,
+    /// Four half floats per particle: R, G, B, opacity; a Uint16Array, item size 4
+    HalfRgba = 3,
+}
+
+/// Packs a colour as RGBA8 with R in the low byte, so the little-endian bytes
+/// read R, G, B, A.
+pub fn pack_rgba8(rgb: [f32; 3], alpha: f32) -> u32 {
+    pack_rgba8_lanes(one(rgb), Simd::splat(alpha))[0]
+}
+
+pub fn unpack_rgba8(packed: u32) -> [f32; 4] {
+    packed.to_le_bytes().map(|byte| byte as f32 / 255.0)
+}
+
+pub fn pack_rgba8_x16(rgb: Channels<16>, alpha: f32x16) -> u32x16 {
+    pack_rgba8_lanes(rgb, alpha)
+}
+
+pub fn pack_rgba8_lanes<const N: usize>([r, g, b]: Channels<N>, alpha: Lanes<N>) -> Simd<u32, N> {
+    unorm(r, 255.0) | unorm(g, 255.0) << 8 | unorm(b, 255.0) << 16 | unorm(alpha, 255.0) << 24
+}
+
+pub fn pack_rgb565(rgb: [f32; 3]) -> u16 {
+    pack_rgb565_lanes(one(rgb))[0]
+}
+
+pub fn unpack_rgb565(packed: u16) -> [f32; 3] {
+    [
+        (packed >> 11) as f32 / 31.0,
+        ((packed >> 5) & 0x3f) as f32 / 63.0,
+        (packed & 0x1f) as f32 / 31.0,
+    ]
+}
+
+pub fn pack_rgb565_x16(rgb: Channels<16>) -> u16x16 {
+    pack_rgb565_lanes(rgb)
+}
+
+pub fn pack_rgb565_lanes<const N: usize>([r, g, b]: Channels<N>) -> Simd<u16, N> {
+    (unorm(r, 31.0) << 11 | unorm(g, 63.0) << 5 | unorm(b, 31.0)).cast()
+}
+
+// Clamps to [0, 1] and rounds onto 0..=max
+#[inline]
+fn unorm<const N: usize>(c: Lanes<N>, max: f32) -> Simd<u32, N> {
+    (c.simd_clamp(Simd::splat(0.0), Simd::splat(1.0)) * Simd::splat(max) + Simd::splat(0.5)).cast()
+}
+
+/// IEEE 754 half-float bits of `x`, rounded to nearest even. Overflow gives
+/// infinity and NaN stays NaN.
+pub fn f32_to_f16(x: f32) -> u16 {
+    f32_to_f16_lanes(Simd::<f32, 1>::splat(x))[0]
+}
+
+/// Exact f32 value of half-float bits.
+pub fn f16_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let mantissa = (half & 0x3ff) as f32;
+    let magnitude = match (half >> 10) & 0x1f {
+        0 => mantissa * (-24f32).exp2(),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        exponent => (1.0 + mantissa / 1024.0) * (exponent as f32 - 15.0).exp2(),
+    };
+    sign * magnitude
+}
+
+pub fn f32_to_f16_x16(x: f32x16) -> u16x16 {
+    f32_to_f16_lanes(x)
+}
+
+pub fn f32_to_f16_lanes<const N: usize>(x: Lanes<N>) -> Simd<u16, N> {
+    let bits = x.to_bits();
+    let sign = bits & Simd::splat(0x8000_0000);
+    let abs = bits ^ sign;
+
+    let special = abs
+        .simd_gt(Simd::splat(0x7f80_0000))
+        .select(Simd::splat(F16_NAN), Simd::splat(F16_INFINITY));
+
+    // Subnormal halves: adding the magic float lets the FPU do the rounding
+    let magic = Simd::splat(F16_SUBNORMAL_MAGIC);
+    let subnormal = (Simd::<f32, N>::from_bits(abs) + Simd::from_bits(magic)).to_bits() - magic;
+
+    // Normal halves: rebias the exponent, round half to even, drop 13 bits
+    let odd = (abs >> 13) & Simd::splat(1);
+    let normal = (abs + Simd::splat(F16_REBIAS.wrapping_add(0xfff)) + odd) >> 13;
+
+    let half = abs.simd_ge(Simd::splat(F16_OVERFLOW)).select(
+        special,
+        abs.simd_lt(Simd::splat(F16_MIN_NORMAL))
+            .select(subnormal, normal),
+    );
+    (half | sign >> 16).cast()
+}
+
+/// Packed copy of a set of SoA colour channels, in one `ColorFormat`.
+#[derive(Clone, Debug, Default)]
+pub(crate) enum PackedColors {
+    #[default]
+    None,
+    Rgba8(Vec<u32>),
+    Rgb565(Vec<u16>),
+    // (R | G << 16, B | A << 16) word pairs
+    HalfRgba(Vec<u32>),
+}
+
+impl PackedColors {
+    /// Buffer for `aligned_count` particles.
+    pub fn new(format: ColorFormat, aligned_count: usize) -> Self {
+        match format {
+            ColorFormat::None => Self::None,
+            ColorFormat::Rgba8 => Self::Rgba8(vec![0; aligned_count]),
+            ColorFormat::Rgb565 => Self::Rgb565(vec![0; aligned_count]),
+            ColorFormat::HalfRgba => Self::HalfRgba(vec![0; aligned_count * 2]),
+        }
+    }
+
+    pub fn format(&self) -> ColorFormat {
+        match self {
+            Self::None => ColorFormat::None,
+            Self::Rgba8(_) => ColorFormat::Rgba8,
+            Self::Rgb565(_) => ColorFormat::Rgb565,
+            Self::HalfRgba(_) => ColorFormat::HalfRgba,
+        }
+    }
+
+    /// Start of the buffer, 0 when there is none.
+    pub fn ptr(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Rgba8(words) | Self::HalfRgba(words) => words.as_ptr() as u32,
+            Self::Rgb565(halves) => halves.as_ptr() as u32,
+        }
+    }
+
+    /// Element count of the JS view described on `ColorFormat`.
+    pub fn view_length(&self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Rgba8(words) => words.len() * 4,
+            Self::Rgb565(halves) => halves.len(),
+            Self::HalfRgba(words) => words.len() * 2,
+        }
+    }
+
+    /// Repacks the first `count` colours; `alpha` defaults to opaque.
+    pub fn pack(&mut self, [r, g, b]: [&[f32]; 3], alpha: Option<&[f32]>, count: usize) {
+        if let Self::None = self {
+            return;
+        }
+        for_each_batch(count, |batch| {
+            let rgb = [batch.load(r), batch.load(g), batch.load(b)];
+            let alpha = alpha.map_or(F32s::splat(1.0), |alpha| batch.load(alpha));
+            match self {
+                Self::None => {}
+                Self::Rgba8(words) => batch.store_u32(words, pack_rgba8_lanes(rgb, alpha)),
+                Self::Rgb565(halves) => batch.store_u16(halves, pack_rgb565_lanes(rgb)),
+                Self::HalfRgba(words) => {
+                    let [r, g, b] = rgb.map(|c| f32_to_f16_lanes(c).cast::<u32>());
+                    let a = f32_to_f16_lanes(alpha).cast::<u32>();
+                    batch.store_u32_pairs(words, r | g << 16, b | a << 16);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3], tolerance: f32) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() <= tolerance, "{actual:?} != {expected:?}");
+        }
+    }
+
+    fn exact_srgb_to_linear(c: f32) -> f32 {
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    #[test]
+    fn transfer_functions_match_the_exact_curve_and_invert() {
+        for i in 0..=1000 {
+            let c = i as f32 / 1000.0;
+            let linear = srgb_to_linear(c);
+            assert!((linear - exact_srgb_to_linear(c)).abs() < 1e-6, "{c}");
+            assert!((linear_to_srgb(linear) - c).abs() < 1e-5, "{c}");
+            assert_eq!(srgb_to_linear(-c), -linear);
+        }
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn oklab_matches_reference_values() {
+        assert_close(linear_srgb_to_oklab([1.0, 1.0, 1.0]), [1.0, 0.0, 0.0], 1e-4);
+        assert_close(linear_srgb_to_oklab([0.0, 0.0, 0.0]), [0.0, 0.0, 0.0], 1e-6);
+        // Reference values from Ottosson's post
+        assert_close(
+            linear_srgb_to_oklab([1.0, 0.0, 0.0]),
+            [0.627_955, 0.224_863, 0.125_846],
+            1e-4,
+        );
+        assert_close(
+            linear_srgb_to_oklab([0.0, 0.0, 1.0]),
+            [0.452_014, -0.032_457, -0.311_528],
+            1e-4,
+        );
+
+        for rgb in [[0.2, 0.5, 0.9], [1.0, 0.8, 0.4], [0.05, 0.0, 0.3]] {
+            let lch = oklab_to_oklch(linear_srgb_to_oklab(rgb));
+            assert_close(oklab_to_linear_srgb(oklch_to_oklab(lch)), rgb, 1e-4);
+        }
+    }
+
+    #[test]
+    fn hsl_round_trips_and_matches_css() {
+        assert_close(hsl_to_srgb([0.0, 1.0, 0.5]), [1.0, 0.0, 0.0], 1e-6);
+        assert_close(hsl_to_srgb([1.0 / 3.0, 1.0, 0.5]), [0.0, 1.0, 0.0], 1e-6);
+        assert_close(hsl_to_srgb([0.75, 0.5, 0.25]), [0.25, 0.125, 0.375], 1e-6);
+        assert_close(srgb_to_hsl([0.5, 0.5, 0.5]), [0.0, 0.0, 0.5], 0.0);
+
+        for rgb in [
+            [0.6, 0.8, 1.0],
+            [1.0, 0.8, 0.4],
+            [0.8, 0.6, 1.0],
+            [0.9, 0.1, 0.4],
+        ] {
+            assert_close(hsl_to_srgb(srgb_to_hsl(rgb)), rgb, 1e-6);
+        }
+    }
+
+    #[test]
+    fn oklch_mix_takes_the_short_way_round_and_keeps_hue_through_grey() {
+        let from = [0.9, 0.1, 0.4];
+        let to = [0.9, 0.3, 0.1];
+        let hue = |srgb: [f32; 3]| {
+            let lch = oklab_to_oklch(linear_srgb_to_oklab(srgb.map(srgb_to_linear)));
+            lch[2]
+        };
+        let (start, end, middle) = (
+            hue(from),
+            hue(to),
+            hue(mix(from, to, 0.5, InterpolationSpace::Oklch)),
+        );
+        assert!(middle > start.min(end) && middle < start.max(end));
+
+        // White has no hue, so a fade from blue to white stays blue
+        let blue = [0.3, 0.5, 0.8];
+        let faded = mix(blue, [1.0, 1.0, 1.0], 0.5, InterpolationSpace::Oklch);
+        assert!((hue(faded) - hue(blue)).abs() < 1e-2);
+
+        assert_close(mix(from, to, 0.0, InterpolationSpace::Oklab), from, 1e-4);
+        assert_close(mix(from, to, 1.0, InterpolationSpace::Oklch), to, 1e-4);
+    }
+
+    #[test]
+    fn gradient_hits_stops_and_clamps() {
+        let flat = [1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.5, 0.0, 1.0, 0.0];
+        for space in [
+            InterpolationSpace::Srgb,
+            InterpolationSpace::LinearSrgb,
+            InterpolationSpace::Oklab,
+            InterpolationSpace::Oklch,
+        ] {
+            let gradient = Gradient::from_flat(&flat, space).unwrap();
+            assert_close(gradient.sample(-1.0), [1.0, 0.0, 0.0], 1e-4);
+            assert_close(gradient.sample(0.5), [0.0, 1.0, 0.0], 1e-4);
+            assert_close(gradient.sample(2.0), [0.0, 0.0, 1.0], 1e-4);
+
+            let t = f32x16::from_array(std::array::from_fn(|i| i as f32 / 15.0));
+            let lanes = gradient.sample_x16(t);
+            for i in 0..16 {
+                let scalar = gradient.sample(t[i]);
+                assert_eq!([lanes[0][i], lanes[1][i], lanes[2][i]], scalar);
+            }
+        }
+
+        let srgb = Gradient::from_flat(&flat, InterpolationSpace::Srgb).unwrap();
+        assert_close(srgb.sample(0.25), [0.5, 0.5, 0.0], 1e-6);
+        assert!(Gradient::from_flat(&flat[..3], InterpolationSpace::Srgb).is_none());
+    }
+
+    #[test]
+    fn convert_buffers_round_trips() {
+        let original: Vec<f32> = (0..37).map(|i| i as f32 / 36.0).collect();
+        let (mut r, mut g, mut b) = (original.clone(), original.clone(), original.clone());
+        convert_buffers(
+            ColorSpace::Srgb,
+            ColorSpace::LinearSrgb,
+            [&mut r, &mut g, &mut b],
+            37,
+        );
+        assert!((r[18] - srgb_to_linear(0.5)).abs() < 1e-6);
+        convert_buffers(
+            ColorSpace::LinearSrgb,
+            ColorSpace::Srgb,
+            [&mut r, &mut g, &mut b],
+            37,
+        );
+        for (a, e) in b.iter().zip(&original) {
+            assert!((a - e).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn rgba8_and_rgb565_pack_in_byte_order() {
+        assert_eq!(pack_rgba8([1.0, 0.0, 0.0], 1.0), 0xff00_00ff);
+        assert_eq!(pack_rgba8([0.0, 0.5, 1.0], 0.0), 0x00ff_8000);
+        assert_eq!(pack_rgba8([-1.0, 2.0, f32::NAN], 1.0), 0xff00_ff00);
+        assert_close(
+            unpack_rgba8(pack_rgba8([0.2, 0.4, 0.6], 1.0))[..3]
+                .try_into()
+                .unwrap(),
+            [0.2, 0.4, 0.6],
+            0.5 / 255.0,
+        );
+
+        assert_eq!(pack_rgb565([1.0, 0.0, 0.0]), 0xf800);
+        assert_eq!(pack_rgb565([0.0, 1.0, 0.0]), 0x07e0);
+        assert_eq!(pack_rgb565([1.0, 1.0, 1.0]), 0xffff);
+        assert_close(
+            unpack_rgb565(pack_rgb565([0.2, 0.4, 0.6])),
+            [0.2, 0.4, 0.6],
+            0.5 / 31.0,
+        );
+    }
+
+    #[test]
+    fn half_floats_round_to_nearest_even() {
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(65520.0), 0x7c00);
+        assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+        assert_eq!(f32_to_f16(f32::NAN), 0x7e00);
+        assert_eq!(f32_to_f16(5.960_464_5e-8), 0x0001);
+        // Ties go to the even mantissa
+        assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3c00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 / 2048.0), 0x3c02);
+
+        for i in 0..=10_000 {
+            let x = i as f32 / 10_000.0 * 4.0 - 2.0;
+            let half = f32_to_f16(x);
+            let ulp = (f16_to_f32(half + 1) - f16_to_f32(half)).abs();
+            assert!((f16_to_f32(half) - x).abs() <= ulp * 0.5, "{x}");
+        }
+    }
+
+    #[test]
+    fn packed_colors_follow_the_channels() {
+        let count = 37;
+        let r: Vec<f32> = (0..48).map(|i| i as f32 / 47.0).collect();
+        let g = vec![0.5; 48];
+        let b = vec![0.25; 48];
+        let alpha = vec![0.75; 48];
+
+        let mut packed = PackedColors::new(ColorFormat::HalfRgba, 48);
+        packed.pack([&r, &g, &b], Some(&alpha), count);
+        let PackedColors::HalfRgba(words) = &packed else {
+            unreachable!()
+        };
+        for i in 0..count {
+            assert_eq!(words[2 * i], f32_to_f16(r[i]) as u32 | 0x3800 << 16);
+            assert_eq!(words[2 * i + 1], 0x3400 | (f32_to_f16(0.75) as u32) << 16);
+        }
+        assert!(words[2 * count..].iter().all(|&word| word == 0));
+        assert_eq!(packed.view_length(), 48 * 4);
+
+        let mut packed = PackedColors::new(ColorFormat::Rgba8, 48);
+        packed.pack([&r, &g, &b], None, count);
+        let PackedColors::Rgba8(words) = &packed else {
+            unreachable!()
+        };
+        assert_eq!(words[36], pack_rgba8([r[36], 0.5, 0.25], 1.0));
+        assert_eq!(words[37], 0);
+    }
+}
@@ -0,0 +1,159 @@
+// Euclidean distance transform over binary masks (Felzenszwalb & Huttenlocher)
+
+const FAR: f32 = 1e20;
+
+// Distance from every inside pixel to the nearest outside pixel; 0 for outside pixels.
+pub(crate) fn inside_distance(inside: &[bool], width: usize, height: usize) -> Vec<f32> {
+    let mut grid: Vec<f32> = inside.iter().map(|&i| if i { FAR } else { 0.0 }).collect();
+
+    let longest = width.max(height);
+    let mut f = vec![0.0f32; longest];
+    let mut d = vec![0.0f32; longest];
+    let mut v = vec![0usize; longest];
+    let mut z = vec![0.0f32; longest + 1];
+
+    // Columns
+    for x in 0..width {
+        for y in 0..height {
+            f[y] = grid[y * width + x];
+        }
+        transform_1d(&f[..height], &mut d[..height], &mut v, &mut z);
+        for y in 0..height {
+            grid[y * width + x] = d[y];
+        }
+    }
+
+    // Rows
+    for y in 0..height {
+        let row = &mut grid[y * width..(y + 1) * width];
+        f[..width].copy_from_slice(row);
+        transform_1d(&f[..width], &mut d[..width], &mut v, &mut z);
+        row.copy_from_slice(&d[..width]);
+    }
+
+    for value in grid.iter_mut() {
+        *value = value.sqrt();
+    }
+    grid
+}
+
+// Squared distance transform of a sampled function along one axis
+fn transform_1d(f: &[f32], d: &mut [f32], v: &mut [usize], z: &mut [f32]) {
+    let n = f.len();
+    if n == 0 {
+        return;
+    }
+
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = -FAR;
+    z[1] = FAR;
+
+    for q in 1..n {
+        let qf = q as f32;
+        let mut s;
+        loop {
+            let p = v[k];
+            let pf = p as f32;
+            s = ((f[q] + qf * qf) - (f[p] + pf * pf)) / (2.0 * qf - 2.0 * pf);
+            if s <= z[k] && k > 0 {
+                k -= 1;
+            } else {
+                break;
+            }
+        }
+        if s <= z[k] {
+            // k == 0 and the new parabola dominates the first one
+            v[0] = q;
+            z[0] = -FAR;
+            z[1] = FAR;
+            continue;
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = FAR;
+    }
+
+    k = 0;
+    for (q, out) in d.iter_mut().enumerate() {
+        let qf = q as f32;
+        while z[k + 1] < qf {
+            k += 1;
+        }
+        let p = v[k] as f32;
+        *out = (qf - p) * (qf - p) + f[v[k]];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::random_f32;
+
+    fn brute_force(inside: &[bool], width: usize, height: usize) -> Vec<f32> {
+        (0..width * height)
+            .map(|i| {
+                if !inside[i] {
+                    return 0.0;
+                }
+                let (x, y) = ((i % width) as f32, (i / width) as f32);
+                (0..width * height)
+                    .filter(|&j| !inside[j])
+                    .map(|j| {
+                        let (ox, oy) = ((j % width) as f32, (j / width) as f32);
+                        ((x - ox).powi(2) + (y - oy).powi(2)).sqrt()
+                    })
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .collect()
+    }
+
+    fn assert_matches_brute_force(inside: &[bool], width: usize, height: usize) {
+        let expected = brute_force(inside, width, height);
+        let actual = inside_distance(inside, width, height);
+        for (i, (&a, &e)) in actual.iter().zip(&expected).enumerate() {
+            if e.is_infinite() {
+                // No outside pixel at all: the sentinel stands in for infinity
+                assert!(a >= FAR.sqrt(), "pixel {i}: {a}");
+                continue;
+            }
+            assert!((a - e).abs() < 1e-4, "pixel {i}: {a} vs {e}");
+        }
+    }
+
+    #[test]
+    fn matches_brute_force_on_random_masks() {
+        for (seed, (width, height)) in [(9, 7), (1, 12), (12, 1), (16, 16)].iter().enumerate() {
+            for density in [0.3, 0.7, 0.95] {
+                let inside: Vec<bool> = (0..width * height)
+                    .map(|i| random_f32(seed as u32, i as u32) < density)
+                    .collect();
+                assert_matches_brute_force(&inside, *width, *height);
+            }
+        }
+    }
+
+    #[test]
+    fn single_hole_in_a_solid_mask() {
+        let (width, height) = (11, 5);
+        let mut inside = vec![true; width * height];
+        inside[2 * width + 10] = false;
+        assert_matches_brute_force(&inside, width, height);
+
+        let distance = inside_distance(&inside, width, height);
+        assert!((distance[0] - (100.0f32 + 4.0).sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn sentinel_dominates_without_outside_pixels() {
+        // Nothing to measure against: every pixel stays at the sentinel distance
+        let distance = inside_distance(&[true; 6 * 4], 6, 4);
+        assert!(distance.iter().all(|&d| d >= FAR.sqrt()));
+
+        let distance = inside_distance(&[false; 6 * 4], 6, 4);
+        assert!(distance.iter().all(|&d| d == 0.0));
+
+        assert!(inside_distance(&[], 0, 0).is_empty());
+    }
+}
@@ -0,0 +1,451 @@
+// Particle storage shared by every effect.
+//
+// An effect declares its per-particle attributes once with
+// `particle_attributes!`. The macro generates a structure-of-arrays store whose
+// columns are padded to whole SIMD batches, can grow and shrink, and describe
+// themselves to JS through `ParticleBuffers`. Per-frame work lives in
+// `stages`: a `Pipeline` runs an effect's update stages in order.
+
+use std::ops::{Deref, DerefMut, Range};
+
+use wasm_bindgen::prelude::*;
+
+pub mod stages;
+
+/// Element type of an attribute column, as JS should view it.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeKind {
+    /// Float32Array
+    F32 = 0,
+    /// Uint8Array
+    U8 = 1,
+    /// One bit per particle, 64 per u64 word (BigUint64Array)
+    Flags = 2,
+}
+
+/// Name and element type of one column, in declaration order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: &'static str,
+    pub kind: AttributeKind,
+}
+
+/// Storage for one attribute across all particles.
+pub trait Column {
+    const KIND: AttributeKind;
+    type Value: Copy;
+
+    fn filled(len: usize, value: Self::Value) -> Self;
+    /// Resizes to hold `len` particles, filling any new slots with `value`.
+    fn resize(&mut self, len: usize, value: Self::Value);
+    fn fill(&mut self, range: Range<usize>, value: Self::Value);
+    fn swap(&mut self, a: usize, b: usize);
+    fn ptr(&self) -> u32;
+    /// Element count of the JS view described on `AttributeKind`.
+    fn view_length(&self) -> usize;
+}
+
+impl Column for Vec<f32> {
+    const KIND: AttributeKind = AttributeKind::F32;
+    type Value = f32;
+
+    fn filled(len: usize, value: f32) -> Self {
+        vec![value; len]
+    }
+
+    fn resize(&mut self, len: usize, value: f32) {
+        Vec::resize(self, len, value);
+    }
+
+    fn fill(&mut self, range: Range<usize>, value: f32) {
+        self[range].fill(value);
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        <[f32]>::swap(self, a, b);
+    }
+
+    fn ptr(&self) -> u32 {
+        self.as_ptr() as u32
+    }
+
+    fn view_length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Column for Vec<u8> {
+    const KIND: AttributeKind = AttributeKind::U8;
+    type Value = u8;
+
+    fn filled(len: usize, value: u8) -> Self {
+        vec![value; len]
+    }
+
+    fn resize(&mut self, len: usize, value: u8) {
+        Vec::resize(self, len, value);
+    }
+
+    fn fill(&mut self, range: Range<usize>, value: u8) {
+        self[range].fill(value);
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        <[u8]>::swap(self, a, b);
+    }
+
+    fn ptr(&self) -> u32 {
+        self.as_ptr() as u32
+    }
+
+    fn view_length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Bitpacked boolean column: particle `i` is bit `i % 64` of word `i / 64`.
+#[derive(Clone, Debug, Default)]
+pub struct Flags(Vec<u64>);
+
+impl Flags {
+    pub fn get(&self, index: usize) -> bool {
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        let bit = 1 << (index % 64);
+        if value {
+            self.0[index / 64] |= bit;
+        } else {
+            self.0[index / 64] &= !bit;
+        }
+    }
+}
+
+impl Deref for Flags {
+    type Target = [u64];
+
+    fn deref(&self) -> &[u64] {
+        &self.0
+    }
+}
+
+impl DerefMut for Flags {
+    fn deref_mut(&mut self) -> &mut [u64] {
+        &mut self.0
+    }
+}
+
+impl Column for Flags {
+    const KIND: AttributeKind = AttributeKind::Flags;
+    type Value = bool;
+
+    fn filled(len: usize, value: bool) -> Self {
+        Self(vec![if value { u64::MAX } else { 0 }; len.div_ceil(64)])
+    }
+
+    fn resize(&mut self, len: usize, value: bool) {
+        self.0
+            .resize(len.div_ceil(64), if value { u64::MAX } else { 0 });
+    }
+
+    fn fill(&mut self, range: Range<usize>, value: bool) {
+        for index in range {
+            self.set(index, value);
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        let (first, second) = (self.get(a), self.get(b));
+        self.set(a, second);
+        self.set(b, first);
+    }
+
+    fn ptr(&self) -> u32 {
+        self.0.as_ptr() as u32
+    }
+
+    fn view_length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// A structure-of-arrays particle store, implemented by `particle_attributes!`.
+///
+/// Columns always hold `padded_len(count)` slots, so kernels can run whole
+/// batches; slots past `count` keep their attribute defaults. Growing may
+/// reallocate, so JS must fetch buffers again after `resize`, `push` or
+/// `retain`.
+pub trait ParticleStorage: Sized {
+    const SCHEMA: &'static [Attribute];
+
+    /// Store of `count` particles, every attribute at its default.
+    fn with_count(count: usize) -> Self;
+    fn count(&self) -> usize;
+    /// Grows or shrinks to `count` particles. Survivors keep their values;
+    /// new and vacated slots return to the defaults.
+    fn resize(&mut self, count: usize);
+    fn swap(&mut self, a: usize, b: usize);
+    /// Pointer and view length of every column, in `SCHEMA` order.
+    fn views(&self) -> Vec<(u32, usize)>;
+
+    fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// Appends a particle at the defaults and returns its index.
+    fn push(&mut self) -> usize {
+        let index = self.count();
+        self.resize(index + 1);
+        index
+    }
+
+    /// Removes particle `index` by moving the last one into its slot.
+    fn swap_remove(&mut self, index: usize) {
+        let last = self.count() - 1;
+        self.swap(index, last);
+        self.resize(last);
+    }
+
+    /// Keeps the particles for which `keep` returns true. Order is not
+    /// preserved: removed slots are filled from the end.
+    fn retain(&mut self, mut keep: impl FnMut(&Self, usize) -> bool) {
+        let mut index = 0;
+        while index < self.count() {
+            if keep(self, index) {
+                index += 1;
+            } else {
+                self.swap_remove(index);
+            }
+        }
+    }
+
+    fn buffers(&self) -> ParticleBuffers {
+        let views = Self::SCHEMA
+            .iter()
+            .zip(self.views())
+            .map(|(attribute, (ptr, length))| BufferView {
+                attribute: *attribute,
+                ptr,
+                length,
+            })
+            .collect();
+        ParticleBuffers {
+            count: self.count(),
+            views,
+        }
+    }
+}
+
+/// Declares a particle store: one column per field, each with its default.
+///
+/// ```ignore
+/// particle_attributes! {
+///     struct Sparks {
+///         positions_x: Vec<f32> = 0.0,
+///         phases: Vec<u8> = 0,
+///         alive: Flags = true,
+///     }
+/// }
+/// ```
+macro_rules! particle_attributes {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident: $column:ty = $default:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            count: usize,
+            $(
+                $(#[$field_meta])*
+                $field: $column,
+            )*
+        }
+
+        impl $crate::engine::ParticleStorage for $name {
+            const SCHEMA: &'static [$crate::engine::Attribute] = &[$(
+                $crate::engine::Attribute {
+                    name: stringify!($field),
+                    kind: <$column as $crate::engine::Column>::KIND,
+                },
+            )*];
+
+            fn with_count(count: usize) -> Self {
+                let padded = $crate::batch::padded_len(count);
+                Self {
+                    count,
+                    $($field: <$column as $crate::engine::Column>::filled(padded, $default),)*
+                }
+            }
+
+            fn count(&self) -> usize {
+                self.count
+            }
+
+            fn resize(&mut self, count: usize) {
+                let padded = $crate::batch::padded_len(count);
+                let reset = self.count.min(count)..padded;
+                $(
+                    $crate::engine::Column::resize(&mut self.$field, padded, $default);
+                    $crate::engine::Column::fill(&mut self.$field, reset.clone(), $default);
+                )*
+                self.count = count;
+            }
+
+            fn swap(&mut self, a: usize, b: usize) {
+                $($crate::engine::Column::swap(&mut self.$field, a, b);)*
+            }
+
+            fn views(&self) -> Vec<(u32, usize)> {
+                vec![$((
+                    $crate::engine::Column::ptr(&self.$field),
+                    $crate::engine::Column::view_length(&self.$field),
+                )),*]
+            }
+        }
+    };
+}
+
+pub(crate) use particle_attributes;
+
+#[derive(Clone, Copy, Debug)]
+struct BufferView {
+    attribute: Attribute,
+    ptr: u32,
+    length: usize,
+}
+
+/// Every column of a particle store, for building typed-array views in JS
+/// without a hand-written pointer struct. Invalidated when the store grows.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ParticleBuffers {
+    count: usize,
+    views: Vec<BufferView>,
+}
+
+#[wasm_bindgen]
+impl ParticleBuffers {
+    /// Live particles; columns are padded beyond this.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn attribute_count(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn name(&self, index: usize) -> Option<String> {
+        self.views
+            .get(index)
+            .map(|view| view.attribute.name.to_string())
+    }
+
+    pub fn kind(&self, index: usize) -> Option<AttributeKind> {
+        self.views.get(index).map(|view| view.attribute.kind)
+    }
+
+    /// Start of the column, 0 when `index` is out of range.
+    pub fn ptr(&self, index: usize) -> u32 {
+        self.views.get(index).map_or(0, |view| view.ptr)
+    }
+
+    /// Element count of the column's view, 0 when `index` is out of range.
+    pub fn length(&self, index: usize) -> usize {
+        self.views.get(index).map_or(0, |view| view.length)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.views
+            .iter()
+            .position(|view| view.attribute.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    particle_attributes! {
+        struct Sparks {
+            x: Vec<f32> = 0.0,
+            heat: Vec<f32> = 1.0,
+            phase: Vec<u8> = 7,
+            alive: Flags = true,
+        }
+    }
+
+    #[test]
+    fn schema_follows_the_declaration() {
+        let names: Vec<_> = Sparks::SCHEMA.iter().map(|a| a.name).collect();
+        assert_eq!(names, ["x", "heat", "phase", "alive"]);
+        assert_eq!(Sparks::SCHEMA[2].kind, AttributeKind::U8);
+        assert_eq!(Sparks::SCHEMA[3].kind, AttributeKind::Flags);
+
+        let sparks = Sparks::with_count(20);
+        assert_eq!(sparks.x.len(), 32);
+        assert!(sparks.heat.iter().all(|&h| h == 1.0));
+        assert!(sparks.phase.iter().all(|&p| p == 7));
+        assert!((0..32).all(|i| sparks.alive.get(i)));
+
+        let buffers = sparks.buffers();
+        assert_eq!(buffers.count(), 20);
+        assert_eq!(buffers.attribute_count(), 4);
+        let heat = buffers.index_of("heat").unwrap();
+        assert_eq!(buffers.ptr(heat), sparks.heat.as_ptr() as u32);
+        assert_eq!(buffers.length(heat), 32);
+        assert_eq!(buffers.length(buffers.index_of("alive").unwrap()), 1);
+        assert_eq!(buffers.ptr(9), 0);
+    }
+
+    #[test]
+    fn resize_keeps_survivors_and_resets_the_rest() {
+        let mut sparks = Sparks::with_count(20);
+        for i in 0..20 {
+            sparks.x[i] = i as f32;
+            sparks.phase[i] = 1;
+            sparks.alive.set(i, false);
+        }
+
+        sparks.resize(5);
+        assert_eq!(sparks.x.len(), 16);
+        assert_eq!(&sparks.x[..5], &[0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert!(sparks.x[5..].iter().all(|&x| x == 0.0));
+        assert!(sparks.phase[5..].iter().all(|&p| p == 7));
+        assert!(!sparks.alive.get(4) && sparks.alive.get(5));
+
+        sparks.resize(40);
+        assert_eq!(sparks.x.len(), 48);
+        assert_eq!(sparks.x[4], 4.0);
+        assert!(sparks.heat[5..].iter().all(|&h| h == 1.0));
+        assert!((5..48).all(|i| sparks.alive.get(i)));
+    }
+
+    #[test]
+    fn retain_fills_gaps_from_the_end() {
+        let mut sparks = Sparks::with_count(6);
+        for i in 0..6 {
+            sparks.x[i] = i as f32;
+            sparks.alive.set(i, i % 2 == 0);
+        }
+
+        sparks.retain(|sparks, i| sparks.alive.get(i));
+        assert_eq!(sparks.count(), 3);
+        let mut kept = sparks.x[..3].to_vec();
+        kept.sort_by(f32::total_cmp);
+        assert_eq!(kept, [0.0, 2.0, 4.0]);
+        assert!(sparks.x[3..].iter().all(|&x| x == 0.0));
+
+        assert_eq!(sparks.push(), 3);
+        assert_eq!(sparks.heat[3], 1.0);
+    }
+}
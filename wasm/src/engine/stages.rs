@@ -0,0 +1,157 @@
+// Per-frame update stages.
+//
+// A stage is any `FnMut(&mut S, Frame)` over an effect's state, or a type
+// implementing `Stage`. Stages run their own kernels on the effect's columns.
+
+/// Timing shared by every stage of one frame, in seconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Frame {
+    pub time: f32,
+    pub delta_time: f32,
+}
+
+/// One step of an effect's update, e.g. motion, culling or colour packing.
+pub trait Stage<S> {
+    fn run(&mut self, state: &mut S, frame: Frame);
+}
+
+impl<S, F: FnMut(&mut S, Frame)> Stage<S> for F {
+    fn run(&mut self, state: &mut S, frame: Frame) {
+        self(state, frame)
+    }
+}
+
+/// Stages run in insertion order.
+pub struct Pipeline<S> {
+    stages: Vec<Box<dyn Stage<S>>>,
+}
+
+impl<S> Pipeline<S> {
+    pub const fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    pub fn with(mut self, stage: impl Stage<S> + 'static) -> Self {
+        self.push(stage);
+        self
+    }
+
+    pub fn push(&mut self, stage: impl Stage<S> + 'static) {
+        self.stages.push(Box::new(stage));
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    pub fn run(&mut self, state: &mut S, frame: Frame) {
+        for stage in &mut self.stages {
+            stage.run(state, frame);
+        }
+    }
+}
+
+impl<S> Default for Pipeline<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Effect state that owns the pipeline updating it.
+pub trait Simulation: Sized {
+    fn pipeline_mut(&mut self) -> &mut Pipeline<Self>;
+
+    /// Runs every stage once. The pipeline is moved out while it runs, so
+    /// stages see the state without it.
+    fn step(&mut self, frame: Frame) {
+        let mut pipeline = std::mem::take(self.pipeline_mut());
+        pipeline.run(self, frame);
+        *self.pipeline_mut() = pipeline;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{particle_attributes, Flags, ParticleStorage};
+
+    particle_attributes! {
+        struct Drops {
+            y: Vec<f32> = 10.0,
+            live: Flags = true,
+        }
+    }
+
+    struct Rain {
+        drops: Drops,
+        log: Vec<&'static str>,
+        pipeline: Pipeline<Rain>,
+    }
+
+    impl Simulation for Rain {
+        fn pipeline_mut(&mut self) -> &mut Pipeline<Self> {
+            &mut self.pipeline
+        }
+    }
+
+    fn emit(rain: &mut Rain, _: Frame) {
+        rain.drops.push();
+        rain.log.push("emit");
+    }
+
+    fn fall(rain: &mut Rain, frame: Frame) {
+        let count = rain.drops.count();
+        for y in &mut rain.drops.y[..count] {
+            *y -= frame.delta_time * 10.0;
+        }
+        rain.log.push("fall");
+    }
+
+    // Struct stages keep their own state between frames
+    struct Expire {
+        frames: usize,
+    }
+
+    impl Stage<Rain> for Expire {
+        fn run(&mut self, rain: &mut Rain, _: Frame) {
+            self.frames += 1;
+            rain.drops.retain(|drops, i| drops.y[i] > 5.5);
+            rain.log.push("expire");
+        }
+    }
+
+    #[test]
+    fn pipeline_runs_stages_in_order() {
+        let mut rain = Rain {
+            drops: Drops::with_count(0),
+            log: Vec::new(),
+            pipeline: Pipeline::new()
+                .with(emit)
+                .with(fall)
+                .with(Expire { frames: 0 }),
+        };
+        let frame = Frame {
+            time: 0.0,
+            delta_time: 0.1,
+        };
+
+        rain.step(frame);
+        assert_eq!(rain.log, ["emit", "fall", "expire"]);
+        assert_eq!(rain.drops.count(), 1);
+        assert_eq!(rain.drops.y[0], 9.0);
+
+        for _ in 0..9 {
+            rain.step(frame);
+        }
+        // One drop per frame; a drop expires on its fifth frame
+        assert_eq!(rain.drops.count(), 4);
+        assert_eq!(rain.log.len(), 30);
+        // The pipeline is back in place after every step
+        assert_eq!(rain.pipeline.len(), 3);
+        assert!(!rain.pipeline.is_empty());
+    }
+}
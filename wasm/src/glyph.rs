@@ -0,0 +1,244 @@
+// Text layout and glyph rasterisation from TTF/OTF font data
+
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+use crate::vector::{fit_raster_scale, rasterize_coverage, Contour, PathBuilder};
+
+// Curve flattening tolerance in pixels
+const FLATTEN_TOLERANCE: f32 = 0.2;
+
+// Empty border around the laid-out text, in pixels
+const RASTER_PADDING: f32 = 2.0;
+
+pub(crate) struct GlyphRaster {
+    pub width: usize,
+    pub height: usize,
+    // One coverage byte per pixel, row-major
+    pub coverage: Vec<u8>,
+}
+
+// Feeds font outlines into a PathBuilder, mapping font units (y up) to pixels (y down)
+struct TransformedOutline<'a> {
+    builder: &'a mut PathBuilder,
+    scale: f32,
+    origin_x: f32,
+    baseline_y: f32,
+}
+
+impl TransformedOutline<'_> {
+    fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.origin_x + x * self.scale,
+            self.baseline_y - y * self.scale,
+        )
+    }
+}
+
+impl OutlineBuilder for TransformedOutline<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x, y) = self.map(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x2, y2) = self.map(x2, y2);
+        let (x, y) = self.map(x, y);
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+fn kerning(face: &Face, left: GlyphId, right: GlyphId) -> f32 {
+    face.tables()
+        .kern
+        .and_then(|kern| {
+            kern.subtables
+                .into_iter()
+                .filter(|subtable| subtable.horizontal && !subtable.variable)
+                .find_map(|subtable| subtable.glyphs_kerning(left, right))
+        })
+        .map(f32::from)
+        .unwrap_or(0.0)
+}
+
+// Horizontal advance of one line in font units, including kerning
+fn line_advance(face: &Face, line: &str) -> f32 {
+    let mut advance = 0.0;
+    let mut previous: Option<GlyphId> = None;
+    for ch in line.chars() {
+        let glyph = face.glyph_index(ch).unwrap_or(GlyphId(0));
+        if let Some(prev) = previous {
+            advance += kerning(face, prev, glyph);
+        }
+        advance += face.glyph_hor_advance(glyph).unwrap_or(0) as f32;
+        previous = Some(glyph);
+    }
+    advance
+}
+
+// Lay out `text` (lines split on '\n', each centred) and return its outlines in
+// pixel space along with the bounding image size. Text that would not fit in
+// MAX_RASTER_SIDE is laid out at a smaller size; None for a bad font or size.
+pub(crate) fn layout_text_outlines(
+    font_data: &[u8],
+    text: &str,
+    font_size: f32,
+) -> Option<(Vec<Contour>, usize, usize)> {
+    if !(font_size.is_finite() && font_size > 0.0) {
+        return None;
+    }
+    let face = Face::parse(font_data, 0).ok()?;
+    let lines: Vec<&str> = text.lines().collect();
+    let advances: Vec<f32> = lines.iter().map(|line| line_advance(&face, line)).collect();
+    let max_advance = advances.iter().copied().fold(0.0f32, f32::max);
+    let line_units = face.ascender() as f32 - face.descender() as f32 + face.line_gap() as f32;
+
+    let mut scale = font_size / face.units_per_em() as f32;
+    scale *= fit_raster_scale(
+        max_advance * scale,
+        line_units * lines.len() as f32 * scale,
+        RASTER_PADDING,
+    );
+    let ascender = face.ascender() as f32 * scale;
+    let line_height = line_units * scale;
+    let widths: Vec<f32> = advances.iter().map(|advance| advance * scale).collect();
+    let max_width = max_advance * scale;
+
+    let width = (max_width + RASTER_PADDING * 2.0).ceil() as usize;
+    let height = (line_height * lines.len() as f32 + RASTER_PADDING * 2.0).ceil() as usize;
+
+    let mut builder = PathBuilder::new(FLATTEN_TOLERANCE);
+    for (row, (line, line_width)) in lines.iter().zip(&widths).enumerate() {
+        let mut pen_x = RASTER_PADDING + (max_width - line_width) / 2.0;
+        let baseline_y = RASTER_PADDING + ascender + line_height * row as f32;
+        let mut previous: Option<GlyphId> = None;
+
+        for ch in line.chars() {
+            let glyph = face.glyph_index(ch).unwrap_or(GlyphId(0));
+            if let Some(prev) = previous {
+                pen_x += kerning(&face, prev, glyph) * scale;
+            }
+
+            let mut outline = TransformedOutline {
+                builder: &mut builder,
+                scale,
+                origin_x: pen_x,
+                baseline_y,
+            };
+            face.outline_glyph(glyph, &mut outline);
+
+            pen_x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+            previous = Some(glyph);
+        }
+    }
+
+    Some((builder.finish(), width, height))
+}
+
+pub(crate) fn rasterize_text(font_data: &[u8], text: &str, font_size: f32) -> Option<GlyphRaster> {
+    let (contours, width, height) = layout_text_outlines(font_data, text, font_size)?;
+    let coverage = rasterize_coverage(&contours, width, height);
+    Some(GlyphRaster {
+        width,
+        height,
+        coverage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::{contour_bounds, MAX_RASTER_SIDE};
+
+    // 1000 units per em: 'A' is a 400 x 700 box advancing 600, 'V' a triangle
+    // advancing 600, with A-V kerned by -200. See testdata/make_test_font.py.
+    const TEST_FONT: &[u8] = include_bytes!("../testdata/test_font.ttf");
+
+    fn bounds(contour: &Contour) -> (f32, f32, f32, f32) {
+        contour_bounds(std::slice::from_ref(contour)).unwrap()
+    }
+
+    #[test]
+    fn kerning_tightens_the_advance() {
+        // 100px: 0.1 px per unit, baseline at padding + ascender = 82
+        let (contours, width, height) = layout_text_outlines(TEST_FONT, "AV", 100.0).unwrap();
+        assert_eq!((width, height), (104, 104));
+        assert_eq!(contours.len(), 2);
+        assert_eq!(bounds(&contours[0]), (12.0, 12.0, 52.0, 82.0));
+        // Pen at 2 + 60 - 20
+        assert_eq!(bounds(&contours[1]), (42.0, 12.0, 102.0, 82.0));
+
+        // No pair for V-A, so the full advances apply
+        let (contours, width, _) = layout_text_outlines(TEST_FONT, "VA", 100.0).unwrap();
+        assert_eq!(width, 124);
+        assert_eq!(bounds(&contours[1]).0, 72.0);
+    }
+
+    #[test]
+    fn lines_are_centred_and_stacked() {
+        let (contours, width, height) = layout_text_outlines(TEST_FONT, "AV\nA", 100.0).unwrap();
+        assert_eq!((width, height), (104, 204));
+        assert_eq!(contours.len(), 3);
+        // The 60px line sits (100 - 60) / 2 in, one 100px line height down
+        assert_eq!(bounds(&contours[2]), (32.0, 112.0, 72.0, 182.0));
+    }
+
+    #[test]
+    fn rasterises_glyph_coverage() {
+        let raster = rasterize_text(TEST_FONT, "A", 100.0).unwrap();
+        assert_eq!((raster.width, raster.height), (64, 104));
+        let at = |x: usize, y: usize| raster.coverage[y * raster.width + x];
+        assert_eq!(at(30, 50), 255);
+        assert_eq!(at(12, 12), 255);
+        assert_eq!(at(51, 81), 255);
+        assert_eq!(at(11, 50), 0);
+        assert_eq!(at(52, 50), 0);
+        assert_eq!(at(30, 82), 0);
+
+        let total: usize = raster.coverage.iter().map(|&c| c as usize).sum();
+        assert_eq!(total, 40 * 70 * 255);
+    }
+
+    #[test]
+    fn unmapped_characters_advance_without_ink() {
+        // '?' falls back to .notdef, which has no outline and advances 500
+        let (contours, width, _) = layout_text_outlines(TEST_FONT, "?A", 100.0).unwrap();
+        assert_eq!(contours.len(), 1);
+        assert_eq!(width, 114);
+        assert_eq!(bounds(&contours[0]).0, 62.0);
+    }
+
+    #[test]
+    fn huge_sizes_fit_the_raster_limit() {
+        let (contours, width, height) = layout_text_outlines(TEST_FONT, "AV\nA", 1e9).unwrap();
+        assert_eq!(height, MAX_RASTER_SIDE as usize);
+        assert!(width < height);
+        let (_, _, max_x, max_y) = contour_bounds(&contours).unwrap();
+        assert!(max_x <= width as f32 && max_y <= height as f32);
+
+        for size in [0.0, -10.0, f32::INFINITY, f32::NAN] {
+            assert!(layout_text_outlines(TEST_FONT, "A", size).is_none());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_font_data() {
+        assert!(layout_text_outlines(&[0; 16], "A", 100.0).is_none());
+        assert!(rasterize_text(&TEST_FONT[..40], "A", 100.0).is_none());
+    }
+}
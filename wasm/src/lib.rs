@@ -5,10 +5,24 @@ use wasm_bindgen::prelude::*;
 mod math;
 mod star_field;
 mod scatter_text;
+mod glyph;
+mod distance_field;
+mod vector;
+mod rng;
+mod batch;
+mod noise;
+mod tween;
+mod color;
+mod engine;
 
 pub use math::*;
 pub use star_field::*;
 pub use scatter_text::*;
+pub use rng::*;
+pub use noise::*;
+pub use tween::*;
+pub use color::*;
+pub use engine::*;
 
 #[wasm_bindgen]
 extern "C" {
@@ -1,10 +1,16 @@
 use std::cell::RefCell;
 use std::f32::consts::PI;
-use std::simd::{f32x16, StdFloat};
+use std::simd::cmp::{SimdPartialEq, SimdPartialOrd};
+use std::simd::{f32x16, num::SimdFloat, num::SimdInt, num::SimdUint, Select, Simd, StdFloat};
+
+pub mod linalg;
 
 // Sin table size
 const SIN_TABLE_SIZE: usize = 1024;
 
+// Table entries per radian
+const SIN_TABLE_SCALE: f32 = SIN_TABLE_SIZE as f32 / (PI * 2.0);
+
 // Thread-local sin table
 thread_local! {
     static SIN_TABLE: RefCell<Option<Vec<f32>>> = const { RefCell::new(None) };
@@ -15,8 +21,9 @@ fn ensure_sin_table_initialized() {
     SIN_TABLE.with(|table_cell| {
         let mut table_ref = table_cell.borrow_mut();
         if table_ref.is_none() {
-            let mut table = Vec::with_capacity(SIN_TABLE_SIZE);
-            for i in 0..SIN_TABLE_SIZE {
+            // One extra entry so interpolation never wraps
+            let mut table = Vec::with_capacity(SIN_TABLE_SIZE + 1);
+            for i in 0..=SIN_TABLE_SIZE {
                 let angle = (i as f32 / SIN_TABLE_SIZE as f32) * PI * 2.0;
                 table.push(angle.sin());
             }
@@ -25,97 +32,50 @@ fn ensure_sin_table_initialized() {
     });
 }
 
-// Get sin value by index
-fn get_sin_value(index: usize) -> f32 {
-    ensure_sin_table_initialized();
-    SIN_TABLE.with(|table_cell| {
-        table_cell
-            .borrow()
-            .as_ref()
-            .expect("Sin table should be initialized")
-            .get(index)
-            .copied()
-            .unwrap_or(0.0)
-    })
+// Linearly interpolated table sample at angle x (radians)
+#[inline]
+fn sample_table(table: &[f32], x: f32) -> f32 {
+    let position = x.rem_euclid(PI * 2.0) * SIN_TABLE_SCALE;
+    let index = (position as usize).min(SIN_TABLE_SIZE - 1);
+    let frac = position - index as f32;
+    let a = table[index];
+    let b = table[index + 1];
+    a + (b - a) * frac
 }
 
-// Fast sin lookup
+/// Table sin with linear interpolation.
+///
+/// Max abs error vs `f32::sin`: 6e-6 for |x| <= 100. Beyond that the f32
+/// argument reduction adds roughly |x| * 3e-8.
 #[inline]
 pub fn fast_sin_lookup(x: f32) -> f32 {
-    let normalized = ((x % (PI * 2.0)) + PI * 2.0) % (PI * 2.0);
-    let index = ((normalized / (PI * 2.0)) * SIN_TABLE_SIZE as f32) as usize;
-    let index = index.min(SIN_TABLE_SIZE - 1);
-    get_sin_value(index)
-}
-
-
-
-
-
-
-// SIMD sin lookup f32x16
-pub fn fast_sin_lookup_simd_16(values: f32x16) -> f32x16 {
     ensure_sin_table_initialized();
-
     SIN_TABLE.with(|table_cell| {
         let table_ref = table_cell.borrow();
-        let table = table_ref.as_ref().expect("Sin table should be initialized");
-
-        // Process 16 values with SIMD
-        let values_arr: [f32; 16] = values.to_array();
-        let mut results = [0.0f32; 16];
-
-        for (i, &val) in values_arr.iter().enumerate() {
-            let normalized = ((val % (PI * 2.0)) + PI * 2.0) % (PI * 2.0);
-            let index = ((normalized / (PI * 2.0)) * SIN_TABLE_SIZE as f32) as usize;
-            let index = index.min(SIN_TABLE_SIZE - 1);
-            results[i] = table[index];
-        }
-
-        f32x16::from_array(results)
+        sample_table(
+            table_ref.as_ref().expect("Sin table should be initialized"),
+            x,
+        )
     })
 }
 
-// Scalar random fallback
+// Former name of `sin_approx_simd_16`, from when it gathered from the table
+#[deprecated(note = "polynomial, not a table lookup; use sin_approx_simd_16")]
+#[inline]
+pub fn fast_sin_lookup_simd_16(values: f32x16) -> f32x16 {
+    sin_approx_simd_16(values)
+}
+
+// Uniform [0, 1) hash of an index, backed by the counter-based generator in
+// rng, so seed_random and seed_random_simd_batch_16 return identical values
 #[inline]
 pub fn seed_random(i: i32) -> f32 {
-    let x = ((i as f32) * 12.9898 + 78.233).sin() * 43_758.547;
-    x - x.floor()
+    crate::rng::random_f32(0, i as u32)
 }
 
-// SIMD random f32x16
+// SIMD random f32x16 for indices start..start + 16
 pub fn seed_random_simd_batch_16(start: i32) -> f32x16 {
-    let indices = f32x16::from_array([
-        start as f32,
-        (start + 1) as f32,
-        (start + 2) as f32,
-        (start + 3) as f32,
-        (start + 4) as f32,
-        (start + 5) as f32,
-        (start + 6) as f32,
-        (start + 7) as f32,
-        (start + 8) as f32,
-        (start + 9) as f32,
-        (start + 10) as f32,
-        (start + 11) as f32,
-        (start + 12) as f32,
-        (start + 13) as f32,
-        (start + 14) as f32,
-        (start + 15) as f32,
-    ]);
-
-    let factor1 = f32x16::splat(12.9898);
-    let factor2 = f32x16::splat(78.233);
-    let factor3 = f32x16::splat(43_758.547);
-
-    // Use SIMD sin lookup
-    let x_values = indices * factor1 + factor2;
-
-    // Use SIMD sin lookup
-    let sin_results = fast_sin_lookup_simd_16(x_values);
-
-    let scaled = sin_results * factor3;
-    scaled - scaled.floor()
+    crate::rng::random_f32_x16(0, start as u32)
 }
 
 // Cosine table
@@ -128,8 +88,8 @@ fn ensure_cos_table_initialized() {
     COS_TABLE.with(|table_cell| {
         let mut table_ref = table_cell.borrow_mut();
         if table_ref.is_none() {
-            let mut table = Vec::with_capacity(SIN_TABLE_SIZE);
-            for i in 0..SIN_TABLE_SIZE {
+            let mut table = Vec::with_capacity(SIN_TABLE_SIZE + 1);
+            for i in 0..=SIN_TABLE_SIZE {
                 let angle = (i as f32 / SIN_TABLE_SIZE as f32) * PI * 2.0;
                 table.push(angle.cos());
             }
@@ -138,48 +98,621 @@ fn ensure_cos_table_initialized() {
     });
 }
 
-// Get cos value by index
-fn get_cos_value(index: usize) -> f32 {
+/// Table cos with linear interpolation, same error as `fast_sin_lookup`.
+#[inline]
+pub fn fast_cos_lookup(x: f32) -> f32 {
     ensure_cos_table_initialized();
     COS_TABLE.with(|table_cell| {
-        table_cell
-            .borrow()
-            .as_ref()
-            .expect("Cos table should be initialized")
-            .get(index)
-            .copied()
-            .unwrap_or(1.0)
+        let table_ref = table_cell.borrow();
+        sample_table(
+            table_ref.as_ref().expect("Cos table should be initialized"),
+            x,
+        )
     })
 }
 
-// Fast cos lookup
+// Former name of `cos_approx_simd_16`, from when it gathered from the table
+#[deprecated(note = "polynomial, not a table lookup; use cos_approx_simd_16")]
 #[inline]
-pub fn fast_cos_lookup(x: f32) -> f32 {
-    let normalized = ((x % (PI * 2.0)) + PI * 2.0) % (PI * 2.0);
-    let index = ((normalized / (PI * 2.0)) * SIN_TABLE_SIZE as f32) as usize;
-    let index = index.min(SIN_TABLE_SIZE - 1);
-    get_cos_value(index)
+pub fn fast_cos_lookup_simd_16(values: f32x16) -> f32x16 {
+    cos_approx_simd_16(values)
 }
 
-// SIMD cos lookup f32x16
-pub fn fast_cos_lookup_simd_16(values: f32x16) -> f32x16 {
-    ensure_cos_table_initialized();
+// Cody-Waite split of pi/2: the leading parts have few enough mantissa bits
+// that k * part is exact for the quadrant counts we reduce
+const FRAC_PI_2_HI: f32 = 1.570_312_5;
+const FRAC_PI_2_MID: f32 = 4.837_513e-4;
+const FRAC_PI_2_LO: f32 = 7.549_79e-8;
 
-    COS_TABLE.with(|table_cell| {
-        let table_ref = table_cell.borrow();
-        let table = table_ref.as_ref().expect("Cos table should be initialized");
+// Minimax coefficients on [-pi/4, pi/4] (Cephes sinf/cosf)
+const SIN_C1: f32 = -1.666_665_5e-1;
+const SIN_C2: f32 = 8.332_161e-3;
+const SIN_C3: f32 = -1.951_529_6e-4;
+const COS_C1: f32 = 4.166_664_6e-2;
+const COS_C2: f32 = -1.388_731_6e-3;
+const COS_C3: f32 = 2.443_315_7e-5;
+
+// Reduce x to r in [-pi/4, pi/4] with x = r + k * pi/2; returns (r, k)
+#[inline]
+fn reduce_quadrant(x: f32) -> (f32, i32) {
+    let k = (x * std::f32::consts::FRAC_2_PI).round();
+    let r = ((x - k * FRAC_PI_2_HI) - k * FRAC_PI_2_MID) - k * FRAC_PI_2_LO;
+    (r, k as i32)
+}
 
-        // Process 16 values with SIMD
-        let values_arr: [f32; 16] = values.to_array();
-        let mut results = [0.0f32; 16];
+#[inline]
+fn sin_kernel(r: f32) -> f32 {
+    let r2 = r * r;
+    r + r * r2 * (SIN_C1 + r2 * (SIN_C2 + r2 * SIN_C3))
+}
+
+#[inline]
+fn cos_kernel(r: f32) -> f32 {
+    let r2 = r * r;
+    1.0 - 0.5 * r2 + r2 * r2 * (COS_C1 + r2 * (COS_C2 + r2 * COS_C3))
+}
 
-        for (i, &val) in values_arr.iter().enumerate() {
-            let normalized = ((val % (PI * 2.0)) + PI * 2.0) % (PI * 2.0);
-            let index = ((normalized / (PI * 2.0)) * SIN_TABLE_SIZE as f32) as usize;
-            let index = index.min(SIN_TABLE_SIZE - 1);
-            results[i] = table[index];
+/// Polynomial sin and cos of `x` in one pass.
+///
+/// Max abs error vs `f32::sin`/`f32::cos`: 1e-7 for |x| <= 8192. Accuracy
+/// degrades gradually beyond that (about 1e-6 at 1e5); non-finite inputs
+/// give NaN.
+#[inline]
+pub fn sincos_approx(x: f32) -> (f32, f32) {
+    let (r, k) = reduce_quadrant(x);
+    let (s, c) = (sin_kernel(r), cos_kernel(r));
+    match k & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+/// Polynomial sin, see `sincos_approx` for the error bound.
+#[inline]
+pub fn sin_approx(x: f32) -> f32 {
+    sincos_approx(x).0
+}
+
+/// Polynomial cos, see `sincos_approx` for the error bound.
+#[inline]
+pub fn cos_approx(x: f32) -> f32 {
+    sincos_approx(x).1
+}
+
+/// Lane-parallel `sincos_approx`: same error bound, no gathers or branches.
+pub fn sincos_approx_simd<const N: usize>(x: Simd<f32, N>) -> (Simd<f32, N>, Simd<f32, N>) {
+    let k = (x * Simd::splat(std::f32::consts::FRAC_2_PI)).round();
+    let r = ((x - k * Simd::splat(FRAC_PI_2_HI)) - k * Simd::splat(FRAC_PI_2_MID))
+        - k * Simd::splat(FRAC_PI_2_LO);
+    let quadrant: Simd<i32, N> = k.cast();
+
+    let r2 = r * r;
+    let s =
+        r + r * r2 * (Simd::splat(SIN_C1) + r2 * (Simd::splat(SIN_C2) + r2 * Simd::splat(SIN_C3)));
+    let c = Simd::splat(1.0) - Simd::splat(0.5) * r2
+        + r2 * r2 * (Simd::splat(COS_C1) + r2 * (Simd::splat(COS_C2) + r2 * Simd::splat(COS_C3)));
+
+    // Odd quadrants swap sin and cos; signs follow the quadrant table above
+    let one = Simd::splat(1);
+    let two = Simd::splat(2);
+    let zero = Simd::splat(0);
+    let swap = (quadrant & one).simd_ne(zero);
+    let sin_negative = (quadrant & two).simd_ne(zero);
+    let cos_negative = ((quadrant + one) & two).simd_ne(zero);
+
+    let sin = swap.select(c, s);
+    let cos = swap.select(s, c);
+    (
+        sin_negative.select(-sin, sin),
+        cos_negative.select(-cos, cos),
+    )
+}
+
+/// Lane-parallel `sin_approx`.
+pub fn sin_approx_simd<const N: usize>(x: Simd<f32, N>) -> Simd<f32, N> {
+    sincos_approx_simd(x).0
+}
+
+/// Lane-parallel `cos_approx`.
+pub fn cos_approx_simd<const N: usize>(x: Simd<f32, N>) -> Simd<f32, N> {
+    sincos_approx_simd(x).1
+}
+
+// asin minimax coefficients on [0, 0.5] (Cephes asinf)
+const ASIN_C0: f32 = 1.666_675_2e-1;
+const ASIN_C1: f32 = 7.495_300_3e-2;
+const ASIN_C2: f32 = 4.547_002_6e-2;
+const ASIN_C3: f32 = 2.418_131e-2;
+const ASIN_C4: f32 = 4.216_32e-2;
+
+/// Lane-parallel acos for x in [-1, 1]; NaN outside.
+///
+/// Max abs error vs `f32::acos`: 3e-7.
+pub fn acos_approx_simd<const N: usize>(x: Simd<f32, N>) -> Simd<f32, N> {
+    let half = Simd::splat(0.5);
+    let a = x.abs();
+
+    // |x| > 0.5 uses acos(a) = 2 asin(sqrt((1 - a) / 2)); both paths are
+    // evaluated and blended, so lanes never branch
+    let large = a.simd_gt(half);
+    let z = large.select(half * (Simd::splat(1.0) - a), x * x);
+    let t = large.select(z.sqrt(), a);
+    let poly = Simd::splat(ASIN_C0)
+        + z * (Simd::splat(ASIN_C1)
+            + z * (Simd::splat(ASIN_C2) + z * (Simd::splat(ASIN_C3) + z * Simd::splat(ASIN_C4))));
+    let asin_t = t + t * z * poly;
+
+    let negative = x.simd_lt(Simd::splat(0.0));
+    let large_result = Simd::splat(2.0) * asin_t;
+    let large_result = negative.select(Simd::splat(PI) - large_result, large_result);
+    let signed_asin = negative.select(-asin_t, asin_t);
+    let small_result = Simd::splat(std::f32::consts::FRAC_PI_2) - signed_asin;
+
+    let result = large.select(large_result, small_result);
+    a.simd_gt(Simd::splat(1.0))
+        .select(Simd::splat(f32::NAN), result)
+}
+
+// atan minimax coefficients on [-tan(pi/8), tan(pi/8)] (Cephes atanf)
+const ATAN_C0: f32 = -3.333_295e-1;
+const ATAN_C1: f32 = 1.997_771_1e-1;
+const ATAN_C2: f32 = -1.387_768_6e-1;
+const ATAN_C3: f32 = 8.053_744_5e-2;
+
+/// Lane-parallel atan2(y, x) in [-pi, pi], following `f32::atan2` for signed
+/// zeros. Non-finite inputs are not special-cased.
+///
+/// Max abs error vs `f32::atan2`: 3e-7.
+pub fn atan2_approx_simd<const N: usize>(y: Simd<f32, N>, x: Simd<f32, N>) -> Simd<f32, N> {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    let zero = Simd::splat(0.0);
+    let one = Simd::splat(1.0);
+    let abs_x = x.abs();
+    let abs_y = y.abs();
+
+    // Reduce to t = min / max in [0, 1]
+    let steep = abs_y.simd_gt(abs_x);
+    let numerator = steep.select(abs_x, abs_y);
+    let denominator = steep.select(abs_y, abs_x);
+    let t = denominator
+        .simd_eq(zero)
+        .select(zero, numerator / denominator);
+
+    // Then to [-tan(pi/8), tan(pi/8)] around pi/4
+    let upper = t.simd_gt(Simd::splat(std::f32::consts::SQRT_2 - 1.0));
+    let u = upper.select((t - one) / (t + one), t);
+    let z = u * u;
+    let poly = Simd::splat(ATAN_C0)
+        + z * (Simd::splat(ATAN_C1) + z * (Simd::splat(ATAN_C2) + z * Simd::splat(ATAN_C3)));
+    let atan_u = u + u * z * poly;
+    let mut angle = upper.select(Simd::splat(FRAC_PI_4) + atan_u, atan_u);
+
+    // Undo the reductions: octant, then quadrant, then sign of y
+    angle = steep.select(Simd::splat(FRAC_PI_2) - angle, angle);
+    angle = x.is_sign_negative().select(Simd::splat(PI) - angle, angle);
+    y.is_sign_negative().select(-angle, angle)
+}
+
+// ln(2) split for exp argument reduction
+const LN_2_HI: f32 = 0.693_359_4;
+const LN_2_LO: f32 = -2.121_944_4e-4;
+
+// exp minimax coefficients on [-ln(2)/2, ln(2)/2] (Cephes expf)
+const EXP_C0: f32 = 0.5;
+const EXP_C1: f32 = 1.666_666_5e-1;
+const EXP_C2: f32 = 4.166_579_6e-2;
+const EXP_C3: f32 = 8.333_452e-3;
+const EXP_C4: f32 = 1.399_82e-3;
+const EXP_C5: f32 = 1.987_569_1e-4;
+
+// Inputs outside this range underflow to 0 or overflow to infinity
+const EXP_MIN: f32 = -87.33;
+const EXP_MAX: f32 = 88.37;
+
+/// Lane-parallel exp. Results below the smallest normal f32 flush to 0.
+///
+/// Max relative error vs `f32::exp`: 3e-7.
+pub fn exp_approx_simd<const N: usize>(x: Simd<f32, N>) -> Simd<f32, N> {
+    let clamped = x
+        .simd_max(Simd::splat(EXP_MIN))
+        .simd_min(Simd::splat(EXP_MAX));
+
+    // x = n ln2 + r with |r| <= ln2 / 2
+    let n = (clamped * Simd::splat(std::f32::consts::LOG2_E)).round();
+    let r = clamped - n * Simd::splat(LN_2_HI) - n * Simd::splat(LN_2_LO);
+
+    let poly = Simd::splat(EXP_C0)
+        + r * (Simd::splat(EXP_C1)
+            + r * (Simd::splat(EXP_C2)
+                + r * (Simd::splat(EXP_C3) + r * (Simd::splat(EXP_C4) + r * Simd::splat(EXP_C5)))));
+    let exp_r = Simd::splat(1.0) + r + r * r * poly;
+
+    // Scale by 2^n by building the exponent bits directly
+    let exponent: Simd<i32, N> = n.cast();
+    let scale = Simd::<f32, N>::from_bits(((exponent + Simd::splat(127)) << 23).cast());
+    let result = exp_r * scale;
+
+    let result = x
+        .simd_lt(Simd::splat(EXP_MIN))
+        .select(Simd::splat(0.0), result);
+    let result = x
+        .simd_gt(Simd::splat(EXP_MAX))
+        .select(Simd::splat(f32::INFINITY), result);
+    x.is_nan().select(x, result)
+}
+
+// ln minimax coefficients on [sqrt(1/2) - 1, sqrt(2) - 1] (Cephes logf)
+const LN_C: [f32; 9] = [
+    7.037_683_6e-2,
+    -1.151_461e-1,
+    1.167_699_9e-1,
+    -1.242_014_1e-1,
+    1.424_932_3e-1,
+    -1.666_805_8e-1,
+    2.000_071_4e-1,
+    -2.499_999_4e-1,
+    3.333_333e-1,
+];
+
+/// Polynomial natural log for positive normal inputs; 0 gives -inf and
+/// negative inputs NaN. Subnormals are not handled.
+///
+/// Max abs error vs `f32::ln`: 2e-7 on [1e-30, 1e30]. `ln_approx_simd_16`
+/// returns identical bits.
+pub fn ln_approx(x: f32) -> f32 {
+    if x.is_nan() || x < 0.0 {
+        return f32::NAN;
+    }
+    if x == 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    if x == f32::INFINITY {
+        return x;
+    }
+
+    // Split into mantissa m in [sqrt(1/2), sqrt(2)) and exponent e
+    let bits = x.to_bits();
+    let mut e = ((bits >> 23) & 0xff) as i32 - 126;
+    let mut m = f32::from_bits((bits & 0x807f_ffff) | 0x3f00_0000);
+    if m < std::f32::consts::FRAC_1_SQRT_2 {
+        e -= 1;
+        m = m + m - 1.0;
+    } else {
+        m -= 1.0;
+    }
+
+    let e = e as f32;
+    let z = m * m;
+    let mut poly = LN_C[0];
+    for &c in &LN_C[1..] {
+        poly = poly * m + c;
+    }
+    let y = poly * m * z + e * LN_2_LO - 0.5 * z;
+    m + y + e * LN_2_HI
+}
+
+/// Lane-parallel `ln_approx`.
+pub fn ln_approx_simd<const N: usize>(x: Simd<f32, N>) -> Simd<f32, N> {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 23) & Simd::splat(0xff)).cast::<i32>() - Simd::splat(126);
+    let mantissa =
+        Simd::<f32, N>::from_bits((bits & Simd::splat(0x807f_ffff)) | Simd::splat(0x3f00_0000));
+
+    let low = mantissa.simd_lt(Simd::splat(std::f32::consts::FRAC_1_SQRT_2));
+    let e = low.select(
+        exponent.cast::<f32>() - Simd::splat(1.0),
+        exponent.cast::<f32>(),
+    );
+    let m = low.select(
+        mantissa + mantissa - Simd::splat(1.0),
+        mantissa - Simd::splat(1.0),
+    );
+
+    let z = m * m;
+    let mut poly = Simd::splat(LN_C[0]);
+    for &c in &LN_C[1..] {
+        poly = poly * m + Simd::splat(c);
+    }
+    let y = poly * m * z + e * Simd::splat(LN_2_LO) - Simd::splat(0.5) * z;
+    let result = m + y + e * Simd::splat(LN_2_HI);
+
+    let result = x.simd_eq(Simd::splat(f32::INFINITY)).select(x, result);
+    let result = x
+        .simd_eq(Simd::splat(0.0))
+        .select(Simd::splat(f32::NEG_INFINITY), result);
+    (x.simd_lt(Simd::splat(0.0)) | x.is_nan()).select(Simd::splat(f32::NAN), result)
+}
+
+// Lane-parallel 2D vector length
+#[inline]
+pub fn length2_simd<const N: usize>(x: Simd<f32, N>, y: Simd<f32, N>) -> Simd<f32, N> {
+    (x * x + y * y).sqrt()
+}
+
+// Lane-parallel 3D vector length
+#[inline]
+pub fn length3_simd<const N: usize>(
+    x: Simd<f32, N>,
+    y: Simd<f32, N>,
+    z: Simd<f32, N>,
+) -> Simd<f32, N> {
+    (x * x + y * y + z * z).sqrt()
+}
+
+// Lane-parallel 3D normalise; zero-length lanes stay zero
+pub fn normalize3_simd<const N: usize>(
+    x: Simd<f32, N>,
+    y: Simd<f32, N>,
+    z: Simd<f32, N>,
+) -> (Simd<f32, N>, Simd<f32, N>, Simd<f32, N>) {
+    let length = length3_simd(x, y, z);
+    let zero = Simd::splat(0.0);
+    let inv = length.simd_gt(zero).select(Simd::splat(1.0) / length, zero);
+    (x * inv, y * inv, z * inv)
+}
+
+// 16-lane forms of the lane-generic helpers above
+
+#[inline]
+pub fn sincos_approx_simd_16(x: f32x16) -> (f32x16, f32x16) {
+    sincos_approx_simd(x)
+}
+
+#[inline]
+pub fn sin_approx_simd_16(x: f32x16) -> f32x16 {
+    sin_approx_simd(x)
+}
+
+#[inline]
+pub fn cos_approx_simd_16(x: f32x16) -> f32x16 {
+    cos_approx_simd(x)
+}
+
+#[inline]
+pub fn acos_approx_simd_16(x: f32x16) -> f32x16 {
+    acos_approx_simd(x)
+}
+
+#[inline]
+pub fn atan2_approx_simd_16(y: f32x16, x: f32x16) -> f32x16 {
+    atan2_approx_simd(y, x)
+}
+
+#[inline]
+pub fn exp_approx_simd_16(x: f32x16) -> f32x16 {
+    exp_approx_simd(x)
+}
+
+#[inline]
+pub fn ln_approx_simd_16(x: f32x16) -> f32x16 {
+    ln_approx_simd(x)
+}
+
+#[inline]
+pub fn length2_simd_16(x: f32x16, y: f32x16) -> f32x16 {
+    length2_simd(x, y)
+}
+
+#[inline]
+pub fn length3_simd_16(x: f32x16, y: f32x16, z: f32x16) -> f32x16 {
+    length3_simd(x, y, z)
+}
+
+#[inline]
+pub fn normalize3_simd_16(x: f32x16, y: f32x16, z: f32x16) -> (f32x16, f32x16, f32x16) {
+    normalize3_simd(x, y, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Evenly spaced samples over [-range, range], plus both ends
+    fn sweep(range: f32, steps: usize) -> impl Iterator<Item = f32> {
+        (0..=steps).map(move |i| -range + 2.0 * range * i as f32 / steps as f32)
+    }
+
+    fn max_error(range: f32, f: impl Fn(f32) -> f32, reference: impl Fn(f64) -> f64) -> f64 {
+        sweep(range, 200_000)
+            .map(|x| (f(x) as f64 - reference(x as f64)).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn table_lookup_error_bound() {
+        assert!(max_error(100.0, fast_sin_lookup, f64::sin) <= 6e-6);
+        assert!(max_error(100.0, fast_cos_lookup, f64::cos) <= 6e-6);
+    }
+
+    #[test]
+    fn table_lookup_is_continuous() {
+        // Interpolation removes the old nearest-index steps
+        let step = 1e-4;
+        for x in sweep(7.0, 10_000) {
+            assert!((fast_sin_lookup(x + step) - fast_sin_lookup(x)).abs() <= step * 1.01 + 1e-6);
         }
+    }
 
-        f32x16::from_array(results)
-    })
+    #[test]
+    fn polynomial_error_bound() {
+        assert!(max_error(8192.0, sin_approx, f64::sin) <= 1e-7);
+        assert!(max_error(8192.0, cos_approx, f64::cos) <= 1e-7);
+    }
+
+    #[test]
+    fn polynomial_matches_f32_sin() {
+        for x in sweep(8192.0, 50_000) {
+            assert!((sin_approx(x) - x.sin()).abs() <= 1.5e-7, "sin({x})");
+            assert!((cos_approx(x) - x.cos()).abs() <= 1.5e-7, "cos({x})");
+        }
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        let inputs: Vec<f32> = sweep(8192.0, 16 * 4096 - 1).collect();
+        for chunk in inputs.as_chunks::<16>().0 {
+            let (sin, cos) = sincos_approx_simd_16(f32x16::from_array(*chunk));
+            for (lane, &x) in chunk.iter().enumerate() {
+                assert_eq!(sin[lane].to_bits(), sin_approx(x).to_bits(), "sin({x})");
+                assert_eq!(cos[lane].to_bits(), cos_approx(x).to_bits(), "cos({x})");
+            }
+        }
+    }
+
+    #[test]
+    fn simd_approx_within_table_error() {
+        for chunk in sweep(100.0, 16 * 1024 - 1)
+            .collect::<Vec<_>>()
+            .as_chunks::<16>()
+            .0
+        {
+            let sin = sin_approx_simd_16(f32x16::from_array(*chunk));
+            let cos = cos_approx_simd_16(f32x16::from_array(*chunk));
+            for (lane, &x) in chunk.iter().enumerate() {
+                assert!((sin[lane] - fast_sin_lookup(x)).abs() <= 6e-6);
+                assert!((cos[lane] - fast_cos_lookup(x)).abs() <= 6e-6);
+            }
+        }
+    }
+
+    // Applies a lane-parallel function to every sample, 16 at a time
+    fn map_simd(inputs: &[f32], f: impl Fn(f32x16) -> f32x16) -> Vec<f32> {
+        inputs
+            .chunks(16)
+            .flat_map(|chunk| {
+                let mut lanes = [0.0; 16];
+                lanes[..chunk.len()].copy_from_slice(chunk);
+                f(f32x16::from_array(lanes)).to_array()[..chunk.len()].to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn acos_error_bound() {
+        let inputs: Vec<f32> = sweep(1.0, 100_000).collect();
+        for (&x, acos) in inputs.iter().zip(map_simd(&inputs, acos_approx_simd_16)) {
+            assert!((acos as f64 - (x as f64).acos()).abs() <= 3e-7, "acos({x})");
+        }
+        let outside = acos_approx_simd_16(f32x16::splat(1.5));
+        assert!(outside.is_nan().all());
+    }
+
+    #[test]
+    fn atan2_error_bound() {
+        let angles: Vec<f32> = sweep(PI, 20_000).collect();
+        for radius in [1e-3, 1.0, 250.0] {
+            let xs: Vec<f32> = angles.iter().map(|a| radius * a.cos()).collect();
+            let ys: Vec<f32> = angles.iter().map(|a| radius * a.sin()).collect();
+            for (chunk_x, chunk_y) in xs.chunks(16).zip(ys.chunks(16)) {
+                if chunk_x.len() < 16 {
+                    break;
+                }
+                let result =
+                    atan2_approx_simd_16(f32x16::from_slice(chunk_y), f32x16::from_slice(chunk_x));
+                for lane in 0..16 {
+                    let expected = (chunk_y[lane] as f64).atan2(chunk_x[lane] as f64);
+                    assert!((result[lane] as f64 - expected).abs() <= 3e-7);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn atan2_signed_zeros() {
+        let y = f32x16::from_array([
+            0.0, -0.0, 0.0, -0.0, 1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        ]);
+        let x = f32x16::from_array([
+            1.0, 1.0, -1.0, -1.0, 0.0, 0.0, 0.0, -0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        ]);
+        let result = atan2_approx_simd_16(y, x);
+        for lane in 0..8 {
+            assert_eq!(result[lane], y[lane].atan2(x[lane]), "lane {lane}");
+        }
+    }
+
+    #[test]
+    fn exp_error_bound() {
+        let inputs: Vec<f32> = sweep(87.0, 200_000).collect();
+        for (&x, exp) in inputs.iter().zip(map_simd(&inputs, exp_approx_simd_16)) {
+            let expected = (x as f64).exp();
+            assert!(
+                ((exp as f64 - expected) / expected).abs() <= 3e-7,
+                "exp({x})"
+            );
+        }
+        let edges = exp_approx_simd_16(f32x16::from_array([
+            -200.0,
+            200.0,
+            0.0,
+            f32::NAN,
+            1.0,
+            -1.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+        ]));
+        assert_eq!(edges[0], 0.0);
+        assert_eq!(edges[1], f32::INFINITY);
+        assert_eq!(edges[2], 1.0);
+        assert!(edges[3].is_nan());
+    }
+
+    #[test]
+    fn ln_error_bound() {
+        let inputs: Vec<f32> = sweep(69.0, 100_000).map(f32::exp).collect();
+        let simd = map_simd(&inputs, ln_approx_simd_16);
+        for (&x, ln) in inputs.iter().zip(simd) {
+            assert!((ln as f64 - (x as f64).ln()).abs() <= 2e-7, "ln({x})");
+            assert_eq!(ln.to_bits(), ln_approx(x).to_bits(), "ln({x})");
+        }
+        let edges = [0.0, -1.0, f32::INFINITY, f32::NAN, 1.0];
+        let simd = map_simd(&edges, ln_approx_simd_16);
+        for (&x, ln) in edges.iter().zip(simd) {
+            let scalar = ln_approx(x);
+            assert!(scalar.to_bits() == ln.to_bits() || (scalar.is_nan() && ln.is_nan()));
+        }
+        assert_eq!(ln_approx(1.0), 0.0);
+        assert_eq!(ln_approx(0.0), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn normalize_handles_zero() {
+        let (x, y, z) = normalize3_simd_16(
+            f32x16::splat(3.0),
+            f32x16::splat(0.0),
+            f32x16::from_array([
+                4.0, 0.0, 4.0, 0.0, 4.0, 0.0, 4.0, 0.0, 4.0, 0.0, 4.0, 0.0, 4.0, 0.0, 4.0, 0.0,
+            ]),
+        );
+        assert!((length3_simd_16(x, y, z)[0] - 1.0).abs() <= 1e-6);
+        assert!((x[0] - 0.6).abs() <= 1e-6 && (z[0] - 0.8).abs() <= 1e-6);
+        let (x, y, z) =
+            normalize3_simd_16(f32x16::splat(0.0), f32x16::splat(0.0), f32x16::splat(0.0));
+        assert_eq!(length3_simd_16(x, y, z), f32x16::splat(0.0));
+        assert_eq!(
+            length2_simd_16(f32x16::splat(3.0), f32x16::splat(4.0)),
+            f32x16::splat(5.0)
+        );
+    }
+
+    #[test]
+    fn sincos_edge_cases() {
+        assert_eq!(sin_approx(0.0), 0.0);
+        assert_eq!(cos_approx(0.0), 1.0);
+        assert!(sin_approx(f32::NAN).is_nan());
+        assert!(sin_approx(f32::INFINITY).is_nan());
+        let (s, c) = sincos_approx(PI / 2.0);
+        assert!((s - 1.0).abs() <= 1e-7 && c.abs() <= 1e-7);
+    }
 }
@@ -0,0 +1,832 @@
+// Small vector, matrix and quaternion types for camera and culling work.
+//
+// Conventions match WebGL and three.js: right-handed, column-major matrices,
+// column vectors (M * v), and clip-space z in [-1, 1].
+
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// Implements the component-wise operators shared by all vector types
+macro_rules! vector_ops {
+    ($name:ident { $($field:ident),+ }) => {
+        impl $name {
+            pub const ZERO: Self = Self { $($field: 0.0),+ };
+
+            #[inline]
+            pub fn dot(self, other: Self) -> f32 {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            #[inline]
+            pub fn length_squared(self) -> f32 {
+                self.dot(self)
+            }
+
+            #[inline]
+            pub fn length(self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            /// Unit vector in the same direction, or zero for a zero vector.
+            #[inline]
+            pub fn normalize(self) -> Self {
+                let length = self.length();
+                if length > 0.0 {
+                    self / length
+                } else {
+                    Self::ZERO
+                }
+            }
+
+            #[inline]
+            pub fn lerp(self, other: Self, t: f32) -> Self {
+                self + (other - self) * t
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            #[inline]
+            fn add(self, other: Self) -> Self {
+                Self { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            #[inline]
+            fn sub(self, other: Self) -> Self {
+                Self { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = Self;
+            #[inline]
+            fn mul(self, scale: f32) -> Self {
+                Self { $($field: self.$field * scale),+ }
+            }
+        }
+
+        impl Div<f32> for $name {
+            type Output = Self;
+            #[inline]
+            fn div(self, scale: f32) -> Self {
+                Self { $($field: self.$field / scale),+ }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+            #[inline]
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $name {
+            #[inline]
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            #[inline]
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<f32> for $name {
+            #[inline]
+            fn mul_assign(&mut self, scale: f32) {
+                *self = *self * scale;
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+vector_ops!(Vec2 { x, y });
+vector_ops!(Vec3 { x, y, z });
+vector_ops!(Vec4 { x, y, z, w });
+
+impl Vec2 {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// z component of the 3D cross product; positive when `other` is counter-clockwise.
+    #[inline]
+    pub fn perp_dot(self, other: Self) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl Vec3 {
+    pub const X: Self = Self::new(1.0, 0.0, 0.0);
+    pub const Y: Self = Self::new(0.0, 1.0, 0.0);
+    pub const Z: Self = Self::new(0.0, 0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    #[inline]
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    #[inline]
+    pub fn min(self, other: Self) -> Self {
+        Self::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    #[inline]
+    pub fn max(self, other: Self) -> Self {
+        Self::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    #[inline]
+    pub fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+}
+
+impl Vec4 {
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    #[inline]
+    pub fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    /// Perspective divide: xyz / w.
+    #[inline]
+    pub fn project(self) -> Vec3 {
+        self.truncate() / self.w
+    }
+}
+
+/// 4x4 matrix stored column-major, as WebGL and three.js's `elements` expect.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub cols: [Vec4; 4],
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mat4 {
+    pub const IDENTITY: Self = Self::from_cols(
+        Vec4::new(1.0, 0.0, 0.0, 0.0),
+        Vec4::new(0.0, 1.0, 0.0, 0.0),
+        Vec4::new(0.0, 0.0, 1.0, 0.0),
+        Vec4::new(0.0, 0.0, 0.0, 1.0),
+    );
+
+    pub const fn from_cols(x: Vec4, y: Vec4, z: Vec4, w: Vec4) -> Self {
+        Self { cols: [x, y, z, w] }
+    }
+
+    /// Reads 16 column-major values, e.g. a three.js `Matrix4.elements` copy.
+    pub fn from_cols_slice(values: &[f32]) -> Self {
+        let col = |c: usize| {
+            Vec4::new(
+                values[c * 4],
+                values[c * 4 + 1],
+                values[c * 4 + 2],
+                values[c * 4 + 3],
+            )
+        };
+        Self::from_cols(col(0), col(1), col(2), col(3))
+    }
+
+    pub fn to_cols_array(&self) -> [f32; 16] {
+        let mut values = [0.0; 16];
+        for (c, col) in self.cols.iter().enumerate() {
+            values[c * 4..c * 4 + 4].copy_from_slice(&[col.x, col.y, col.z, col.w]);
+        }
+        values
+    }
+
+    #[inline]
+    pub fn row(&self, index: usize) -> Vec4 {
+        let pick = |v: Vec4| [v.x, v.y, v.z, v.w][index];
+        Vec4::new(
+            pick(self.cols[0]),
+            pick(self.cols[1]),
+            pick(self.cols[2]),
+            pick(self.cols[3]),
+        )
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_cols(self.row(0), self.row(1), self.row(2), self.row(3))
+    }
+
+    pub fn from_translation(t: Vec3) -> Self {
+        let mut m = Self::IDENTITY;
+        m.cols[3] = t.extend(1.0);
+        m
+    }
+
+    pub fn from_scale(s: Vec3) -> Self {
+        Self::from_cols(
+            Vec4::new(s.x, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, s.y, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, s.z, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
+    pub fn from_quat(q: Quat) -> Self {
+        let (x, y, z, w) = (q.x, q.y, q.z, q.w);
+        let (x2, y2, z2) = (x + x, y + y, z + z);
+        let (xx, xy, xz) = (x * x2, x * y2, x * z2);
+        let (yy, yz, zz) = (y * y2, y * z2, z * z2);
+        let (wx, wy, wz) = (w * x2, w * y2, w * z2);
+        Self::from_cols(
+            Vec4::new(1.0 - (yy + zz), xy + wz, xz - wy, 0.0),
+            Vec4::new(xy - wz, 1.0 - (xx + zz), yz + wx, 0.0),
+            Vec4::new(xz + wy, yz - wx, 1.0 - (xx + yy), 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+        )
+    }
+
+    /// Translation * rotation * scale, as three.js `Matrix4.compose`.
+    pub fn from_trs(translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        let mut m = Self::from_quat(rotation);
+        m.cols[0] *= scale.x;
+        m.cols[1] *= scale.y;
+        m.cols[2] *= scale.z;
+        m.cols[3] = translation.extend(1.0);
+        m
+    }
+
+    /// OpenGL-style perspective projection; `fov_y` in radians.
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0 / (fov_y * 0.5).tan();
+        let range = 1.0 / (near - far);
+        Self::from_cols(
+            Vec4::new(f / aspect, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, f, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, (far + near) * range, -1.0),
+            Vec4::new(0.0, 0.0, 2.0 * far * near * range, 0.0),
+        )
+    }
+
+    /// View matrix for a camera at `eye` looking at `target` (camera looks down -z).
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        let forward = (target - eye).normalize();
+        let right = forward.cross(up).normalize();
+        let up = right.cross(forward);
+        Self::from_cols(
+            Vec4::new(right.x, up.x, -forward.x, 0.0),
+            Vec4::new(right.y, up.y, -forward.y, 0.0),
+            Vec4::new(right.z, up.z, -forward.z, 0.0),
+            Vec4::new(-right.dot(eye), -up.dot(eye), forward.dot(eye), 1.0),
+        )
+    }
+
+    #[inline]
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        (*self * p.extend(1.0)).project()
+    }
+
+    /// Applies the linear part only, ignoring translation.
+    #[inline]
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        (*self * v.extend(0.0)).truncate()
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let [a, b, c, d] = self.cols;
+        let s0 = a.x * b.y - b.x * a.y;
+        let s1 = a.x * b.z - b.x * a.z;
+        let s2 = a.x * b.w - b.x * a.w;
+        let s3 = a.y * b.z - b.y * a.z;
+        let s4 = a.y * b.w - b.y * a.w;
+        let s5 = a.z * b.w - b.z * a.w;
+        let c5 = c.z * d.w - d.z * c.w;
+        let c4 = c.y * d.w - d.y * c.w;
+        let c3 = c.y * d.z - d.y * c.z;
+        let c2 = c.x * d.w - d.x * c.w;
+        let c1 = c.x * d.z - d.x * c.z;
+        let c0 = c.x * d.y - d.x * c.y;
+        s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+    }
+
+    /// Inverse via 2x2 sub-determinants, or `None` when singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d] = self.cols;
+        let s0 = a.x * b.y - b.x * a.y;
+        let s1 = a.x * b.z - b.x * a.z;
+        let s2 = a.x * b.w - b.x * a.w;
+        let s3 = a.y * b.z - b.y * a.z;
+        let s4 = a.y * b.w - b.y * a.w;
+        let s5 = a.z * b.w - b.z * a.w;
+        let c5 = c.z * d.w - d.z * c.w;
+        let c4 = c.y * d.w - d.y * c.w;
+        let c3 = c.y * d.z - d.y * c.z;
+        let c2 = c.x * d.w - d.x * c.w;
+        let c1 = c.x * d.z - d.x * c.z;
+        let c0 = c.x * d.y - d.x * c.y;
+
+        let det = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv = 1.0 / det;
+
+        Some(Self::from_cols(
+            Vec4::new(
+                (b.y * c5 - b.z * c4 + b.w * c3) * inv,
+                (-a.y * c5 + a.z * c4 - a.w * c3) * inv,
+                (d.y * s5 - d.z * s4 + d.w * s3) * inv,
+                (-c.y * s5 + c.z * s4 - c.w * s3) * inv,
+            ),
+            Vec4::new(
+                (-b.x * c5 + b.z * c2 - b.w * c1) * inv,
+                (a.x * c5 - a.z * c2 + a.w * c1) * inv,
+                (-d.x * s5 + d.z * s2 - d.w * s1) * inv,
+                (c.x * s5 - c.z * s2 + c.w * s1) * inv,
+            ),
+            Vec4::new(
+                (b.x * c4 - b.y * c2 + b.w * c0) * inv,
+                (-a.x * c4 + a.y * c2 - a.w * c0) * inv,
+                (d.x * s4 - d.y * s2 + d.w * s0) * inv,
+                (-c.x * s4 + c.y * s2 - c.w * s0) * inv,
+            ),
+            Vec4::new(
+                (-b.x * c3 + b.y * c1 - b.z * c0) * inv,
+                (a.x * c3 - a.y * c1 + a.z * c0) * inv,
+                (-d.x * s3 + d.y * s1 - d.z * s0) * inv,
+                (c.x * s3 - c.y * s1 + c.z * s0) * inv,
+            ),
+        ))
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+    #[inline]
+    fn mul(self, v: Vec4) -> Vec4 {
+        self.cols[0] * v.x + self.cols[1] * v.y + self.cols[2] * v.z + self.cols[3] * v.w
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Self;
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        Self::from_cols(
+            self * other.cols[0],
+            self * other.cols[1],
+            self * other.cols[2],
+            self * other.cols[3],
+        )
+    }
+}
+
+/// Unit quaternion rotation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Quat {
+    pub const IDENTITY: Self = Self::from_xyzw(0.0, 0.0, 0.0, 1.0);
+
+    pub const fn from_xyzw(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Rotation of `angle` radians about `axis` (normalised here).
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        let axis = axis.normalize() * sin;
+        Self::from_xyzw(axis.x, axis.y, axis.z, cos)
+    }
+
+    /// Intrinsic XYZ Euler angles in radians, three.js's default order.
+    pub fn from_euler(x: f32, y: f32, z: f32) -> Self {
+        Self::from_axis_angle(Vec3::X, x)
+            * Self::from_axis_angle(Vec3::Y, y)
+            * Self::from_axis_angle(Vec3::Z, z)
+    }
+
+    fn as_vec4(self) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, self.w)
+    }
+
+    fn from_vec4(v: Vec4) -> Self {
+        Self::from_xyzw(v.x, v.y, v.z, v.w)
+    }
+
+    #[inline]
+    pub fn dot(self, other: Self) -> f32 {
+        self.as_vec4().dot(other.as_vec4())
+    }
+
+    pub fn normalize(self) -> Self {
+        let length = self.as_vec4().length();
+        if length > 0.0 {
+            Self::from_vec4(self.as_vec4() / length)
+        } else {
+            Self::IDENTITY
+        }
+    }
+
+    /// Inverse rotation, for unit quaternions.
+    #[inline]
+    pub fn conjugate(self) -> Self {
+        Self::from_xyzw(-self.x, -self.y, -self.z, self.w)
+    }
+
+    #[inline]
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        // v + 2w(q x v) + 2q x (q x v), with q the vector part
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+        v + t * self.w + q.cross(t)
+    }
+
+    /// Shortest-path spherical interpolation.
+    pub fn slerp(self, other: Self, t: f32) -> Self {
+        let mut cos = self.dot(other);
+        let mut end = other.as_vec4();
+        if cos < 0.0 {
+            cos = -cos;
+            end = -end;
+        }
+        // Nearly parallel: fall back to normalised lerp to avoid dividing by ~0
+        if cos > 0.9995 {
+            return Self::from_vec4(self.as_vec4().lerp(end, t)).normalize();
+        }
+        let angle = cos.acos();
+        let sin = angle.sin();
+        let a = ((1.0 - t) * angle).sin() / sin;
+        let b = (t * angle).sin() / sin;
+        Self::from_vec4(self.as_vec4() * a + end * b)
+    }
+}
+
+impl Mul for Quat {
+    type Output = Self;
+    /// Composition: `(a * b).rotate(v) == a.rotate(b.rotate(v))`.
+    #[inline]
+    fn mul(self, o: Self) -> Self {
+        Self::from_xyzw(
+            self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+            self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+        )
+    }
+}
+
+/// Plane `normal . p + d = 0`; points with positive distance are in front.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: Vec3,
+    pub d: f32,
+}
+
+impl Plane {
+    /// Plane from (a, b, c, d) coefficients, scaled to a unit normal.
+    pub fn from_coefficients(v: Vec4) -> Self {
+        let length = v.truncate().length();
+        let scale = if length > 0.0 { 1.0 / length } else { 0.0 };
+        Self {
+            normal: v.truncate() * scale,
+            d: v.w * scale,
+        }
+    }
+
+    #[inline]
+    pub fn distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) + self.d
+    }
+}
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    #[inline]
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    #[inline]
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+}
+
+/// The six clip planes of a view-projection matrix, normals pointing inward.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near, far
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Gribb-Hartmann extraction: each plane is row 3 plus or minus row 0, 1 or 2.
+    pub fn from_view_projection(vp: &Mat4) -> Self {
+        let w = vp.row(3);
+        let plane = |v: Vec4| Plane::from_coefficients(v);
+        Self {
+            planes: [
+                plane(w + vp.row(0)),
+                plane(w - vp.row(0)),
+                plane(w + vp.row(1)),
+                plane(w - vp.row(1)),
+                plane(w + vp.row(2)),
+                plane(w - vp.row(2)),
+            ],
+        }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|plane| plane.distance(point) >= 0.0)
+    }
+
+    /// Conservative: may accept spheres just outside a frustum corner.
+    pub fn intersects_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.distance(center) >= -radius)
+    }
+
+    /// Conservative: may accept boxes just outside a frustum corner.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let center = aabb.center();
+        let extents = aabb.half_extents();
+        self.planes.iter().all(|plane| {
+            // Projected radius of the box onto the plane normal
+            let radius = extents.x * plane.normal.x.abs()
+                + extents.y * plane.normal.y.abs()
+                + extents.z * plane.normal.z.abs();
+            plane.distance(center) >= -radius
+        })
+    }
+}
+
+/// Half-line `origin + t * direction`, t >= 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// Unit length
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Unprojects normalised device coordinates (x, y in [-1, 1]) through
+    /// `inverse_view_projection`, from the near plane towards the far plane.
+    pub fn from_ndc(inverse_view_projection: &Mat4, ndc_x: f32, ndc_y: f32) -> Self {
+        let near = inverse_view_projection.transform_point(Vec3::new(ndc_x, ndc_y, -1.0));
+        let far = inverse_view_projection.transform_point(Vec3::new(ndc_x, ndc_y, 1.0));
+        Self {
+            origin: near,
+            direction: (far - near).normalize(),
+        }
+    }
+
+    #[inline]
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_vec3_near(a: Vec3, b: Vec3, tolerance: f32) {
+        assert!((a - b).length() <= tolerance, "{a:?} != {b:?}");
+    }
+
+    fn assert_mat4_near(a: &Mat4, b: &Mat4, tolerance: f32) {
+        let (a, b) = (a.to_cols_array(), b.to_cols_array());
+        for (x, y) in a.iter().zip(&b) {
+            assert!((x - y).abs() <= tolerance, "{a:?} != {b:?}");
+        }
+    }
+
+    fn camera() -> (Mat4, Mat4) {
+        let projection = Mat4::perspective(60f32.to_radians(), 16.0 / 9.0, 0.1, 100.0);
+        let view = Mat4::look_at(Vec3::new(3.0, 2.0, 10.0), Vec3::ZERO, Vec3::Y);
+        (projection, view)
+    }
+
+    #[test]
+    fn vector_basics() {
+        assert_eq!(Vec3::X.cross(Vec3::Y), Vec3::Z);
+        assert_eq!(Vec3::new(3.0, 4.0, 0.0).length(), 5.0);
+        assert_eq!(Vec3::ZERO.normalize(), Vec3::ZERO);
+        assert_eq!(Vec2::new(1.0, 0.0).perp_dot(Vec2::new(0.0, 1.0)), 1.0);
+        assert_eq!(
+            Vec4::new(2.0, 4.0, 6.0, 2.0).project(),
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn matrix_column_major_layout() {
+        let values: Vec<f32> = (0..16).map(|i| i as f32).collect();
+        let m = Mat4::from_cols_slice(&values);
+        assert_eq!(m.to_cols_array().to_vec(), values);
+        assert_eq!(m.row(1), Vec4::new(1.0, 5.0, 9.0, 13.0));
+        assert_eq!(m.transpose().row(1), m.cols[1]);
+
+        let t = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(t.to_cols_array()[12..15], [1.0, 2.0, 3.0]);
+        assert_eq!(t.transform_point(Vec3::ZERO), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(t.transform_vector(Vec3::X), Vec3::X);
+    }
+
+    #[test]
+    fn multiply_composes_transforms() {
+        let a = Mat4::from_translation(Vec3::new(5.0, 0.0, 0.0));
+        let b = Mat4::from_scale(Vec3::new(2.0, 2.0, 2.0));
+        // Scale first, then translate
+        let p = (a * b).transform_point(Vec3::new(1.0, 1.0, 1.0));
+        assert_eq!(p, Vec3::new(7.0, 2.0, 2.0));
+        assert_eq!(a * Mat4::IDENTITY, a);
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        let (projection, view) = camera();
+        let trs = Mat4::from_trs(
+            Vec3::new(1.0, -2.0, 3.0),
+            Quat::from_euler(0.3, -1.1, 2.0),
+            Vec3::new(2.0, 0.5, 1.5),
+        );
+        for m in [projection, view, trs, projection * view * trs] {
+            let inverse = m.inverse().expect("invertible");
+            assert_mat4_near(&(m * inverse), &Mat4::IDENTITY, 1e-4);
+            assert!((m.determinant() * inverse.determinant() - 1.0).abs() < 1e-4);
+        }
+        assert_eq!(Mat4::from_scale(Vec3::new(1.0, 0.0, 1.0)).inverse(), None);
+    }
+
+    #[test]
+    fn perspective_maps_near_and_far_to_clip_range() {
+        let (projection, _) = camera();
+        let near = projection.transform_point(Vec3::new(0.0, 0.0, -0.1));
+        let far = projection.transform_point(Vec3::new(0.0, 0.0, -100.0));
+        assert!((near.z + 1.0).abs() < 1e-5);
+        assert!((far.z - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn look_at_puts_target_straight_ahead() {
+        let (_, view) = camera();
+        let target = view.transform_point(Vec3::ZERO);
+        let distance = Vec3::new(3.0, 2.0, 10.0).length();
+        assert_vec3_near(target, Vec3::new(0.0, 0.0, -distance), 1e-5);
+        // The camera's up stays in the y/z plane of view space
+        assert!(view.transform_vector(Vec3::Y).x.abs() < 1e-6);
+    }
+
+    #[test]
+    fn quaternion_rotation() {
+        let q = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        assert_vec3_near(q.rotate(Vec3::X), Vec3::Y, 1e-6);
+        assert_vec3_near(q.conjugate().rotate(Vec3::Y), Vec3::X, 1e-6);
+
+        // Composition and matrix form agree with applying rotations in turn
+        let a = Quat::from_euler(0.4, 0.2, -0.7);
+        let b = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 1.3);
+        let v = Vec3::new(0.3, -2.0, 1.5);
+        assert_vec3_near((a * b).rotate(v), a.rotate(b.rotate(v)), 1e-5);
+        assert_vec3_near(Mat4::from_quat(a).transform_vector(v), a.rotate(v), 1e-5);
+
+        // Euler XYZ is Rx * Ry * Rz
+        let euler = Quat::from_euler(0.4, 0.2, -0.7);
+        let chained = Quat::from_axis_angle(Vec3::X, 0.4).rotate(
+            Quat::from_axis_angle(Vec3::Y, 0.2)
+                .rotate(Quat::from_axis_angle(Vec3::Z, -0.7).rotate(v)),
+        );
+        assert_vec3_near(euler.rotate(v), chained, 1e-5);
+    }
+
+    #[test]
+    fn slerp_interpolates_angle() {
+        let start = Quat::IDENTITY;
+        let end = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+        let half = start.slerp(end, 0.5);
+        let expected = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2 / 2.0);
+        assert!((half.dot(expected).abs() - 1.0).abs() < 1e-6);
+        assert!((start.slerp(end, 1.0).dot(end) - 1.0).abs() < 1e-6);
+        // Nearly identical rotations take the lerp path and stay unit length
+        let close = Quat::from_axis_angle(Vec3::Z, 1e-3);
+        let mid = start.slerp(close, 0.5);
+        assert!((mid.dot(mid) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn frustum_culling() {
+        let (projection, view) = camera();
+        let frustum = Frustum::from_view_projection(&(projection * view));
+
+        for plane in &frustum.planes {
+            assert!((plane.normal.length() - 1.0).abs() < 1e-5);
+        }
+
+        assert!(frustum.contains_point(Vec3::ZERO));
+        // Behind the camera and beyond the far plane
+        assert!(!frustum.contains_point(Vec3::new(6.0, 4.0, 20.0)));
+        assert!(!frustum.contains_point(Vec3::new(-30.0, -20.0, -100.0)));
+
+        // A sphere just outside the left plane, and one overlapping it
+        let left = frustum.planes[0];
+        let outside = Vec3::ZERO - left.normal * (left.distance(Vec3::ZERO) + 2.0);
+        assert!(!frustum.contains_point(outside));
+        assert!(!frustum.intersects_sphere(outside, 1.0));
+        assert!(frustum.intersects_sphere(outside, 3.0));
+
+        let offset = Vec3::new(1.5, 1.5, 1.5);
+        assert!(frustum.intersects_aabb(&Aabb::new(outside - offset, outside + offset)));
+        let far_away = Vec3::new(500.0, 0.0, 0.0);
+        assert!(!frustum.intersects_aabb(&Aabb::new(far_away - offset, far_away + offset)));
+    }
+
+    #[test]
+    fn ray_from_ndc_passes_through_projected_point() {
+        let (projection, view) = camera();
+        let view_projection = projection * view;
+        let inverse = view_projection.inverse().unwrap();
+        let eye = Vec3::new(3.0, 2.0, 10.0);
+
+        // The centre ray starts at the near plane and heads at the target
+        let centre = Ray::from_ndc(&inverse, 0.0, 0.0);
+        assert_vec3_near(centre.direction, (Vec3::ZERO - eye).normalize(), 1e-4);
+        assert!(((centre.origin - eye).length() - 0.1).abs() < 1e-3);
+
+        // Any point projected to NDC lies on the ray unprojected from it
+        let point = Vec3::new(1.0, -0.5, 2.0);
+        let ndc = view_projection.transform_point(point);
+        let ray = Ray::from_ndc(&inverse, ndc.x, ndc.y);
+        let t = (point - ray.origin).dot(ray.direction);
+        assert_vec3_near(ray.at(t), point, 1e-3);
+    }
+}
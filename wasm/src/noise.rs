@@ -0,0 +1,703 @@
+// Gradient (simplex) and value noise, fractal sums and curl fields.
+//
+// Lattice values come from the counter-based rng, so every field is a pure
+// function of (seed, position). Each noise is written once over N lanes; the
+// scalar forms run the same kernel on one lane, so they return exactly the
+// bits of the matching f32x16 lane.
+
+use std::simd::cmp::{SimdPartialEq, SimdPartialOrd};
+use std::simd::num::{SimdFloat, SimdInt, SimdUint};
+use std::simd::{f32x16, Mask, Select, Simd, StdFloat};
+
+use crate::rng::random_u32_lanes;
+
+// Spatial hash multipliers (Teschner et al.) folding lattice coordinates into one counter
+const PRIME_X: u32 = 0x8da6_b343;
+const PRIME_Y: u32 = 0xd816_3841;
+const PRIME_Z: u32 = 0xcb1a_b31f;
+
+// Seeds for the second and third potentials of 3D curl noise
+const CURL_SEED_Y: u32 = 0x3c6e_f372;
+const CURL_SEED_Z: u32 = 0xa54f_f53a;
+
+// Finite-difference step for curl noise, in lattice units
+const CURL_EPSILON: f32 = 1e-2;
+
+// 2^-23: maps the top 24 bits of a hash onto [0, 2)
+const VALUE_SCALE: f32 = 1.0 / (1u32 << 23) as f32;
+
+// Simplex skew/unskew factors
+const F2: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+const F3: f32 = 1.0 / 3.0;
+const G3: f32 = 1.0 / 6.0;
+
+// Output scales that bring each simplex variant to roughly [-1, 1]
+const SIMPLEX_1D_SCALE: f32 = 0.395;
+const SIMPLEX_2D_SCALE: f32 = 45.0;
+const SIMPLEX_3D_SCALE: f32 = 32.0;
+
+/// Octave settings shared by the fractal sums.
+#[derive(Clone, Copy, Debug)]
+pub struct Fractal {
+    pub octaves: u32,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+}
+
+impl Fractal {
+    pub const fn new(octaves: u32) -> Self {
+        Self {
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+type Lanes<const N: usize> = Simd<f32, N>;
+type Cells<const N: usize> = Simd<i32, N>;
+
+#[inline]
+fn one(x: f32) -> Lanes<1> {
+    Simd::splat(x)
+}
+
+#[inline]
+fn hash_1d<const N: usize>(seed: u32, ix: Cells<N>) -> Simd<u32, N> {
+    random_u32_lanes(seed, ix.cast::<u32>())
+}
+
+#[inline]
+fn hash_2d<const N: usize>(seed: u32, ix: Cells<N>, iy: Cells<N>) -> Simd<u32, N> {
+    let counter =
+        (ix.cast::<u32>() * Simd::splat(PRIME_X)) ^ (iy.cast::<u32>() * Simd::splat(PRIME_Y));
+    random_u32_lanes(seed, counter)
+}
+
+#[inline]
+fn hash_3d<const N: usize>(seed: u32, ix: Cells<N>, iy: Cells<N>, iz: Cells<N>) -> Simd<u32, N> {
+    let counter = (ix.cast::<u32>() * Simd::splat(PRIME_X))
+        ^ (iy.cast::<u32>() * Simd::splat(PRIME_Y))
+        ^ (iz.cast::<u32>() * Simd::splat(PRIME_Z));
+    random_u32_lanes(seed, counter)
+}
+
+// Lattice value in [-1, 1)
+#[inline]
+fn lattice_value<const N: usize>(hash: Simd<u32, N>) -> Lanes<N> {
+    (hash >> Simd::splat(8)).cast::<f32>() * Simd::splat(VALUE_SCALE) - Simd::splat(1.0)
+}
+
+// Quintic fade 6t^5 - 15t^4 + 10t^3: zero first and second derivatives at the cell edges
+#[inline]
+fn fade<const N: usize>(t: Lanes<N>) -> Lanes<N> {
+    t * t * t * (t * (t * Simd::splat(6.0) - Simd::splat(15.0)) + Simd::splat(10.0))
+}
+
+#[inline]
+fn lerp<const N: usize>(a: Lanes<N>, b: Lanes<N>, t: Lanes<N>) -> Lanes<N> {
+    a + (b - a) * t
+}
+
+#[inline]
+fn bit<const N: usize>(hash: Simd<u32, N>, bit: u32) -> Mask<i32, N> {
+    (hash & Simd::splat(bit)).simd_ne(Simd::splat(0))
+}
+
+#[inline]
+fn negate_if<const N: usize>(mask: Mask<i32, N>, value: Lanes<N>) -> Lanes<N> {
+    mask.select(-value, value)
+}
+
+// Integer cell and offset within it
+#[inline]
+fn cell<const N: usize>(x: Lanes<N>) -> (Cells<N>, Lanes<N>) {
+    let floor = x.floor();
+    (floor.cast::<i32>(), x - floor)
+}
+
+// ---------------------------------------------------------------------------
+// Value noise
+// ---------------------------------------------------------------------------
+
+/// `value_1d` for each lane.
+pub fn value_1d_lanes<const N: usize>(seed: u32, x: Lanes<N>) -> Lanes<N> {
+    let (ix, tx) = cell(x);
+    let a = lattice_value(hash_1d(seed, ix));
+    let b = lattice_value(hash_1d(seed, ix + Simd::splat(1)));
+    lerp(a, b, fade(tx))
+}
+
+/// `value_2d` for each lane.
+pub fn value_2d_lanes<const N: usize>(seed: u32, x: Lanes<N>, y: Lanes<N>) -> Lanes<N> {
+    let (ix, tx) = cell(x);
+    let (iy, ty) = cell(y);
+    let ix1 = ix + Simd::splat(1);
+    let iy1 = iy + Simd::splat(1);
+    let (u, v) = (fade(tx), fade(ty));
+
+    let a = lattice_value(hash_2d(seed, ix, iy));
+    let b = lattice_value(hash_2d(seed, ix1, iy));
+    let c = lattice_value(hash_2d(seed, ix, iy1));
+    let d = lattice_value(hash_2d(seed, ix1, iy1));
+    lerp(lerp(a, b, u), lerp(c, d, u), v)
+}
+
+/// `value_3d` for each lane.
+pub fn value_3d_lanes<const N: usize>(
+    seed: u32,
+    x: Lanes<N>,
+    y: Lanes<N>,
+    z: Lanes<N>,
+) -> Lanes<N> {
+    let (ix, tx) = cell(x);
+    let (iy, ty) = cell(y);
+    let (iz, tz) = cell(z);
+    let ix1 = ix + Simd::splat(1);
+    let iy1 = iy + Simd::splat(1);
+    let iz1 = iz + Simd::splat(1);
+    let (u, v, w) = (fade(tx), fade(ty), fade(tz));
+
+    let corner = |cx, cy, cz| lattice_value(hash_3d(seed, cx, cy, cz));
+    let near = lerp(
+        lerp(corner(ix, iy, iz), corner(ix1, iy, iz), u),
+        lerp(corner(ix, iy1, iz), corner(ix1, iy1, iz), u),
+        v,
+    );
+    let far = lerp(
+        lerp(corner(ix, iy, iz1), corner(ix1, iy, iz1), u),
+        lerp(corner(ix, iy1, iz1), corner(ix1, iy1, iz1), u),
+        v,
+    );
+    lerp(near, far, w)
+}
+
+/// Smooth value noise in [-1, 1] on an integer lattice.
+pub fn value_1d(seed: u32, x: f32) -> f32 {
+    value_1d_lanes(seed, one(x))[0]
+}
+
+pub fn value_2d(seed: u32, x: f32, y: f32) -> f32 {
+    value_2d_lanes(seed, one(x), one(y))[0]
+}
+
+pub fn value_3d(seed: u32, x: f32, y: f32, z: f32) -> f32 {
+    value_3d_lanes(seed, one(x), one(y), one(z))[0]
+}
+
+pub fn value_1d_x16(seed: u32, x: f32x16) -> f32x16 {
+    value_1d_lanes(seed, x)
+}
+
+pub fn value_2d_x16(seed: u32, x: f32x16, y: f32x16) -> f32x16 {
+    value_2d_lanes(seed, x, y)
+}
+
+pub fn value_3d_x16(seed: u32, x: f32x16, y: f32x16, z: f32x16) -> f32x16 {
+    value_3d_lanes(seed, x, y, z)
+}
+
+// ---------------------------------------------------------------------------
+// Simplex noise (after Gustavson's simplexnoise1234)
+// ---------------------------------------------------------------------------
+
+// Gradients 1..8 with random sign
+#[inline]
+fn grad_1d<const N: usize>(hash: Simd<u32, N>, x: Lanes<N>) -> Lanes<N> {
+    let magnitude = (hash & Simd::splat(7)).cast::<f32>() + Simd::splat(1.0);
+    negate_if(bit(hash, 8), magnitude) * x
+}
+
+// Eight gradients of the form (±1, ±2) and (±2, ±1)
+#[inline]
+fn grad_2d<const N: usize>(hash: Simd<u32, N>, x: Lanes<N>, y: Lanes<N>) -> Lanes<N> {
+    let swap = bit(hash, 4);
+    let u = swap.select(y, x);
+    let v = swap.select(x, y);
+    negate_if(bit(hash, 1), u) + negate_if(bit(hash, 2), Simd::splat(2.0) * v)
+}
+
+// The twelve cube-edge gradients, with four repeated to fill 16
+#[inline]
+fn grad_3d<const N: usize>(hash: Simd<u32, N>, x: Lanes<N>, y: Lanes<N>, z: Lanes<N>) -> Lanes<N> {
+    let h = hash & Simd::splat(15);
+    let u = h.simd_lt(Simd::splat(8)).select(x, y);
+    let v_is_x = h.simd_eq(Simd::splat(12)) | h.simd_eq(Simd::splat(14));
+    let v = h.simd_lt(Simd::splat(4)).select(y, v_is_x.select(x, z));
+    negate_if(bit(hash, 1), u) + negate_if(bit(hash, 2), v)
+}
+
+// Radial falloff (r0 - d^2)^4, zero outside the kernel
+#[inline]
+fn falloff<const N: usize>(r0: f32, distance_sq: Lanes<N>) -> Lanes<N> {
+    let t = (Simd::splat(r0) - distance_sq).simd_max(Simd::splat(0.0));
+    let t2 = t * t;
+    t2 * t2
+}
+
+/// `simplex_1d` for each lane.
+pub fn simplex_1d_lanes<const N: usize>(seed: u32, x: Lanes<N>) -> Lanes<N> {
+    let (i0, x0) = cell(x);
+    let x1 = x0 - Simd::splat(1.0);
+
+    let n0 = falloff(1.0, x0 * x0) * grad_1d(hash_1d(seed, i0), x0);
+    let n1 = falloff(1.0, x1 * x1) * grad_1d(hash_1d(seed, i0 + Simd::splat(1)), x1);
+    (n0 + n1) * Simd::splat(SIMPLEX_1D_SCALE)
+}
+
+/// `simplex_2d` for each lane.
+pub fn simplex_2d_lanes<const N: usize>(seed: u32, x: Lanes<N>, y: Lanes<N>) -> Lanes<N> {
+    // Skew to the square grid to find the simplex cell
+    let skew = (x + y) * Simd::splat(F2);
+    let i = (x + skew).floor();
+    let j = (y + skew).floor();
+    let unskew = (i + j) * Simd::splat(G2);
+    let x0 = x - (i - unskew);
+    let y0 = y - (j - unskew);
+    let (i, j) = (i.cast::<i32>(), j.cast::<i32>());
+
+    // Lower or upper triangle of the cell
+    let lower = x0.simd_gt(y0);
+    let (one_f, zero_f) = (Simd::splat(1.0), Simd::splat(0.0));
+    let i1 = lower.select(one_f, zero_f);
+    let j1 = lower.select(zero_f, one_f);
+
+    let x1 = x0 - i1 + Simd::splat(G2);
+    let y1 = y0 - j1 + Simd::splat(G2);
+    let x2 = x0 - Simd::splat(1.0 - 2.0 * G2);
+    let y2 = y0 - Simd::splat(1.0 - 2.0 * G2);
+
+    let h0 = hash_2d(seed, i, j);
+    let h1 = hash_2d(seed, i + i1.cast::<i32>(), j + j1.cast::<i32>());
+    let h2 = hash_2d(seed, i + Simd::splat(1), j + Simd::splat(1));
+
+    let n0 = falloff(0.5, x0 * x0 + y0 * y0) * grad_2d(h0, x0, y0);
+    let n1 = falloff(0.5, x1 * x1 + y1 * y1) * grad_2d(h1, x1, y1);
+    let n2 = falloff(0.5, x2 * x2 + y2 * y2) * grad_2d(h2, x2, y2);
+    (n0 + n1 + n2) * Simd::splat(SIMPLEX_2D_SCALE)
+}
+
+/// `simplex_3d` for each lane.
+pub fn simplex_3d_lanes<const N: usize>(
+    seed: u32,
+    x: Lanes<N>,
+    y: Lanes<N>,
+    z: Lanes<N>,
+) -> Lanes<N> {
+    let skew = (x + y + z) * Simd::splat(F3);
+    let i = (x + skew).floor();
+    let j = (y + skew).floor();
+    let k = (z + skew).floor();
+    let unskew = (i + j + k) * Simd::splat(G3);
+    let x0 = x - (i - unskew);
+    let y0 = y - (j - unskew);
+    let z0 = z - (k - unskew);
+    let (i, j, k) = (i.cast::<i32>(), j.cast::<i32>(), k.cast::<i32>());
+
+    // Rank the offsets to pick which of the six tetrahedra holds the point;
+    // the ranks are always a permutation of 0, 1, 2
+    let (one_i, zero_i) = (Simd::splat(1), Simd::splat(0));
+    let count = |mask: Mask<i32, N>| -> Cells<N> { mask.select(one_i, zero_i) };
+    let rank_x = count(x0.simd_gt(y0)) + count(x0.simd_gt(z0));
+    let rank_y = count(y0.simd_ge(x0)) + count(y0.simd_gt(z0));
+    let rank_z = count(z0.simd_ge(x0)) + count(z0.simd_ge(y0));
+
+    let step =
+        |rank: Cells<N>, at_least: i32| -> Cells<N> { count(rank.simd_ge(Simd::splat(at_least))) };
+    let (i1, j1, k1) = (step(rank_x, 2), step(rank_y, 2), step(rank_z, 2));
+    let (i2, j2, k2) = (step(rank_x, 1), step(rank_y, 1), step(rank_z, 1));
+
+    let x1 = x0 - i1.cast::<f32>() + Simd::splat(G3);
+    let y1 = y0 - j1.cast::<f32>() + Simd::splat(G3);
+    let z1 = z0 - k1.cast::<f32>() + Simd::splat(G3);
+    let x2 = x0 - i2.cast::<f32>() + Simd::splat(2.0 * G3);
+    let y2 = y0 - j2.cast::<f32>() + Simd::splat(2.0 * G3);
+    let z2 = z0 - k2.cast::<f32>() + Simd::splat(2.0 * G3);
+    let x3 = x0 - Simd::splat(1.0 - 3.0 * G3);
+    let y3 = y0 - Simd::splat(1.0 - 3.0 * G3);
+    let z3 = z0 - Simd::splat(1.0 - 3.0 * G3);
+
+    let h0 = hash_3d(seed, i, j, k);
+    let h1 = hash_3d(seed, i + i1, j + j1, k + k1);
+    let h2 = hash_3d(seed, i + i2, j + j2, k + k2);
+    let h3 = hash_3d(seed, i + one_i, j + one_i, k + one_i);
+
+    let n0 = falloff(0.6, x0 * x0 + y0 * y0 + z0 * z0) * grad_3d(h0, x0, y0, z0);
+    let n1 = falloff(0.6, x1 * x1 + y1 * y1 + z1 * z1) * grad_3d(h1, x1, y1, z1);
+    let n2 = falloff(0.6, x2 * x2 + y2 * y2 + z2 * z2) * grad_3d(h2, x2, y2, z2);
+    let n3 = falloff(0.6, x3 * x3 + y3 * y3 + z3 * z3) * grad_3d(h3, x3, y3, z3);
+    (n0 + n1 + n2 + n3) * Simd::splat(SIMPLEX_3D_SCALE)
+}
+
+/// Simplex gradient noise in [-1, 1]; zero at every lattice point.
+pub fn simplex_1d(seed: u32, x: f32) -> f32 {
+    simplex_1d_lanes(seed, one(x))[0]
+}
+
+pub fn simplex_2d(seed: u32, x: f32, y: f32) -> f32 {
+    simplex_2d_lanes(seed, one(x), one(y))[0]
+}
+
+pub fn simplex_3d(seed: u32, x: f32, y: f32, z: f32) -> f32 {
+    simplex_3d_lanes(seed, one(x), one(y), one(z))[0]
+}
+
+pub fn simplex_1d_x16(seed: u32, x: f32x16) -> f32x16 {
+    simplex_1d_lanes(seed, x)
+}
+
+pub fn simplex_2d_x16(seed: u32, x: f32x16, y: f32x16) -> f32x16 {
+    simplex_2d_lanes(seed, x, y)
+}
+
+pub fn simplex_3d_x16(seed: u32, x: f32x16, y: f32x16, z: f32x16) -> f32x16 {
+    simplex_3d_lanes(seed, x, y, z)
+}
+
+// ---------------------------------------------------------------------------
+// Fractal sums over simplex noise
+// ---------------------------------------------------------------------------
+
+// Each octave samples its own seed so octaves don't share lattice features
+#[inline]
+fn octave_seed(seed: u32, octave: u32) -> u32 {
+    seed.wrapping_add(octave)
+}
+
+// Sums `octaves` layers of `noise`, each at lacunarity times the frequency
+// and gain times the amplitude of the last, normalised to the amplitude total
+#[inline]
+fn fractal_sum<const N: usize>(
+    fractal: Fractal,
+    mut layer: impl FnMut(u32, Lanes<N>, Lanes<N>) -> Lanes<N>,
+) -> Lanes<N> {
+    let mut sum = Simd::splat(0.0);
+    let mut weight = Simd::splat(1.0);
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    let mut total = 0.0;
+    for octave in 0..fractal.octaves {
+        let signal = layer(octave, Simd::splat(frequency), weight);
+        sum += signal * Simd::splat(amplitude);
+        weight = signal;
+        total += amplitude;
+        amplitude *= fractal.gain;
+        frequency *= fractal.lacunarity;
+    }
+    if total > 0.0 {
+        sum / Simd::splat(total)
+    } else {
+        sum
+    }
+}
+
+// Musgrave's ridged layer: (1 - |n|)^2, weighted by the previous layer
+#[inline]
+fn ridge<const N: usize>(noise: Lanes<N>, previous: Lanes<N>) -> Lanes<N> {
+    let signal = Simd::splat(1.0) - noise.abs();
+    let weight = (previous * Simd::splat(2.0)).simd_clamp(Simd::splat(0.0), Simd::splat(1.0));
+    signal * signal * weight
+}
+
+/// `fbm_2d` for each lane.
+pub fn fbm_2d_lanes<const N: usize>(
+    seed: u32,
+    x: Lanes<N>,
+    y: Lanes<N>,
+    fractal: Fractal,
+) -> Lanes<N> {
+    fractal_sum(fractal, |octave, frequency, _| {
+        simplex_2d_lanes(octave_seed(seed, octave), x * frequency, y * frequency)
+    })
+}
+
+/// `fbm_3d` for each lane.
+pub fn fbm_3d_lanes<const N: usize>(
+    seed: u32,
+    x: Lanes<N>,
+    y: Lanes<N>,
+    z: Lanes<N>,
+    fractal: Fractal,
+) -> Lanes<N> {
+    fractal_sum(fractal, |octave, frequency, _| {
+        simplex_3d_lanes(
+            octave_seed(seed, octave),
+            x * frequency,
+            y * frequency,
+            z * frequency,
+        )
+    })
+}
+
+/// `ridged_2d` for each lane.
+pub fn ridged_2d_lanes<const N: usize>(
+    seed: u32,
+    x: Lanes<N>,
+    y: Lanes<N>,
+    fractal: Fractal,
+) -> Lanes<N> {
+    // The first layer's "previous" weight of 1 clamps to full strength
+    fractal_sum(fractal, |octave, frequency, previous| {
+        let noise = simplex_2d_lanes(octave_seed(seed, octave), x * frequency, y * frequency);
+        ridge(noise, previous)
+    })
+}
+
+/// `ridged_3d` for each lane.
+pub fn ridged_3d_lanes<const N: usize>(
+    seed: u32,
+    x: Lanes<N>,
+    y: Lanes<N>,
+    z: Lanes<N>,
+    fractal: Fractal,
+) -> Lanes<N> {
+    fractal_sum(fractal, |octave, frequency, previous| {
+        let noise = simplex_3d_lanes(
+            octave_seed(seed, octave),
+            x * frequency,
+            y * frequency,
+            z * frequency,
+        );
+        ridge(noise, previous)
+    })
+}
+
+/// Fractal Brownian motion: summed simplex octaves, in [-1, 1].
+pub fn fbm_2d(seed: u32, x: f32, y: f32, fractal: Fractal) -> f32 {
+    fbm_2d_lanes(seed, one(x), one(y), fractal)[0]
+}
+
+pub fn fbm_3d(seed: u32, x: f32, y: f32, z: f32, fractal: Fractal) -> f32 {
+    fbm_3d_lanes(seed, one(x), one(y), one(z), fractal)[0]
+}
+
+/// Ridged multifractal: sharp crests where the noise crosses zero, in [0, 1].
+pub fn ridged_2d(seed: u32, x: f32, y: f32, fractal: Fractal) -> f32 {
+    ridged_2d_lanes(seed, one(x), one(y), fractal)[0]
+}
+
+pub fn ridged_3d(seed: u32, x: f32, y: f32, z: f32, fractal: Fractal) -> f32 {
+    ridged_3d_lanes(seed, one(x), one(y), one(z), fractal)[0]
+}
+
+pub fn fbm_2d_x16(seed: u32, x: f32x16, y: f32x16, fractal: Fractal) -> f32x16 {
+    fbm_2d_lanes(seed, x, y, fractal)
+}
+
+pub fn fbm_3d_x16(seed: u32, x: f32x16, y: f32x16, z: f32x16, fractal: Fractal) -> f32x16 {
+    fbm_3d_lanes(seed, x, y, z, fractal)
+}
+
+pub fn ridged_2d_x16(seed: u32, x: f32x16, y: f32x16, fractal: Fractal) -> f32x16 {
+    ridged_2d_lanes(seed, x, y, fractal)
+}
+
+pub fn ridged_3d_x16(seed: u32, x: f32x16, y: f32x16, z: f32x16, fractal: Fractal) -> f32x16 {
+    ridged_3d_lanes(seed, x, y, z, fractal)
+}
+
+// ---------------------------------------------------------------------------
+// Curl noise: divergence-free velocity fields from simplex potentials
+// ---------------------------------------------------------------------------
+
+/// `curl_2d` for each lane.
+pub fn curl_2d_lanes<const N: usize>(seed: u32, x: Lanes<N>, y: Lanes<N>) -> (Lanes<N>, Lanes<N>) {
+    let e = Simd::splat(CURL_EPSILON);
+    let inv_2e = Simd::splat(0.5 / CURL_EPSILON);
+    let potential = |x, y| simplex_2d_lanes(seed, x, y);
+
+    let d_dx = (potential(x + e, y) - potential(x - e, y)) * inv_2e;
+    let d_dy = (potential(x, y + e) - potential(x, y - e)) * inv_2e;
+    (d_dy, -d_dx)
+}
+
+/// `curl_3d` for each lane.
+pub fn curl_3d_lanes<const N: usize>(
+    seed: u32,
+    x: Lanes<N>,
+    y: Lanes<N>,
+    z: Lanes<N>,
+) -> (Lanes<N>, Lanes<N>, Lanes<N>) {
+    let e = Simd::splat(CURL_EPSILON);
+    let inv_2e = Simd::splat(0.5 / CURL_EPSILON);
+    // Central difference of the potential with seed `s` along one axis
+    let derivative = |s: u32, dx: Lanes<N>, dy: Lanes<N>, dz: Lanes<N>| {
+        (simplex_3d_lanes(s, x + dx, y + dy, z + dz) - simplex_3d_lanes(s, x - dx, y - dy, z - dz))
+            * inv_2e
+    };
+    let zero = Simd::splat(0.0);
+    let (seed_x, seed_y, seed_z) = (seed, seed ^ CURL_SEED_Y, seed ^ CURL_SEED_Z);
+
+    let curl_x = derivative(seed_z, zero, e, zero) - derivative(seed_y, zero, zero, e);
+    let curl_y = derivative(seed_x, zero, zero, e) - derivative(seed_z, e, zero, zero);
+    let curl_z = derivative(seed_y, e, zero, zero) - derivative(seed_x, zero, e, zero);
+    (curl_x, curl_y, curl_z)
+}
+
+/// Curl of a simplex potential; divergence free, so advected particles swirl
+/// without bunching up.
+pub fn curl_2d(seed: u32, x: f32, y: f32) -> (f32, f32) {
+    let (cx, cy) = curl_2d_lanes(seed, one(x), one(y));
+    (cx[0], cy[0])
+}
+
+pub fn curl_3d(seed: u32, x: f32, y: f32, z: f32) -> (f32, f32, f32) {
+    let (cx, cy, cz) = curl_3d_lanes(seed, one(x), one(y), one(z));
+    (cx[0], cy[0], cz[0])
+}
+
+pub fn curl_2d_x16(seed: u32, x: f32x16, y: f32x16) -> (f32x16, f32x16) {
+    curl_2d_lanes(seed, x, y)
+}
+
+pub fn curl_3d_x16(seed: u32, x: f32x16, y: f32x16, z: f32x16) -> (f32x16, f32x16, f32x16) {
+    curl_3d_lanes(seed, x, y, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic scatter of sample points over a few hundred lattice cells
+    fn points(count: u32) -> impl Iterator<Item = (f32, f32, f32)> {
+        (0..count).map(|i| {
+            let x = crate::rng::random_range(1, i, -200.0, 200.0);
+            let y = crate::rng::random_range(2, i, -200.0, 200.0);
+            let z = crate::rng::random_range(3, i, -200.0, 200.0);
+            (x, y, z)
+        })
+    }
+
+    fn lanes(values: &[f32]) -> f32x16 {
+        f32x16::from_slice(values)
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        let samples: Vec<_> = points(16).collect();
+        let x = lanes(&samples.iter().map(|p| p.0).collect::<Vec<_>>());
+        let y = lanes(&samples.iter().map(|p| p.1).collect::<Vec<_>>());
+        let z = lanes(&samples.iter().map(|p| p.2).collect::<Vec<_>>());
+        let fractal = Fractal::default();
+
+        let value = value_3d_x16(7, x, y, z);
+        let simplex_1 = simplex_1d_x16(7, x);
+        let simplex_2 = simplex_2d_x16(7, x, y);
+        let simplex_3 = simplex_3d_x16(7, x, y, z);
+        let fbm = fbm_2d_x16(7, x, y, fractal);
+        let ridged = ridged_3d_x16(7, x, y, z, fractal);
+        let (curl_x, curl_y, curl_z) = curl_3d_x16(7, x, y, z);
+
+        for (lane, &(px, py, pz)) in samples.iter().enumerate() {
+            assert_eq!(value[lane].to_bits(), value_3d(7, px, py, pz).to_bits());
+            assert_eq!(simplex_1[lane].to_bits(), simplex_1d(7, px).to_bits());
+            assert_eq!(simplex_2[lane].to_bits(), simplex_2d(7, px, py).to_bits());
+            assert_eq!(
+                simplex_3[lane].to_bits(),
+                simplex_3d(7, px, py, pz).to_bits()
+            );
+            assert_eq!(fbm[lane].to_bits(), fbm_2d(7, px, py, fractal).to_bits());
+            assert_eq!(
+                ridged[lane].to_bits(),
+                ridged_3d(7, px, py, pz, fractal).to_bits()
+            );
+            let (cx, cy, cz) = curl_3d(7, px, py, pz);
+            assert_eq!((curl_x[lane], curl_y[lane], curl_z[lane]), (cx, cy, cz));
+        }
+    }
+
+    #[test]
+    fn outputs_stay_in_range() {
+        let fractal = Fractal::default();
+        for (x, y, z) in points(50_000) {
+            let signed = [
+                value_1d(0, x),
+                value_2d(0, x, y),
+                value_3d(0, x, y, z),
+                simplex_1d(0, x),
+                simplex_2d(0, x, y),
+                simplex_3d(0, x, y, z),
+                fbm_2d(0, x, y, fractal),
+                fbm_3d(0, x, y, z, fractal),
+            ];
+            assert!(
+                signed.iter().all(|v| (-1.0..=1.0).contains(v)),
+                "{signed:?}"
+            );
+            assert!((0.0..=1.0).contains(&ridged_2d(0, x, y, fractal)));
+            assert!((0.0..=1.0).contains(&ridged_3d(0, x, y, z, fractal)));
+        }
+    }
+
+    #[test]
+    fn fields_are_not_flat() {
+        // Spread across the range rather than collapsing near zero
+        let values: Vec<f32> = points(20_000)
+            .map(|(x, y, z)| simplex_3d(0, x, y, z))
+            .collect();
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+        assert!(mean.abs() < 0.02, "mean {mean}");
+        assert!(variance > 0.04, "variance {variance}");
+    }
+
+    #[test]
+    fn simplex_vanishes_on_lattice() {
+        for i in -20..20 {
+            assert_eq!(simplex_1d(5, i as f32), 0.0);
+        }
+        assert_eq!(simplex_2d(5, 0.0, 0.0), 0.0);
+        assert_eq!(simplex_3d(5, 0.0, 0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn noise_is_continuous() {
+        // A step of 1e-3 lattice units moves the field by at most a few 1e-2
+        for (x, y, z) in points(10_000) {
+            let step = 1e-3;
+            assert!((value_2d(0, x, y) - value_2d(0, x + step, y)).abs() < 0.01);
+            assert!((simplex_2d(0, x, y) - simplex_2d(0, x, y + step)).abs() < 0.02);
+            assert!((simplex_3d(0, x, y, z) - simplex_3d(0, x, y, z + step)).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn seeds_give_independent_fields() {
+        let (same, total) = points(10_000).fold((0, 0), |(same, total), (x, y, _)| {
+            let a = simplex_2d(1, x, y) > 0.0;
+            let b = simplex_2d(2, x, y) > 0.0;
+            (same + (a == b) as u32, total + 1)
+        });
+        let agreement = same as f32 / total as f32;
+        assert!((agreement - 0.5).abs() < 0.05, "agreement {agreement}");
+    }
+
+    #[test]
+    fn curl_is_divergence_free() {
+        let h = 1e-2;
+        for (x, y, z) in points(2_000) {
+            let (x, y, z) = (x * 0.05, y * 0.05, z * 0.05);
+            let div_2d = (curl_2d(0, x + h, y).0 - curl_2d(0, x - h, y).0 + curl_2d(0, x, y + h).1
+                - curl_2d(0, x, y - h).1)
+                / (2.0 * h);
+            let div_3d = (curl_3d(0, x + h, y, z).0 - curl_3d(0, x - h, y, z).0
+                + curl_3d(0, x, y + h, z).1
+                - curl_3d(0, x, y - h, z).1
+                + curl_3d(0, x, y, z + h).2
+                - curl_3d(0, x, y, z - h).2)
+                / (2.0 * h);
+            let magnitude = curl_3d(0, x, y, z).0.abs() + curl_3d(0, x, y, z).1.abs();
+            assert!(div_2d.abs() < 0.05, "2d divergence {div_2d}");
+            assert!(div_3d.abs() < 0.1, "3d divergence {div_3d} vs {magnitude}");
+        }
+    }
+}
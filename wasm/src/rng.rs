@@ -0,0 +1,438 @@
+// Counter-based random numbers: every value is a pure hash of (seed, counter),
+// so any element can be generated on its own, in any order, and the scalar and
+// f32x16/u32x16 variants return identical bits for the same counters.
+
+use std::simd::cmp::{SimdOrd, SimdPartialOrd};
+use std::simd::num::{SimdFloat, SimdInt, SimdUint};
+use std::simd::{f32x16, i32x16, u32x16, Simd, StdFloat};
+
+use crate::math::{
+    cos_approx, cos_approx_simd, ln_approx, ln_approx_simd, sincos_approx, sincos_approx_simd,
+};
+
+const GOLDEN_RATIO: u32 = 0x9e37_79b9;
+
+// Streams used internally by helpers that need more than one value per counter
+const SECOND_STREAM: u32 = 0x6a09_e667;
+const THIRD_STREAM: u32 = 0xbb67_ae85;
+
+// 2^-24: maps the top 24 bits of a hash onto [0, 1) exactly
+const UNIT_SCALE: f32 = 1.0 / (1u32 << 24) as f32;
+
+// Lane offsets for counters first..first + 16
+const LANE_OFFSETS: u32x16 =
+    u32x16::from_array([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+// 32-bit integer finaliser (lowbias32): full avalanche in two multiplies
+#[inline]
+fn mix(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x
+}
+
+#[inline]
+fn mix_lanes<const N: usize>(mut x: Simd<u32, N>) -> Simd<u32, N> {
+    x ^= x >> Simd::splat(16);
+    x *= Simd::splat(0x7feb_352d);
+    x ^= x >> Simd::splat(15);
+    x *= Simd::splat(0x846c_a68b);
+    x ^= x >> Simd::splat(16);
+    x
+}
+
+#[inline]
+fn seed_key(seed: u32) -> u32 {
+    mix(seed.wrapping_add(GOLDEN_RATIO))
+}
+
+/// Random u32 for `counter` in the stream picked by `seed`.
+#[inline]
+pub fn random_u32(seed: u32, counter: u32) -> u32 {
+    let key = seed_key(seed);
+    // Two rounds so different seeds are not just permutations of one stream
+    mix(mix(counter ^ key).wrapping_add(key))
+}
+
+/// `random_u32` for each lane's counter.
+#[inline]
+pub fn random_u32_lanes<const N: usize>(seed: u32, counters: Simd<u32, N>) -> Simd<u32, N> {
+    let key = Simd::splat(seed_key(seed));
+    mix_lanes(mix_lanes(counters ^ key) + key)
+}
+
+/// `random_u32` for counters `first..first + 16`.
+#[inline]
+pub fn random_u32_x16(seed: u32, first: u32) -> u32x16 {
+    random_u32_lanes(seed, u32x16::splat(first) + LANE_OFFSETS)
+}
+
+/// Uniform f32 in [0, 1) with 24 bits of resolution.
+#[inline]
+pub fn random_f32(seed: u32, counter: u32) -> f32 {
+    (random_u32(seed, counter) >> 8) as f32 * UNIT_SCALE
+}
+
+/// `random_f32` for each lane's counter.
+#[inline]
+pub fn random_f32_lanes<const N: usize>(seed: u32, counters: Simd<u32, N>) -> Simd<f32, N> {
+    (random_u32_lanes(seed, counters) >> Simd::splat(8)).cast::<f32>() * Simd::splat(UNIT_SCALE)
+}
+
+/// `random_f32` for counters `first..first + 16`.
+#[inline]
+pub fn random_f32_x16(seed: u32, first: u32) -> f32x16 {
+    random_f32_lanes(seed, u32x16::splat(first) + LANE_OFFSETS)
+}
+
+/// Uniform f32 in [min, max).
+#[inline]
+pub fn random_range(seed: u32, counter: u32, min: f32, max: f32) -> f32 {
+    min + (max - min) * random_f32(seed, counter)
+}
+
+/// `random_range` for each lane's counter.
+#[inline]
+pub fn random_range_lanes<const N: usize>(
+    seed: u32,
+    counters: Simd<u32, N>,
+    min: f32,
+    max: f32,
+) -> Simd<f32, N> {
+    Simd::splat(min) + Simd::splat(max - min) * random_f32_lanes(seed, counters)
+}
+
+/// `random_range` for counters `first..first + 16`.
+#[inline]
+pub fn random_range_x16(seed: u32, first: u32, min: f32, max: f32) -> f32x16 {
+    random_range_lanes(seed, u32x16::splat(first) + LANE_OFFSETS, min, max)
+}
+
+/// Uniformly distributed point on the unit circle.
+pub fn unit_vector_2d(seed: u32, counter: u32) -> (f32, f32) {
+    let angle = random_f32(seed, counter) * std::f32::consts::TAU;
+    let (sin, cos) = sincos_approx(angle);
+    (cos, sin)
+}
+
+/// `unit_vector_2d` for each lane's counter.
+pub fn unit_vector_2d_lanes<const N: usize>(
+    seed: u32,
+    counters: Simd<u32, N>,
+) -> (Simd<f32, N>, Simd<f32, N>) {
+    let angle = random_f32_lanes(seed, counters) * Simd::splat(std::f32::consts::TAU);
+    let (sin, cos) = sincos_approx_simd(angle);
+    (cos, sin)
+}
+
+/// `unit_vector_2d` for counters `first..first + 16`.
+pub fn unit_vector_2d_x16(seed: u32, first: u32) -> (f32x16, f32x16) {
+    unit_vector_2d_lanes(seed, u32x16::splat(first) + LANE_OFFSETS)
+}
+
+/// Uniformly distributed point on the unit sphere.
+pub fn unit_vector_3d(seed: u32, counter: u32) -> (f32, f32, f32) {
+    // Archimedes: z is uniform on [-1, 1] for a uniform sphere
+    let z = 2.0 * random_f32(seed, counter) - 1.0;
+    let angle = random_f32(seed ^ SECOND_STREAM, counter) * std::f32::consts::TAU;
+    let ring = (1.0 - z * z).max(0.0).sqrt();
+    let (sin, cos) = sincos_approx(angle);
+    (ring * cos, ring * sin, z)
+}
+
+/// `unit_vector_3d` for each lane's counter.
+pub fn unit_vector_3d_lanes<const N: usize>(
+    seed: u32,
+    counters: Simd<u32, N>,
+) -> (Simd<f32, N>, Simd<f32, N>, Simd<f32, N>) {
+    let z = Simd::splat(2.0) * random_f32_lanes(seed, counters) - Simd::splat(1.0);
+    let angle =
+        random_f32_lanes(seed ^ SECOND_STREAM, counters) * Simd::splat(std::f32::consts::TAU);
+    let ring = (Simd::splat(1.0) - z * z).simd_max(Simd::splat(0.0)).sqrt();
+    let (sin, cos) = sincos_approx_simd(angle);
+    (ring * cos, ring * sin, z)
+}
+
+/// `unit_vector_3d` for counters `first..first + 16`.
+pub fn unit_vector_3d_x16(seed: u32, first: u32) -> (f32x16, f32x16, f32x16) {
+    unit_vector_3d_lanes(seed, u32x16::splat(first) + LANE_OFFSETS)
+}
+
+/// Standard normal sample (mean 0, deviation 1) via Box-Muller.
+pub fn gaussian(seed: u32, counter: u32) -> f32 {
+    // 1 - u is in (0, 1], so the log stays finite
+    let radius_input = 1.0 - random_f32(seed, counter);
+    let angle = random_f32(seed ^ THIRD_STREAM, counter) * std::f32::consts::TAU;
+    (-2.0 * ln_approx(radius_input)).sqrt() * cos_approx(angle)
+}
+
+/// `gaussian` for each lane's counter.
+pub fn gaussian_lanes<const N: usize>(seed: u32, counters: Simd<u32, N>) -> Simd<f32, N> {
+    let radius_input = Simd::splat(1.0) - random_f32_lanes(seed, counters);
+    let angle =
+        random_f32_lanes(seed ^ THIRD_STREAM, counters) * Simd::splat(std::f32::consts::TAU);
+    (Simd::splat(-2.0) * ln_approx_simd(radius_input)).sqrt() * cos_approx_simd(angle)
+}
+
+/// `gaussian` for counters `first..first + 16`.
+pub fn gaussian_x16(seed: u32, first: u32) -> f32x16 {
+    gaussian_lanes(seed, u32x16::splat(first) + LANE_OFFSETS)
+}
+
+/// Picks indices with probability proportional to their weight.
+pub struct WeightedChoice {
+    cumulative: Vec<f32>,
+    // Last index with a positive weight, the fallback when rounding puts a
+    // draw at or past the total
+    last: usize,
+}
+
+impl WeightedChoice {
+    /// Negative weights count as zero. Returns `None` when no weight is positive.
+    pub fn new(weights: &[f32]) -> Option<Self> {
+        let mut total = 0.0;
+        let cumulative: Vec<f32> = weights
+            .iter()
+            .map(|&weight| {
+                total += weight.max(0.0);
+                total
+            })
+            .collect();
+        let last = weights.iter().rposition(|&weight| weight > 0.0)?;
+        Some(Self { cumulative, last })
+    }
+
+    fn total(&self) -> f32 {
+        *self.cumulative.last().expect("weights are not empty")
+    }
+
+    /// Index picked for `counter` in the stream picked by `seed`.
+    pub fn pick(&self, seed: u32, counter: u32) -> usize {
+        self.index_for(random_f32(seed, counter))
+    }
+
+    // Index for a uniform draw in [0, 1]
+    fn index_for(&self, draw: f32) -> usize {
+        let target = draw * self.total();
+        // Count the buckets ending at or below the target; the same rule as
+        // index_for_x16, so the two agree exactly
+        let index = self.cumulative.iter().filter(|&&end| end <= target).count();
+        index.min(self.last)
+    }
+
+    /// `pick` for counters `first..first + 16`.
+    pub fn pick_x16(&self, seed: u32, first: u32) -> u32x16 {
+        self.index_for_x16(random_f32_x16(seed, first))
+    }
+
+    fn index_for_x16(&self, draw: f32x16) -> u32x16 {
+        let target = draw * f32x16::splat(self.total());
+        let mut index = i32x16::splat(0);
+        for &end in &self.cumulative {
+            // True lanes are -1 as integers
+            index -= f32x16::splat(end).simd_le(target).to_simd();
+        }
+        let index = index.simd_min(i32x16::splat(self.last as i32));
+        index.cast::<u32>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: u32 = 1 << 20;
+
+    fn uniform_samples(seed: u32) -> Vec<f32> {
+        (0..SAMPLES).map(|i| random_f32(seed, i)).collect()
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        for seed in [0, 1, 42, u32::MAX] {
+            for first in [0, 16, 1000, u32::MAX - 20] {
+                let bits = random_u32_x16(seed, first);
+                let uniform = random_f32_x16(seed, first);
+                let range = random_range_x16(seed, first, -3.0, 5.0);
+                let (cx, cy) = unit_vector_2d_x16(seed, first);
+                let (sx, sy, sz) = unit_vector_3d_x16(seed, first);
+                let normal = gaussian_x16(seed, first);
+                for lane in 0..16 {
+                    let counter = first.wrapping_add(lane as u32);
+                    assert_eq!(bits[lane], random_u32(seed, counter));
+                    assert_eq!(uniform[lane].to_bits(), random_f32(seed, counter).to_bits());
+                    assert_eq!(
+                        range[lane].to_bits(),
+                        random_range(seed, counter, -3.0, 5.0).to_bits()
+                    );
+                    let (x, y) = unit_vector_2d(seed, counter);
+                    assert_eq!(
+                        (cx[lane].to_bits(), cy[lane].to_bits()),
+                        (x.to_bits(), y.to_bits())
+                    );
+                    let (x, y, z) = unit_vector_3d(seed, counter);
+                    assert_eq!(
+                        (sx[lane].to_bits(), sy[lane].to_bits(), sz[lane].to_bits()),
+                        (x.to_bits(), y.to_bits(), z.to_bits())
+                    );
+                    assert_eq!(normal[lane].to_bits(), gaussian(seed, counter).to_bits());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn uniform_moments() {
+        let samples = uniform_samples(7);
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|&x| x as f64).sum::<f64>() / n;
+        let variance = samples
+            .iter()
+            .map(|&x| (x as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        // Standard error of the mean is sqrt(1/12 / n) ~ 2.8e-4
+        assert!((mean - 0.5).abs() < 1.5e-3, "mean {mean}");
+        assert!((variance - 1.0 / 12.0).abs() < 1e-3, "variance {variance}");
+        assert!(samples.iter().all(|&x| (0.0..1.0).contains(&x)));
+    }
+
+    #[test]
+    fn uniform_chi_squared() {
+        const BINS: usize = 256;
+        let mut counts = [0u32; BINS];
+        for x in uniform_samples(3) {
+            counts[(x * BINS as f32) as usize] += 1;
+        }
+        let expected = SAMPLES as f64 / BINS as f64;
+        let chi_squared: f64 = counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum();
+        // 255 degrees of freedom: the 99.9th percentile is about 330
+        assert!(chi_squared < 330.0, "chi squared {chi_squared}");
+    }
+
+    #[test]
+    fn consecutive_counters_uncorrelated() {
+        for seed in [0, 9] {
+            let samples = uniform_samples(seed);
+            let n = (samples.len() - 1) as f64;
+            let covariance = samples
+                .windows(2)
+                .map(|pair| (pair[0] as f64 - 0.5) * (pair[1] as f64 - 0.5))
+                .sum::<f64>()
+                / n;
+            let correlation = covariance * 12.0;
+            assert!(correlation.abs() < 5e-3, "correlation {correlation}");
+        }
+    }
+
+    #[test]
+    fn adjacent_seeds_uncorrelated() {
+        let a = uniform_samples(100);
+        let b = uniform_samples(101);
+        let covariance = a
+            .iter()
+            .zip(&b)
+            .map(|(&x, &y)| (x as f64 - 0.5) * (y as f64 - 0.5))
+            .sum::<f64>()
+            / a.len() as f64;
+        assert!((covariance * 12.0).abs() < 5e-3);
+    }
+
+    #[test]
+    fn bits_are_balanced() {
+        let mut ones = [0u32; 32];
+        for i in 0..SAMPLES {
+            let value = random_u32(11, i);
+            for (bit, count) in ones.iter_mut().enumerate() {
+                *count += (value >> bit) & 1;
+            }
+        }
+        for (bit, &count) in ones.iter().enumerate() {
+            let fraction = count as f64 / SAMPLES as f64;
+            assert!((fraction - 0.5).abs() < 3e-3, "bit {bit}: {fraction}");
+        }
+    }
+
+    #[test]
+    fn gaussian_moments() {
+        let samples: Vec<f64> = (0..SAMPLES).map(|i| gaussian(5, i) as f64).collect();
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let within_one = samples.iter().filter(|x| x.abs() <= 1.0).count() as f64 / n;
+        let within_two = samples.iter().filter(|x| x.abs() <= 2.0).count() as f64 / n;
+        assert!(mean.abs() < 5e-3, "mean {mean}");
+        assert!((variance - 1.0).abs() < 1e-2, "variance {variance}");
+        assert!(
+            (within_one - 0.6827).abs() < 3e-3,
+            "within 1 sigma {within_one}"
+        );
+        assert!(
+            (within_two - 0.9545).abs() < 2e-3,
+            "within 2 sigma {within_two}"
+        );
+        assert!(samples.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn unit_vectors_are_unit_and_unbiased() {
+        let (mut sum_x, mut sum_y, mut sum_z) = (0.0f64, 0.0f64, 0.0f64);
+        for i in 0..SAMPLES {
+            let (x, y) = unit_vector_2d(1, i);
+            assert!(((x * x + y * y) - 1.0).abs() < 1e-5);
+            let (x, y, z) = unit_vector_3d(1, i);
+            assert!(((x * x + y * y + z * z) - 1.0).abs() < 1e-5);
+            sum_x += x as f64;
+            sum_y += y as f64;
+            sum_z += z as f64;
+        }
+        let n = SAMPLES as f64;
+        // Each component has variance 1/3, so the standard error is ~5.6e-4
+        for mean in [sum_x / n, sum_y / n, sum_z / n] {
+            assert!(mean.abs() < 3e-3, "mean {mean}");
+        }
+    }
+
+    #[test]
+    fn weighted_choice_frequencies() {
+        let weights = [1.0, 0.0, 3.0, -2.0, 4.0];
+        let choice = WeightedChoice::new(&weights).unwrap();
+        let mut counts = [0u32; 5];
+        for first in (0..SAMPLES).step_by(16) {
+            let picks = choice.pick_x16(2, first);
+            for lane in 0..16 {
+                let index = picks[lane] as usize;
+                assert_eq!(index, choice.pick(2, first + lane as u32));
+                counts[index] += 1;
+            }
+        }
+        assert_eq!(counts[1], 0);
+        assert_eq!(counts[3], 0);
+        for (index, expected) in [(0, 0.125), (2, 0.375), (4, 0.5)] {
+            let fraction = counts[index] as f64 / SAMPLES as f64;
+            assert!((fraction - expected).abs() < 3e-3, "{index}: {fraction}");
+        }
+        assert!(WeightedChoice::new(&[0.0, -1.0]).is_none());
+        assert!(WeightedChoice::new(&[]).is_none());
+    }
+
+    #[test]
+    fn weighted_choice_never_falls_back_to_a_zero_weight() {
+        let choice = WeightedChoice::new(&[1.0, 0.0]).unwrap();
+        for draw in [0.0, 0.5, 1.0 - f32::EPSILON, 1.0] {
+            assert_eq!(choice.index_for(draw), 0, "{draw}");
+            assert!(choice.index_for_x16(f32x16::splat(draw)) == u32x16::splat(0));
+        }
+
+        let choice = WeightedChoice::new(&[0.0, 2.0, 0.0, 0.0]).unwrap();
+        for draw in [0.0, 1.0 - f32::EPSILON, 1.0] {
+            assert_eq!(choice.index_for(draw), 1, "{draw}");
+        }
+    }
+}
@@ -1,424 +1,3607 @@
 use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
 use web_sys::console;
 
-use std::simd::{f32x16, num::SimdFloat};
+use std::simd::cmp::SimdPartialOrd;
+use std::simd::{num::SimdFloat, Select, StdFloat};
+
+use crate::batch::{for_each_batch, Batch, F32s, LaneMask, U8s};
+use crate::color::{
+    convert_buffers, ColorFormat, ColorSpace, Gradient, InterpolationSpace, PackedColors,
+};
+use crate::distance_field::inside_distance;
+use crate::engine::stages::{Frame, Pipeline, Simulation};
+use crate::engine::{particle_attributes, Flags, ParticleBuffers, ParticleStorage};
+use crate::glyph::rasterize_text;
+use crate::noise::{curl_3d_lanes, value_2d};
+use crate::rng::{random_f32, random_u32};
+use crate::tween::{CubicBezier, Curve, Easing};
+use crate::vector::{
+    contour_bounds, fit_raster_scale, parse_svg_path, rasterize_coverage, sample_contours, Contour,
+    PathBuilder,
+};
 
-use crate::math::seed_random;
-
-const SIMD_BATCH_SIZE: usize = 16;
 const MAX_PARTICLES: usize = 10000;
+const MAX_TRAIL_LENGTH: usize = 32;
+
+// Flattening tolerance (in output pixels) and border for vector shapes
+const VECTOR_FLATTEN_TOLERANCE: f32 = 0.2;
+const VECTOR_PADDING: f32 = 2.0;
+
+// Distance (in pixels) under which a forming particle counts as settled
+const SETTLE_EPSILON: f32 = 0.5;
+
+// Random streams, one per randomised particle attribute
+const FORMATION_STREAM: u32 = 1;
+// Spawn coordinates: x or polar angle, then y or polar radius
+const SPAWN_U_STREAM: u32 = 2;
+const SPAWN_V_STREAM: u32 = 3;
+// Interior thinning, keyed by source pixel rather than particle
+const DENSITY_STREAM: u32 = 4;
+const SIZE_STREAM: u32 = 5;
+const ROTATION_STREAM: u32 = 6;
+const SCATTER_ANGLE_STREAM: u32 = 7;
+const SCATTER_SPEED_STREAM: u32 = 8;
+
+// Console warning in the browser; native builds (tests) have no console to call
+fn warn(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    console::warn_1(&message.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
 
+// Instance behind the free-function API
 thread_local! {
-    static SCATTER_TEXT_STATE: RefCell<Option<ScatterTextState>> = RefCell::new(None);
+    static DEFAULT_SCATTER_TEXT: RefCell<ScatterText> = const { RefCell::new(ScatterText::empty()) };
 }
 
-#[repr(C)]
-struct ScatterTextState {
-    // Particle positions (current)
-    positions_x: Vec<f32>,
-    positions_y: Vec<f32>,
-
-    // Target positions (text formation)
-    target_x: Vec<f32>,
-    target_y: Vec<f32>,
-
-    // Scatter velocities (pre-calculated)
-    scatter_vx: Vec<f32>,
-    scatter_vy: Vec<f32>,
+/// Lifecycle phase of a single scatter text particle, stored as one byte per particle.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticlePhase {
+    Idle = 0,
+    Forming = 1,
+    Formed = 2,
+    Scattering = 3,
+    Dead = 4,
+}
 
-    // Visual properties
-    colors_r: Vec<f32>,
-    colors_g: Vec<f32>,
-    colors_b: Vec<f32>,
-    opacity: Vec<f32>,
+/// Order in which particles start easing toward their targets after `start_forming`.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormationOrder {
+    /// Every particle starts at once
+    Simultaneous = 0,
+    /// Typewriter sweep by target x
+    LeftToRight = 1,
+    /// Bloom outward from the centre of the text
+    Radial = 2,
+    /// Row by row, top to bottom
+    Rows = 3,
+    /// Seeded random delay per particle
+    Random = 4,
+}
 
-    // State flags (bitpacked)
-    scattered_flags: Vec<u64>,
+// Staggered formation: per-particle start delays of up to `spread` seconds
+#[derive(Clone, Copy)]
+struct FormationConfig {
+    order: FormationOrder,
+    spread: f32,
+    seed: u32,
+}
 
-    // Control parameters
-    particle_count: usize,
-    forming: bool,
-    easing_factor: f32,
-    fade_rate: f32,
-    scatter_speed: f32,
+impl FormationConfig {
+    const fn new() -> Self {
+        Self {
+            order: FormationOrder::Simultaneous,
+            spread: 0.0,
+            seed: 0,
+        }
+    }
 }
 
+/// Where particles start before easing toward the text.
 #[wasm_bindgen]
-pub struct ScatterTextPointers {
-    pub positions_x_ptr: u32,
-    pub positions_y_ptr: u32,
-    pub target_x_ptr: u32,
-    pub target_y_ptr: u32,
-    pub scatter_vx_ptr: u32,
-    pub scatter_vy_ptr: u32,
-    pub colors_r_ptr: u32,
-    pub colors_g_ptr: u32,
-    pub colors_b_ptr: u32,
-    pub opacity_ptr: u32,
-    pub scattered_flags_ptr: u32,
-    pub particle_count: usize,
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnMode {
+    /// Uniformly over the whole canvas
+    Canvas = 0,
+    /// From a point (e.g. a clicked button), jittered within the spawn radius
+    Point = 1,
+    /// From a random position along the canvas edges
+    Edges = 2,
+    /// From a ring around the text centre
+    Ring = 3,
+    /// From the previous text's particle positions, so re-forming does not jump
+    Previous = 4,
+    /// From positions supplied through `set_spawn_positions`
+    Custom = 5,
 }
 
-// Internal initialization - no longer exported
-fn initialize_scatter_text_internal(particle_count: usize) -> ScatterTextPointers {
-    let aligned_count = particle_count.div_ceil(SIMD_BATCH_SIZE) * SIMD_BATCH_SIZE;
-    let flag_count = aligned_count.div_ceil(64);
-
-    let state = ScatterTextState {
-        positions_x: vec![0.0; aligned_count],
-        positions_y: vec![0.0; aligned_count],
-        target_x: vec![0.0; aligned_count],
-        target_y: vec![0.0; aligned_count],
-        scatter_vx: vec![0.0; aligned_count],
-        scatter_vy: vec![0.0; aligned_count],
-        colors_r: vec![1.0; aligned_count],
-        colors_g: vec![1.0; aligned_count],
-        colors_b: vec![1.0; aligned_count],
-        opacity: vec![1.0; aligned_count],
-        scattered_flags: vec![0u64; flag_count],
-        particle_count,
-        forming: false,
-        easing_factor: 0.08,
-        fade_rate: 0.02,
-        scatter_speed: 3.0,
-    };
-
-    let pointers = ScatterTextPointers {
-        positions_x_ptr: state.positions_x.as_ptr() as u32,
-        positions_y_ptr: state.positions_y.as_ptr() as u32,
-        target_x_ptr: state.target_x.as_ptr() as u32,
-        target_y_ptr: state.target_y.as_ptr() as u32,
-        scatter_vx_ptr: state.scatter_vx.as_ptr() as u32,
-        scatter_vy_ptr: state.scatter_vy.as_ptr() as u32,
-        colors_r_ptr: state.colors_r.as_ptr() as u32,
-        colors_g_ptr: state.colors_g.as_ptr() as u32,
-        colors_b_ptr: state.colors_b.as_ptr() as u32,
-        opacity_ptr: state.opacity.as_ptr() as u32,
-        scattered_flags_ptr: state.scattered_flags.as_ptr() as u32,
-        particle_count,
-    };
-
-    SCATTER_TEXT_STATE.with(|cell| {
-        *cell.borrow_mut() = Some(state);
-    });
+#[derive(Clone)]
+struct SpawnConfig {
+    mode: SpawnMode,
+    origin_x: f32,
+    origin_y: f32,
+    radius: f32,
+    custom_positions: Vec<f32>,
+}
 
-    pointers
+impl SpawnConfig {
+    const fn new() -> Self {
+        Self {
+            mode: SpawnMode::Canvas,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            radius: 0.0,
+            custom_positions: Vec::new(),
+        }
+    }
 }
 
-#[wasm_bindgen]
-pub fn set_text_pixels(
-    pixel_data: &[u8],
-    width: u32,
-    height: u32,
+// Geometry needed to place a spawn point
+struct SpawnContext<'a> {
     canvas_width: f32,
     canvas_height: f32,
-    skip: u32,
-) -> usize {
-    // First pass: count visible pixels to determine particle count
-    let mut particle_count = 0;
-    let skip_size = skip as usize;
-
-    for y in (0..height as usize).step_by(skip_size) {
-        for x in (0..width as usize).step_by(skip_size) {
-            let index = (y * width as usize + x) * 4;
-
-            if index + 3 < pixel_data.len() {
-                let alpha = pixel_data[index + 3];
-                if alpha > 128 && particle_count < MAX_PARTICLES {
-                    particle_count += 1;
-                }
+    text_width: f32,
+    text_height: f32,
+    previous: Option<(&'a [f32], &'a [f32])>,
+}
+
+fn spawn_position(config: &SpawnConfig, ctx: &SpawnContext, index: usize) -> (f32, f32) {
+    let i = index as u32;
+    let u = random_f32(SPAWN_U_STREAM, i);
+    let v = random_f32(SPAWN_V_STREAM, i);
+    let uniform = || (u * ctx.canvas_width, v * ctx.canvas_height);
+
+    match config.mode {
+        SpawnMode::Canvas => uniform(),
+        SpawnMode::Point => {
+            let angle = u * std::f32::consts::PI * 2.0;
+            let r = config.radius * v.sqrt();
+            (
+                config.origin_x + angle.cos() * r,
+                config.origin_y + angle.sin() * r,
+            )
+        }
+        SpawnMode::Edges => {
+            let (w, h) = (ctx.canvas_width, ctx.canvas_height);
+            let t = u * 2.0 * (w + h);
+            if t < w {
+                (t, 0.0)
+            } else if t < w + h {
+                (w, t - w)
+            } else if t < 2.0 * w + h {
+                (2.0 * w + h - t, h)
+            } else {
+                (0.0, 2.0 * (w + h) - t)
+            }
+        }
+        SpawnMode::Ring => {
+            let base =
+                (ctx.text_width * ctx.text_width + ctx.text_height * ctx.text_height).sqrt() / 2.0;
+            let radius = config.radius.max(base);
+            let angle = u * std::f32::consts::PI * 2.0;
+            (
+                ctx.canvas_width / 2.0 + angle.cos() * radius,
+                ctx.canvas_height / 2.0 + angle.sin() * radius,
+            )
+        }
+        SpawnMode::Previous => match ctx.previous {
+            Some((xs, ys)) if !xs.is_empty() => (xs[index % xs.len()], ys[index % ys.len()]),
+            _ => uniform(),
+        },
+        SpawnMode::Custom => {
+            let pairs = config.custom_positions.len() / 2;
+            if pairs == 0 {
+                return uniform();
             }
+            let p = (index % pairs) * 2;
+            (config.custom_positions[p], config.custom_positions[p + 1])
         }
     }
+}
 
-    if particle_count >= MAX_PARTICLES {
-        console::warn_1(&format!("ScatterText: hit MAX_PARTICLES limit ({}), some pixels were dropped", MAX_PARTICLES).into());
-    }
+/// How sampled pixels are turned into particle colours.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Copy the source pixel's RGB
+    Source = 0,
+    /// Use the flat override colour
+    Flat = 1,
+    /// Colour stops across the text, left to right
+    HorizontalGradient = 2,
+    /// Colour stops across the text, top to bottom
+    VerticalGradient = 3,
+    /// Colour stops from the text centre outward
+    RadialGradient = 4,
+    /// Palette entry picked by source pixel luminance
+    LuminancePalette = 5,
+}
 
-    // Initialize memory with exact particle count
-    initialize_scatter_text_internal(particle_count);
+/// Which opaque pixels become particles.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplingMode {
+    /// Every opaque pixel on the sampling grid
+    Fill = 0,
+    /// Full density near glyph edges, thinning toward the interior
+    Outline = 1,
+    /// Evenly spaced points along the glyph edges only
+    Contour = 2,
+}
 
-    // Second pass: set particle data
-    SCATTER_TEXT_STATE.with(|cell| {
-        let mut state_ref = cell.borrow_mut();
-        let state = state_ref
-            .as_mut()
-            .expect("ScatterText state should be initialized");
+#[derive(Clone)]
+struct SamplingConfig {
+    alpha_threshold: u8,
+    sampling_mode: SamplingMode,
+    // Edge band (pixels) kept at full density in Outline mode
+    outline_width: f32,
+    // Density deep inside glyphs in Outline mode (0..1)
+    interior_density: f32,
+    opacity_from_alpha: bool,
+    color_mode: ColorMode,
+    // Colours below are sRGB; particles are written in `color_space`
+    color_space: ColorSpace,
+    flat_color: [f32; 3],
+    gradient: Option<Gradient>,
+    gradient_space: InterpolationSpace,
+    // Flattened [r, g, b] entries, darkest luminance first
+    palette: Vec<f32>,
+}
 
-        let mut particle_index = 0;
+impl SamplingConfig {
+    const fn new() -> Self {
+        Self {
+            alpha_threshold: 128,
+            sampling_mode: SamplingMode::Fill,
+            outline_width: 3.0,
+            interior_density: 0.0,
+            opacity_from_alpha: false,
+            color_mode: ColorMode::Source,
+            color_space: ColorSpace::Srgb,
+            flat_color: [1.0, 1.0, 1.0],
+            gradient: None,
+            gradient_space: InterpolationSpace::Srgb,
+            palette: Vec::new(),
+        }
+    }
 
-        // Clear scattered flags
-        for flag in state.scattered_flags.iter_mut() {
-            *flag = 0;
+    fn sample_color(&self, rgb: [f32; 3], u: f32, v: f32) -> [f32; 3] {
+        match self.color_mode {
+            ColorMode::Source => rgb,
+            ColorMode::Flat => self.flat_color,
+            ColorMode::HorizontalGradient => self.gradient_at(u, rgb),
+            ColorMode::VerticalGradient => self.gradient_at(v, rgb),
+            ColorMode::RadialGradient => {
+                let (du, dv) = (u - 0.5, v - 0.5);
+                self.gradient_at(
+                    (du * du + dv * dv).sqrt() / std::f32::consts::FRAC_1_SQRT_2,
+                    rgb,
+                )
+            }
+            ColorMode::LuminancePalette => {
+                let entries = self.palette.len() / 3;
+                if entries == 0 {
+                    return rgb;
+                }
+                let luminance = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+                let p = ((luminance * entries as f32) as usize).min(entries - 1) * 3;
+                [self.palette[p], self.palette[p + 1], self.palette[p + 2]]
+            }
         }
+    }
+
+    fn gradient_at(&self, t: f32, fallback: [f32; 3]) -> [f32; 3] {
+        self.gradient
+            .as_ref()
+            .map_or(fallback, |gradient| gradient.sample(t))
+    }
+}
 
-        // Calculate offsets to center the text in the canvas
-        let text_center_x = (width as f32) / 2.0;
-        let text_center_y = (height as f32) / 2.0;
-        let canvas_center_x = canvas_width / 2.0;
-        let canvas_center_y = canvas_height / 2.0;
-
-        // Sample pixels from the image data
-        for y in (0..height as usize).step_by(skip_size) {
-            for x in (0..width as usize).step_by(skip_size) {
-                let index = (y * width as usize + x) * 4;
-
-                if index + 3 < pixel_data.len() {
-                    let alpha = pixel_data[index + 3];
-
-                    // If pixel is visible
-                    if alpha > 128 && particle_index < particle_count {
-                        // Set target position centered in canvas
-                        state.target_x[particle_index] =
-                            (x as f32 - text_center_x) + canvas_center_x;
-                        state.target_y[particle_index] =
-                            (y as f32 - text_center_y) + canvas_center_y;
-
-                        // Set random starting position
-                        state.positions_x[particle_index] =
-                            seed_random(particle_index as i32 + 1000) * canvas_width;
-                        state.positions_y[particle_index] =
-                            seed_random(particle_index as i32 + 2000) * canvas_height;
-
-                        // Set color
-                        state.colors_r[particle_index] = (pixel_data[index] as f32) / 255.0;
-                        state.colors_g[particle_index] = (pixel_data[index + 1] as f32) / 255.0;
-                        state.colors_b[particle_index] = (pixel_data[index + 2] as f32) / 255.0;
-
-                        // Reset opacity
-                        state.opacity[particle_index] = 1.0;
-
-                        // Pre-calculate scatter velocity
-                        let angle = seed_random(particle_index as i32 + 3000) * std::f32::consts::PI * 2.0;
-                        let speed = (seed_random(particle_index as i32 + 4000) * state.scatter_speed) + 1.0;
-                        state.scatter_vx[particle_index] = angle.cos() * speed;
-                        state.scatter_vy[particle_index] = angle.sin() * speed;
-
-                        particle_index += 1;
+// Grid positions (x, y) of the pixels that become particles, in row-major order
+fn collect_sample_points(
+    alpha: impl Fn(usize) -> u8,
+    width: usize,
+    height: usize,
+    skip: usize,
+    sampling: &SamplingConfig,
+) -> Vec<(usize, usize)> {
+    let skip = skip.max(1);
+    let inside: Vec<bool> = (0..width * height)
+        .map(|i| alpha(i) > sampling.alpha_threshold)
+        .collect();
+
+    let mut samples = Vec::new();
+    match sampling.sampling_mode {
+        SamplingMode::Fill => {
+            for y in (0..height).step_by(skip) {
+                for x in (0..width).step_by(skip) {
+                    if inside[y * width + x] {
+                        samples.push((x, y));
                     }
                 }
             }
         }
+        SamplingMode::Outline => {
+            let distance = inside_distance(&inside, width, height);
+            let band = sampling.outline_width.max(1.0);
+            for y in (0..height).step_by(skip) {
+                for x in (0..width).step_by(skip) {
+                    let i = y * width + x;
+                    if !inside[i] {
+                        continue;
+                    }
+                    let d = distance[i];
+                    // Linear falloff over a second band width, then flat
+                    let falloff = (1.0 - (d - band) / band).clamp(0.0, 1.0);
+                    let density =
+                        sampling.interior_density + (1.0 - sampling.interior_density) * falloff;
+                    if density >= 1.0 || random_f32(DENSITY_STREAM, i as u32) < density {
+                        samples.push((x, y));
+                    }
+                }
+            }
+        }
+        SamplingMode::Contour => {
+            // Greedy Poisson-disk pass over the one-pixel edge band, `skip` apart
+            let distance = inside_distance(&inside, width, height);
+            let cell = skip;
+            let grid_w = width.div_ceil(cell);
+            let grid_h = height.div_ceil(cell);
+            let mut occupied: Vec<Option<(usize, usize)>> = vec![None; grid_w * grid_h];
+            let min_sq = (skip * skip) as isize;
+
+            for y in 0..height {
+                for x in 0..width {
+                    let i = y * width + x;
+                    if !inside[i] || distance[i] > 1.5 {
+                        continue;
+                    }
+                    let (gx, gy) = (x / cell, y / cell);
+                    let too_close = (gy.saturating_sub(1)..(gy + 2).min(grid_h)).any(|ny| {
+                        (gx.saturating_sub(1)..(gx + 2).min(grid_w)).any(|nx| {
+                            occupied[ny * grid_w + nx].is_some_and(|(px, py)| {
+                                let dx = px as isize - x as isize;
+                                let dy = py as isize - y as isize;
+                                dx * dx + dy * dy < min_sq
+                            })
+                        })
+                    });
+                    if !too_close && occupied[gy * grid_w + gx].is_none() {
+                        occupied[gy * grid_w + gx] = Some((x, y));
+                        samples.push((x, y));
+                    }
+                }
+            }
+        }
+    }
+    samples
+}
 
-        state.particle_count = particle_index;
-        state.forming = true;
-    });
-
-    particle_count
+/// How particles leave the text after `start_scattering`.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScatterMode {
+    /// Random radial burst with friction
+    Radial = 0,
+    /// Burst away from a point, e.g. the click position
+    Explode = 1,
+    /// Directional sweep with turbulence, upwind particles leaving first
+    Wind = 2,
+    /// Fall under gravity, bouncing off a floor
+    Gravity = 3,
+    /// Fade out in noise order with very little motion
+    Dissolve = 4,
 }
 
+// Frequency of the wind turbulence and dissolve noise, per pixel
+const SCATTER_NOISE_FREQUENCY: f32 = 0.02;
+
+// Noise stream ordering the dissolve
+const DISSOLVE_NOISE_SEED: u32 = 0x00d1_550f;
+
+// Noise stream for the wind turbulence, and its evolution rate per second
+const WIND_NOISE_SEED: u32 = 0x0077_1d00;
+const WIND_NOISE_RATE: f32 = 2.0;
+
+// Per-frame velocity damping for the burst modes
+const SCATTER_FRICTION: f32 = 0.98;
+
+/// What sets each particle's base size between the configured min and max.
 #[wasm_bindgen]
-pub fn get_scatter_text_pointers() -> ScatterTextPointers {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let state_ref = cell.borrow();
-        let state = state_ref.as_ref().expect("ScatterText not initialized");
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeSource {
+    /// Every particle gets the max size
+    Uniform = 0,
+    /// Source pixel alpha, so anti-aliased edges become smaller particles
+    Alpha = 1,
+    /// Source pixel luminance
+    Luminance = 2,
+    /// Random within the bounds
+    Random = 3,
+}
 
-        ScatterTextPointers {
-            positions_x_ptr: state.positions_x.as_ptr() as u32,
-            positions_y_ptr: state.positions_y.as_ptr() as u32,
-            target_x_ptr: state.target_x.as_ptr() as u32,
-            target_y_ptr: state.target_y.as_ptr() as u32,
-            scatter_vx_ptr: state.scatter_vx.as_ptr() as u32,
-            scatter_vy_ptr: state.scatter_vy.as_ptr() as u32,
-            colors_r_ptr: state.colors_r.as_ptr() as u32,
-            colors_g_ptr: state.colors_g.as_ptr() as u32,
-            colors_b_ptr: state.colors_b.as_ptr() as u32,
-            opacity_ptr: state.opacity.as_ptr() as u32,
-            scattered_flags_ptr: state.scattered_flags.as_ptr() as u32,
-            particle_count: state.particle_count,
-        }
-    })
+// Distance from the target at which the forming swell is at its largest
+const SWELL_DISTANCE: f32 = 100.0;
+
+// Particle size and rotation settings
+#[derive(Clone, Copy)]
+struct SizeConfig {
+    source: SizeSource,
+    min_size: f32,
+    max_size: f32,
+    // Extra size fraction for particles far from their target while forming
+    swell: f32,
+    // Shrink with opacity while scattering
+    shrink: bool,
+    random_rotation: bool,
+    // Rotation speed while scattering, radians per second
+    spin: f32,
 }
 
-#[wasm_bindgen]
-pub fn start_forming() {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let mut state_ref = cell.borrow_mut();
-        if let Some(state) = state_ref.as_mut() {
-            state.forming = true;
-            // Reset scattered flags when forming
-            for flag in state.scattered_flags.iter_mut() {
-                *flag = 0;
+impl SizeConfig {
+    const fn new() -> Self {
+        Self {
+            source: SizeSource::Uniform,
+            min_size: 1.0,
+            max_size: 1.0,
+            swell: 0.0,
+            shrink: false,
+            random_rotation: false,
+            spin: 0.0,
+        }
+    }
+
+    fn base_size(&self, sample: &SamplePoint, index: usize) -> f32 {
+        let t = match self.source {
+            SizeSource::Uniform => 1.0,
+            SizeSource::Alpha => sample.alpha as f32 / 255.0,
+            SizeSource::Luminance => {
+                let [r, g, b] = sample.rgb;
+                0.2126 * r + 0.7152 * g + 0.0722 * b
             }
+            SizeSource::Random => random_f32(SIZE_STREAM, index as u32),
+        };
+        self.min_size + (self.max_size - self.min_size) * t
+    }
+}
+
+// Noise stream for the idle drift
+const DRIFT_NOISE_SEED: u32 = 0x00d7_1f70;
+
+// Typical peak length of the curl field; `curl_field_simd` maps it to 1 and
+// clamps the rarer, stronger extremes
+const CURL_FIELD_PEAK: f32 = 6.0;
+
+// Idle motion of formed particles around their targets
+#[derive(Clone, Copy)]
+struct DriftConfig {
+    // Peak offset from the target in pixels; 0 disables drift
+    amplitude: f32,
+    // Noise frequency in lattice cells per pixel
+    frequency: f32,
+    // Speed multiplier for the field's evolution
+    time_scale: f32,
+}
+
+impl DriftConfig {
+    const fn new() -> Self {
+        Self {
+            amplitude: 0.0,
+            frequency: 0.02,
+            time_scale: 1.0,
         }
-    });
+    }
+
+    fn enabled(&self) -> bool {
+        self.amplitude > 0.0
+    }
 }
 
-#[wasm_bindgen]
-pub fn start_scattering() {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let mut state_ref = cell.borrow_mut();
-        if let Some(state) = state_ref.as_mut() {
-            state.forming = false;
+// Timed formation: particles follow `curve` from where they started to their
+// target over `duration` seconds, instead of easing exponentially
+#[derive(Clone, Copy)]
+struct FormCurve {
+    curve: Curve,
+    duration: f32,
+}
+
+// Planar slice of 3D curl noise with time as the third axis, so the flow keeps
+// changing without repeating. Lengths are scaled to at most 1.
+fn curl_field_simd(seed: u32, x: F32s, y: F32s, time: f32) -> (F32s, F32s) {
+    let (curl_x, curl_y, _) = curl_3d_lanes(seed, x, y, F32s::splat(time));
+    let length = (curl_x * curl_x + curl_y * curl_y).sqrt();
+    let scale = F32s::splat(1.0) / length.simd_max(F32s::splat(CURL_FIELD_PEAK));
+    (curl_x * scale, curl_y * scale)
+}
+
+// Neighbouring targets sample nearby noise, so particles swirl together instead
+// of jittering independently. Evaluated at the target, so the offset never
+// accumulates.
+fn drift_offset_simd(config: &DriftConfig, x: F32s, y: F32s, time: f32) -> (F32s, F32s) {
+    let k = F32s::splat(config.frequency);
+    let (flow_x, flow_y) = curl_field_simd(DRIFT_NOISE_SEED, x * k, y * k, time);
+    let amplitude = F32s::splat(config.amplitude);
+    (flow_x * amplitude, flow_y * amplitude)
+}
+
+#[derive(Clone, Copy)]
+struct ScatterConfig {
+    mode: ScatterMode,
+    // Explode origin
+    origin_x: f32,
+    origin_y: f32,
+    // Wind direction (normalised)
+    direction_x: f32,
+    direction_y: f32,
+    // Initial speed in pixels per second (Explode, Wind, Gravity)
+    strength: f32,
+    // Wind turbulence amplitude in pixels per second
+    turbulence: f32,
+    // Gravity acceleration in pixels per second squared
+    gravity: f32,
+    floor_y: f32,
+    restitution: f32,
+    // Time over which staggered modes (Wind, Dissolve) start every particle
+    duration: f32,
+}
+
+impl ScatterConfig {
+    const fn new() -> Self {
+        Self {
+            mode: ScatterMode::Radial,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            direction_x: 1.0,
+            direction_y: 0.0,
+            strength: 0.0,
+            turbulence: 0.0,
+            gravity: 0.0,
+            floor_y: 0.0,
+            restitution: 0.0,
+            duration: 0.0,
         }
-    });
+    }
 }
 
 #[wasm_bindgen]
-pub fn update_particles(delta_time: f32) {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let mut state_ref = cell.borrow_mut();
-        let state = state_ref.as_mut().expect("ScatterText not initialized");
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HorizontalAlign {
+    Left = 0,
+    Center = 1,
+    Right = 2,
+}
 
-        let count = state.particle_count;
-        if count == 0 {
-            return;
-        }
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top = 0,
+    Middle = 1,
+    Bottom = 2,
+}
 
-        // Process particles in SIMD batches
-        let simd_chunks = count / SIMD_BATCH_SIZE;
+/// How the source image is scaled into the padded canvas area.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FitMode {
+    /// Fixed scale (times DPR), regardless of canvas size
+    Fixed = 0,
+    /// Largest scale that fits entirely inside the area
+    Contain = 1,
+    /// Smallest scale that covers the whole area
+    Cover = 2,
+    /// Fixed scale, shrunk like `Contain` when the text would overflow
+    ScaleDown = 3,
+}
 
-        for chunk in 0..simd_chunks {
-            let base = chunk * SIMD_BATCH_SIZE;
-            update_particle_batch_simd(state, base, delta_time);
-        }
+#[derive(Clone, Copy)]
+struct LayoutConfig {
+    h_align: HorizontalAlign,
+    v_align: VerticalAlign,
+    fit: FitMode,
+    scale: f32,
+    padding: f32,
+    dpr: f32,
+}
 
-        // Handle remaining particles
-        let remaining_start = simd_chunks * SIMD_BATCH_SIZE;
-        for i in remaining_start..count {
-            update_particle_scalar(state, i, delta_time);
+impl LayoutConfig {
+    const fn new() -> Self {
+        Self {
+            h_align: HorizontalAlign::Center,
+            v_align: VerticalAlign::Middle,
+            fit: FitMode::Fixed,
+            scale: 1.0,
+            padding: 0.0,
+            dpr: 1.0,
         }
-    });
+    }
+
+    // Scale and offset mapping source pixels onto the canvas
+    fn transform(
+        &self,
+        source_width: f32,
+        source_height: f32,
+        canvas_width: f32,
+        canvas_height: f32,
+    ) -> (f32, f32, f32) {
+        let padding = self.padding * self.dpr;
+        let area_w = (canvas_width - padding * 2.0).max(0.0);
+        let area_h = (canvas_height - padding * 2.0).max(0.0);
+        let fit_x = area_w / source_width.max(1.0);
+        let fit_y = area_h / source_height.max(1.0);
+        let fixed = self.scale * self.dpr;
+
+        let scale = match self.fit {
+            FitMode::Fixed => fixed,
+            FitMode::Contain => fit_x.min(fit_y),
+            FitMode::Cover => fit_x.max(fit_y),
+            FitMode::ScaleDown => fixed.min(fit_x.min(fit_y)),
+        };
+
+        let anchor = |align: f32, area: f32, size: f32| padding + (area - size * scale) * align;
+        let align_x = match self.h_align {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => 0.5,
+            HorizontalAlign::Right => 1.0,
+        };
+        let align_y = match self.v_align {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Middle => 0.5,
+            VerticalAlign::Bottom => 1.0,
+        };
+
+        (
+            scale,
+            anchor(align_x, area_w, source_width),
+            anchor(align_y, area_h, source_height),
+        )
+    }
 }
 
-fn update_particle_batch_simd(state: &mut ScatterTextState, base: usize, delta_time: f32) {
-    // Load current positions
-    let pos_x = f32x16::from_slice(&state.positions_x[base..base + SIMD_BATCH_SIZE]);
-    let pos_y = f32x16::from_slice(&state.positions_y[base..base + SIMD_BATCH_SIZE]);
+impl ParticlePhase {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => ParticlePhase::Forming,
+            2 => ParticlePhase::Formed,
+            3 => ParticlePhase::Scattering,
+            4 => ParticlePhase::Dead,
+            _ => ParticlePhase::Idle,
+        }
+    }
+}
 
-    if state.forming {
-        // Load target positions
-        let target_x = f32x16::from_slice(&state.target_x[base..base + SIMD_BATCH_SIZE]);
-        let target_y = f32x16::from_slice(&state.target_y[base..base + SIMD_BATCH_SIZE]);
+particle_attributes! {
+    /// Per-particle columns shared with JS.
+    struct TextParticles {
+        // Particle positions (current)
+        positions_x: Vec<f32> = 0.0,
+        positions_y: Vec<f32> = 0.0,
+
+        // Target positions (text formation)
+        target_x: Vec<f32> = 0.0,
+        target_y: Vec<f32> = 0.0,
+
+        // Scatter velocities (pre-calculated)
+        scatter_vx: Vec<f32> = 0.0,
+        scatter_vy: Vec<f32> = 0.0,
+
+        // Visual properties
+        colors_r: Vec<f32> = 1.0,
+        colors_g: Vec<f32> = 1.0,
+        colors_b: Vec<f32> = 1.0,
+        opacity: Vec<f32> = 1.0,
+        // Opacity restored while forming (source alpha or 1.0)
+        base_opacity: Vec<f32> = 1.0,
+        // Rendered size and rotation (radians), animated from base_size
+        sizes: Vec<f32> = 1.0,
+        base_size: Vec<f32> = 1.0,
+        rotations: Vec<f32> = 0.0,
+
+        // State flags (bitpacked)
+        scattered_flags: Flags = false,
+
+        // Per-particle lifecycle phase (ParticlePhase as u8)
+        phases: Vec<u8> = ParticlePhase::Idle as u8,
+
+        // Sample position in the source image, kept so layout can be redone on resize
+        source_x: Vec<f32> = 0.0,
+        source_y: Vec<f32> = 0.0,
+
+        // Per-particle formation start delay (seconds)
+        start_delay: Vec<f32> = 0.0,
+        // Per-particle delay before scatter motion and fading begin (seconds)
+        scatter_delay: Vec<f32> = 0.0,
+
+        // Curve-driven formation starts from these positions
+        form_from_x: Vec<f32> = 0.0,
+        form_from_y: Vec<f32> = 0.0,
+    }
+}
 
-        // Calculate deltas
-        let dx = target_x - pos_x;
-        let dy = target_y - pos_y;
+struct ScatterTextState {
+    particles: TextParticles,
+    color_space: ColorSpace,
+    // Colours and opacity in the upload format, repacked every update
+    packed_colors: PackedColors,
 
-        // Apply easing
-        let easing = f32x16::splat(state.easing_factor);
-        let new_x = pos_x + dx * easing;
-        let new_y = pos_y + dy * easing;
+    source_width: f32,
+    source_height: f32,
+    canvas_width: f32,
+    canvas_height: f32,
 
-        // Store new positions
-        new_x.copy_to_slice(&mut state.positions_x[base..base + SIMD_BATCH_SIZE]);
-        new_y.copy_to_slice(&mut state.positions_y[base..base + SIMD_BATCH_SIZE]);
+    // Control parameters
+    forming: bool,
+    easing_factor: f32,
+    fade_rate: f32,
+    scatter_speed: f32,
 
-        // Reset opacity when forming
-        let full_opacity = f32x16::splat(1.0);
-        full_opacity.copy_to_slice(&mut state.opacity[base..base + SIMD_BATCH_SIZE]);
-    } else {
-        // Scattering - load scatter velocities
-        let vx = f32x16::from_slice(&state.scatter_vx[base..base + SIMD_BATCH_SIZE]);
-        let vy = f32x16::from_slice(&state.scatter_vy[base..base + SIMD_BATCH_SIZE]);
+    // Staggered formation
+    formation: FormationConfig,
+    form_elapsed: f32,
+
+    // Scatter behaviour, captured at `start_scattering`
+    scatter: ScatterConfig,
+    scatter_elapsed: f32,
+
+    // Idle drift around the targets while formed
+    drift: DriftConfig,
+    drift_time: f32,
+    // Curve-driven formation
+    form_curve: Option<FormCurve>,
+
+    // Size animation and spin
+    size: SizeConfig,
+
+    // Position history ring: slot s holds every particle's position at
+    // trail_x[s * aligned_count..(s + 1) * aligned_count]
+    trail_x: Vec<f32>,
+    trail_y: Vec<f32>,
+    trail_length: usize,
+    // Slot holding the most recent positions
+    trail_head: usize,
+    // Slots written since the trail was reset
+    trail_filled: usize,
+
+    // Motion, trail recording and colour packing, run by `update`
+    pipeline: Pipeline<ScatterTextState>,
+    stats: UpdateStats,
+}
 
-        // Update positions with scatter velocity
-        let dt = f32x16::splat(delta_time);
-        let new_x = pos_x + vx * dt;
-        let new_y = pos_y + vy * dt;
+impl Simulation for ScatterTextState {
+    fn pipeline_mut(&mut self) -> &mut Pipeline<Self> {
+        &mut self.pipeline
+    }
+}
 
-        // Store new positions
-        new_x.copy_to_slice(&mut state.positions_x[base..base + SIMD_BATCH_SIZE]);
-        new_y.copy_to_slice(&mut state.positions_y[base..base + SIMD_BATCH_SIZE]);
+#[wasm_bindgen]
+pub struct ScatterTextPointers {
+    pub positions_x_ptr: u32,
+    pub positions_y_ptr: u32,
+    pub target_x_ptr: u32,
+    pub target_y_ptr: u32,
+    pub scatter_vx_ptr: u32,
+    pub scatter_vy_ptr: u32,
+    pub colors_r_ptr: u32,
+    pub colors_g_ptr: u32,
+    pub colors_b_ptr: u32,
+    /// Encoding of the colour buffers
+    pub color_space: ColorSpace,
+    /// Packed colours with opacity as alpha, 0 when `color_format` is `None`
+    pub color_format: ColorFormat,
+    pub packed_colors_ptr: u32,
+    pub packed_colors_length: usize,
+    pub opacity_ptr: u32,
+    pub sizes_ptr: u32,
+    pub rotations_ptr: u32,
+    pub scattered_flags_ptr: u32,
+    pub phases_ptr: u32,
+    // Trail ring buffers; positions `age` frames old live in slot
+    // (trail_head + trail_length - age) % trail_length, at offset slot * trail_stride
+    pub trail_x_ptr: u32,
+    pub trail_y_ptr: u32,
+    pub trail_length: usize,
+    pub trail_stride: usize,
+    pub particle_count: usize,
+}
 
-        // Update opacity (fade out)
-        let opacity = f32x16::from_slice(&state.opacity[base..base + SIMD_BATCH_SIZE]);
-        let fade = f32x16::splat(state.fade_rate);
-        let zero = f32x16::splat(0.0);
-        let new_opacity = (opacity - fade).simd_max(zero);
-        new_opacity.copy_to_slice(&mut state.opacity[base..base + SIMD_BATCH_SIZE]);
+impl ScatterTextState {
+    fn get_pointers(&self) -> ScatterTextPointers {
+        ScatterTextPointers {
+            positions_x_ptr: self.particles.positions_x.as_ptr() as u32,
+            positions_y_ptr: self.particles.positions_y.as_ptr() as u32,
+            target_x_ptr: self.particles.target_x.as_ptr() as u32,
+            target_y_ptr: self.particles.target_y.as_ptr() as u32,
+            scatter_vx_ptr: self.particles.scatter_vx.as_ptr() as u32,
+            scatter_vy_ptr: self.particles.scatter_vy.as_ptr() as u32,
+            colors_r_ptr: self.particles.colors_r.as_ptr() as u32,
+            colors_g_ptr: self.particles.colors_g.as_ptr() as u32,
+            colors_b_ptr: self.particles.colors_b.as_ptr() as u32,
+            color_space: self.color_space,
+            color_format: self.packed_colors.format(),
+            packed_colors_ptr: self.packed_colors.ptr(),
+            packed_colors_length: self.packed_colors.view_length(),
+            opacity_ptr: self.particles.opacity.as_ptr() as u32,
+            sizes_ptr: self.particles.sizes.as_ptr() as u32,
+            rotations_ptr: self.particles.rotations.as_ptr() as u32,
+            scattered_flags_ptr: self.particles.scattered_flags.as_ptr() as u32,
+            phases_ptr: self.particles.phases.as_ptr() as u32,
+            trail_x_ptr: self.trail_x.as_ptr() as u32,
+            trail_y_ptr: self.trail_y.as_ptr() as u32,
+            trail_length: self.trail_length,
+            trail_stride: self.particles.positions_x.len(),
+            particle_count: self.particles.count(),
+        }
+    }
 
-        // Slow down scatter velocity
-        let friction = f32x16::splat(0.98);
-        let new_vx = vx * friction;
-        let new_vy = vy * friction;
-        new_vx.copy_to_slice(&mut state.scatter_vx[base..base + SIMD_BATCH_SIZE]);
-        new_vy.copy_to_slice(&mut state.scatter_vy[base..base + SIMD_BATCH_SIZE]);
+    fn set_color_space(&mut self, space: ColorSpace) {
+        let count = self.particles.count();
+        let particles = &mut self.particles;
+        convert_buffers(
+            self.color_space,
+            space,
+            [
+                &mut particles.colors_r,
+                &mut particles.colors_g,
+                &mut particles.colors_b,
+            ],
+            count,
+        );
+        self.color_space = space;
+        self.pack_colors();
     }
-}
 
-fn update_particle_scalar(state: &mut ScatterTextState, index: usize, delta_time: f32) {
-    if state.forming {
-        // Reset opacity
-        state.opacity[index] = 1.0;
+    fn set_color_format(&mut self, format: ColorFormat) {
+        self.packed_colors = PackedColors::new(format, self.particles.positions_x.len());
+        self.pack_colors();
+    }
 
-        // Clear scattered flag
-        let flag_index = index / 64;
-        let bit_index = index % 64;
-        state.scattered_flags[flag_index] &= !(1u64 << bit_index);
+    fn pack_colors(&mut self) {
+        self.packed_colors.pack(
+            [
+                &self.particles.colors_r,
+                &self.particles.colors_g,
+                &self.particles.colors_b,
+            ],
+            Some(&self.particles.opacity),
+            self.particles.count(),
+        );
+    }
 
-        // Ease towards target
-        let dx = state.target_x[index] - state.positions_x[index];
-        let dy = state.target_y[index] - state.positions_y[index];
+    // Map source sample positions to canvas targets
+    fn apply_layout(&mut self, layout: &LayoutConfig) {
+        let (scale, offset_x, offset_y) = layout.transform(
+            self.source_width,
+            self.source_height,
+            self.canvas_width,
+            self.canvas_height,
+        );
+        let count = self.particles.count();
+        for i in 0..count {
+            self.particles.target_x[i] = offset_x + self.particles.source_x[i] * scale;
+            self.particles.target_y[i] = offset_y + self.particles.source_y[i] * scale;
+        }
+        self.compute_start_delays();
+    }
 
-        state.positions_x[index] += dx * state.easing_factor;
-        state.positions_y[index] += dy * state.easing_factor;
-    } else {
-        // Set scattered flag
-        let flag_index = index / 64;
-        let bit_index = index % 64;
-        let is_scattered = (state.scattered_flags[flag_index] & (1u64 << bit_index)) != 0;
+    fn compute_start_delays(&mut self) {
+        let count = self.particles.count();
+        let spread = self.formation.spread;
+        let delays = &mut self.particles.start_delay[..count];
 
-        if !is_scattered {
-            state.scattered_flags[flag_index] |= 1u64 << bit_index;
+        if count == 0 || spread <= 0.0 || self.formation.order == FormationOrder::Simultaneous {
+            delays.fill(0.0);
+            return;
         }
 
-        // Scatter animation
-        state.positions_x[index] += state.scatter_vx[index] * delta_time;
-        state.positions_y[index] += state.scatter_vy[index] * delta_time;
+        let targets_x = &self.particles.target_x[..count];
+        let targets_y = &self.particles.target_y[..count];
 
-        // Fade out
-        state.opacity[index] = (state.opacity[index] - state.fade_rate).max(0.0);
+        // Normalise an axis to 0..1 across the text bounds
+        let normalise = |values: &[f32], out: &mut [f32]| {
+            let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+            let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let range = (max - min).max(f32::EPSILON);
+            for (delay, &v) in out.iter_mut().zip(values) {
+                *delay = (v - min) / range * spread;
+            }
+        };
+
+        match self.formation.order {
+            FormationOrder::Simultaneous => {}
+            FormationOrder::LeftToRight => normalise(targets_x, delays),
+            FormationOrder::Rows => normalise(targets_y, delays),
+            FormationOrder::Radial => {
+                let center_x = targets_x.iter().sum::<f32>() / count as f32;
+                let center_y = targets_y.iter().sum::<f32>() / count as f32;
+                for (i, delay) in delays.iter_mut().enumerate() {
+                    let dx = targets_x[i] - center_x;
+                    let dy = targets_y[i] - center_y;
+                    *delay = (dx * dx + dy * dy).sqrt();
+                }
+                let max = delays
+                    .iter()
+                    .copied()
+                    .fold(0.0f32, f32::max)
+                    .max(f32::EPSILON);
+                for delay in delays.iter_mut() {
+                    *delay = *delay / max * spread;
+                }
+            }
+            FormationOrder::Random => {
+                // Hash the seed into its own stream so nearby seeds are unrelated
+                let stream = random_u32(FORMATION_STREAM, self.formation.seed);
+                for (i, delay) in delays.iter_mut().enumerate() {
+                    *delay = random_f32(stream, i as u32) * spread;
+                }
+            }
+        }
+    }
 
-        // Slow down
-        state.scatter_vx[index] *= 0.98;
-        state.scatter_vy[index] *= 0.98;
+    fn count_settled(&self) -> usize {
+        self.particles.phases[..self.particles.count()]
+            .iter()
+            .filter(|&&phase| {
+                phase == ParticlePhase::Formed as u8 || phase == ParticlePhase::Dead as u8
+            })
+            .count()
     }
 }
 
+/// Progress snapshot of the last `update_particles`, read through
+/// `get_scatter_text_status` or the matching `ScatterText` getters.
 #[wasm_bindgen]
-pub fn set_easing_factor(factor: f32) {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let mut state_ref = cell.borrow_mut();
-        if let Some(state) = state_ref.as_mut() {
-            state.easing_factor = factor.clamp(0.01, 0.5);
-        }
-    });
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScatterTextStatus {
+    pub particle_count: usize,
+    /// Fraction of particles within `SETTLE_EPSILON` of their target (0..1)
+    pub settled_fraction: f32,
+    /// Mean remaining distance to target, in pixels
+    pub mean_distance: f32,
+    /// Particles whose opacity has reached zero
+    pub transparent_count: usize,
+    /// True once the current transition has finished (all formed, or all faded)
+    pub idle: bool,
+    /// Trail slot holding this frame's positions
+    pub trail_head: usize,
+    /// Valid trail slots, up to the trail length
+    pub trail_filled: usize,
 }
 
-#[wasm_bindgen]
-pub fn set_fade_rate(rate: f32) {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let mut state_ref = cell.borrow_mut();
-        if let Some(state) = state_ref.as_mut() {
-            state.fade_rate = rate.clamp(0.001, 0.1);
-        }
-    });
+// Per-frame accumulators filled by the update kernels
+#[derive(Default)]
+struct UpdateStats {
+    settled: usize,
+    transparent: usize,
+    distance_sum: f32,
 }
 
-#[wasm_bindgen]
-pub fn set_scatter_speed(speed: f32) {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let mut state_ref = cell.borrow_mut();
-        if let Some(state) = state_ref.as_mut() {
-            state.scatter_speed = speed.clamp(0.5, 10.0);
+impl ScatterTextState {
+    fn new(particle_count: usize) -> Self {
+        Self {
+            particles: TextParticles::with_count(particle_count),
+            color_space: ColorSpace::Srgb,
+            packed_colors: PackedColors::None,
+            forming: false,
+            easing_factor: 0.08,
+            fade_rate: 0.02,
+            scatter_speed: 3.0,
+            source_width: 0.0,
+            source_height: 0.0,
+            canvas_width: 0.0,
+            canvas_height: 0.0,
+            formation: FormationConfig::new(),
+            form_elapsed: 0.0,
+            scatter: ScatterConfig::new(),
+            scatter_elapsed: 0.0,
+            drift: DriftConfig::new(),
+            drift_time: 0.0,
+            form_curve: None,
+            size: SizeConfig::new(),
+            trail_x: Vec::new(),
+            trail_y: Vec::new(),
+            trail_length: 0,
+            trail_head: 0,
+            trail_filled: 0,
+            pipeline: Pipeline::new()
+                .with(move_particles)
+                .with(|state: &mut Self, _: Frame| state.record_trail())
+                .with(|state: &mut Self, _: Frame| state.pack_colors()),
+            stats: UpdateStats::default(),
         }
-    });
+    }
+
+    fn start_forming(&mut self) {
+        self.forming = true;
+        // Reset scattered flags when forming
+        for flag in self.particles.scattered_flags.iter_mut() {
+            *flag = 0;
+        }
+        self.form_elapsed = 0.0;
+        let count = self.particles.count();
+        self.particles.phases[..count].fill(ParticlePhase::Forming as u8);
+        self.capture_form_start();
+    }
+
+    // Curve-driven formation interpolates from the positions at its start
+    fn capture_form_start(&mut self) {
+        self.particles
+            .form_from_x
+            .copy_from_slice(&self.particles.positions_x);
+        self.particles
+            .form_from_y
+            .copy_from_slice(&self.particles.positions_y);
+    }
+
+    fn start_scattering(&mut self, config: &ScatterConfig) {
+        self.forming = false;
+        let count = self.particles.count();
+        self.particles.phases[..count].fill(ParticlePhase::Scattering as u8);
+        self.init_scatter(config);
+    }
+
+    // Initial velocities and start delays for the chosen scatter behaviour
+    fn init_scatter(&mut self, config: &ScatterConfig) {
+        use std::f32::consts::PI;
+
+        self.scatter = *config;
+        self.scatter_elapsed = 0.0;
+        let count = self.particles.count();
+        self.particles.scatter_delay[..count].fill(0.0);
+
+        // Wind sweeps from the upwind edge: delay by position along the direction
+        let (mut sweep_min, mut sweep_max) = (f32::INFINITY, f32::NEG_INFINITY);
+        if config.mode == ScatterMode::Wind {
+            for i in 0..count {
+                let along = self.particles.positions_x[i] * config.direction_x
+                    + self.particles.positions_y[i] * config.direction_y;
+                sweep_min = sweep_min.min(along);
+                sweep_max = sweep_max.max(along);
+            }
+        }
+        let sweep_range = (sweep_max - sweep_min).max(f32::EPSILON);
+
+        for i in 0..count {
+            let random_turn = random_f32(SCATTER_ANGLE_STREAM, i as u32);
+            let random_angle = random_turn * PI * 2.0;
+            let random_speed = random_f32(SCATTER_SPEED_STREAM, i as u32);
+            let (x, y) = (self.particles.positions_x[i], self.particles.positions_y[i]);
+
+            let (vx, vy) = match config.mode {
+                ScatterMode::Radial => {
+                    let speed = random_speed * self.scatter_speed + 1.0;
+                    (random_angle.cos() * speed, random_angle.sin() * speed)
+                }
+                ScatterMode::Explode => {
+                    let (dx, dy) = (x - config.origin_x, y - config.origin_y);
+                    let length = (dx * dx + dy * dy).sqrt();
+                    let (dir_x, dir_y) = if length > 1e-3 {
+                        (dx / length, dy / length)
+                    } else {
+                        (random_angle.cos(), random_angle.sin())
+                    };
+                    let speed = config.strength * (0.5 + random_speed);
+                    (dir_x * speed, dir_y * speed)
+                }
+                ScatterMode::Wind => {
+                    let along = x * config.direction_x + y * config.direction_y;
+                    self.particles.scatter_delay[i] =
+                        (along - sweep_min) / sweep_range * config.duration;
+                    let speed = config.strength * (0.75 + 0.5 * random_speed);
+                    (config.direction_x * speed, config.direction_y * speed)
+                }
+                ScatterMode::Gravity => (
+                    (random_turn - 0.5) * config.strength,
+                    -random_speed * config.strength,
+                ),
+                ScatterMode::Dissolve => {
+                    let noise = value_2d(
+                        DISSOLVE_NOISE_SEED,
+                        x * SCATTER_NOISE_FREQUENCY * 2.0,
+                        y * SCATTER_NOISE_FREQUENCY * 2.0,
+                    );
+                    self.particles.scatter_delay[i] = (0.5 + 0.5 * noise) * config.duration;
+                    let speed = (random_speed * self.scatter_speed + 1.0) * 0.25;
+                    (random_angle.cos() * speed, random_angle.sin() * speed)
+                }
+            };
+            self.particles.scatter_vx[i] = vx;
+            self.particles.scatter_vy[i] = vy;
+        }
+    }
+
+    fn update(&mut self, delta_time: f32) -> ScatterTextStatus {
+        let count = self.particles.count();
+        if count == 0 {
+            return ScatterTextStatus {
+                idle: true,
+                ..ScatterTextStatus::default()
+            };
+        }
+
+        if self.forming {
+            self.form_elapsed += delta_time;
+        } else {
+            self.scatter_elapsed += delta_time;
+        }
+        self.drift_time += delta_time * self.drift.time_scale;
+
+        let time = if self.forming {
+            self.form_elapsed
+        } else {
+            self.scatter_elapsed
+        };
+        self.step(Frame { time, delta_time });
+        let stats = &self.stats;
+
+        // Drifting text keeps moving, so it never reports idle
+        let idle = if self.forming {
+            stats.settled == count && !self.drift.enabled()
+        } else {
+            stats.transparent == count
+        };
+
+        ScatterTextStatus {
+            particle_count: count,
+            settled_fraction: stats.settled as f32 / count as f32,
+            mean_distance: stats.distance_sum / count as f32,
+            transparent_count: stats.transparent,
+            idle,
+            trail_head: self.trail_head,
+            trail_filled: self.trail_filled,
+        }
+    }
+
+    // Reallocate the trail ring and fill it with the current positions
+    fn set_trail_length(&mut self, length: usize) {
+        let stride = self.particles.positions_x.len();
+        self.trail_length = length;
+        self.trail_x = vec![0.0; stride * length];
+        self.trail_y = vec![0.0; stride * length];
+        self.clear_trail();
+    }
+
+    // Drop the recorded history, keeping only the current positions. The
+    // buffers stay in place, so JS views remain valid.
+    fn clear_trail(&mut self) {
+        self.trail_head = 0;
+        self.trail_filled = 0;
+        self.record_trail();
+    }
+
+    // Copy this frame's positions into the next trail slot
+    fn record_trail(&mut self) {
+        if self.trail_length == 0 {
+            return;
+        }
+        let stride = self.particles.positions_x.len();
+        if self.trail_filled > 0 {
+            self.trail_head = (self.trail_head + 1) % self.trail_length;
+        }
+        self.trail_filled = (self.trail_filled + 1).min(self.trail_length);
+
+        let slot = self.trail_head * stride..(self.trail_head + 1) * stride;
+        self.trail_x[slot.clone()].copy_from_slice(&self.particles.positions_x);
+        self.trail_y[slot].copy_from_slice(&self.particles.positions_y);
+    }
+
+    fn resize(&mut self, new_width: f32, new_height: f32, layout: &LayoutConfig) {
+        let ratio_x = if self.canvas_width > 0.0 {
+            new_width / self.canvas_width
+        } else {
+            1.0
+        };
+        let ratio_y = if self.canvas_height > 0.0 {
+            new_height / self.canvas_height
+        } else {
+            1.0
+        };
+        let count = self.particles.count();
+        for x in self.particles.positions_x[..count].iter_mut() {
+            *x *= ratio_x;
+        }
+        for y in self.particles.positions_y[..count].iter_mut() {
+            *y *= ratio_y;
+        }
+
+        for x in self.particles.form_from_x[..count].iter_mut() {
+            *x *= ratio_x;
+        }
+        for y in self.particles.form_from_y[..count].iter_mut() {
+            *y *= ratio_y;
+        }
+
+        // The gravity floor is a canvas coordinate too
+        self.scatter.floor_y *= ratio_y;
+
+        self.canvas_width = new_width;
+        self.canvas_height = new_height;
+        self.apply_layout(layout);
+
+        // Old positions would streak across the layout change
+        self.clear_trail();
+    }
 }
 
-#[wasm_bindgen]
-pub fn get_particle_count() -> usize {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let state_ref = cell.borrow();
-        state_ref.as_ref().map(|s| s.particle_count).unwrap_or(0)
-    })
+// A source point that becomes one particle, in source image coordinates
+struct SamplePoint {
+    x: f32,
+    y: f32,
+    rgb: [f32; 3],
+    alpha: u8,
 }
 
+/// One independent scatter text effect: its own particles, formation/scatter
+/// state and spawn, sampling and layout settings. Use one per heading when
+/// several run on the same page; the free functions drive a shared default one.
 #[wasm_bindgen]
-pub fn is_forming() -> bool {
-    SCATTER_TEXT_STATE.with(|cell| {
-        let state_ref = cell.borrow();
-        state_ref.as_ref().map(|s| s.forming).unwrap_or(false)
-    })
+pub struct ScatterText {
+    state: Option<ScatterTextState>,
+    // Settings outlive the state, which is rebuilt by every input call
+    spawn: SpawnConfig,
+    sampling: SamplingConfig,
+    layout: LayoutConfig,
+    scatter: ScatterConfig,
+    drift: DriftConfig,
+    form_curve: Option<FormCurve>,
+    size: SizeConfig,
+    formation: FormationConfig,
+    trail_length: usize,
+    color_format: ColorFormat,
+    // Progress from the last update, kept so the per-frame call returns a primitive
+    status: ScatterTextStatus,
+}
+
+impl Default for ScatterText {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl ScatterText {
+    const fn empty() -> Self {
+        Self {
+            state: None,
+            spawn: SpawnConfig::new(),
+            sampling: SamplingConfig::new(),
+            layout: LayoutConfig::new(),
+            scatter: ScatterConfig::new(),
+            drift: DriftConfig::new(),
+            form_curve: None,
+            size: SizeConfig::new(),
+            formation: FormationConfig::new(),
+            trail_length: 0,
+            color_format: ColorFormat::None,
+            status: ScatterTextStatus {
+                particle_count: 0,
+                settled_fraction: 0.0,
+                mean_distance: 0.0,
+                transparent_count: 0,
+                idle: false,
+                trail_head: 0,
+                trail_filled: 0,
+            },
+        }
+    }
+
+    // Shared by the easing and cubic-bezier setters; a zero duration returns to
+    // exponential easing
+    fn set_form_curve(&mut self, curve: Curve, duration: f32) {
+        self.form_curve = (duration > 0.0).then_some(FormCurve { curve, duration });
+        if let Some(state) = self.state.as_mut() {
+            state.form_curve = self.form_curve;
+        }
+    }
+
+    // Second pass shared by every input format: rebuild the state and set particle data
+    fn populate_particles(
+        &mut self,
+        mut samples: Vec<SamplePoint>,
+        width: f32,
+        height: f32,
+        canvas_width: f32,
+        canvas_height: f32,
+    ) -> usize {
+        if samples.len() > MAX_PARTICLES {
+            warn(&format!(
+                "ScatterText: hit MAX_PARTICLES limit ({}), some pixels were dropped",
+                MAX_PARTICLES
+            ));
+            samples.truncate(MAX_PARTICLES);
+        }
+        let particle_count = samples.len();
+
+        // Keep the outgoing particle positions around for SpawnMode::Previous
+        let previous = self.state.take().map(|mut old| {
+            old.particles.positions_x.truncate(old.particles.count());
+            old.particles.positions_y.truncate(old.particles.count());
+            (old.particles.positions_x, old.particles.positions_y)
+        });
+
+        // Initialize memory with exact particle count
+        let mut state = ScatterTextState::new(particle_count);
+        let sampling = &self.sampling;
+        let spawn_ctx = SpawnContext {
+            canvas_width,
+            canvas_height,
+            text_width: width,
+            text_height: height,
+            previous: previous
+                .as_ref()
+                .map(|(xs, ys)| (xs.as_slice(), ys.as_slice())),
+        };
+
+        state.source_width = width;
+        state.source_height = height;
+        state.canvas_width = canvas_width;
+        state.canvas_height = canvas_height;
+
+        for (particle_index, sample) in samples.iter().enumerate() {
+            state.particles.source_x[particle_index] = sample.x;
+            state.particles.source_y[particle_index] = sample.y;
+
+            // Set starting position from the spawn distribution
+            let (spawn_x, spawn_y) = spawn_position(&self.spawn, &spawn_ctx, particle_index);
+            state.particles.positions_x[particle_index] = spawn_x;
+            state.particles.positions_y[particle_index] = spawn_y;
+
+            // Set color
+            let [r, g, b] = sampling.sample_color(sample.rgb, sample.x / width, sample.y / height);
+            state.particles.colors_r[particle_index] = r;
+            state.particles.colors_g[particle_index] = g;
+            state.particles.colors_b[particle_index] = b;
+
+            // Reset opacity
+            let base_opacity = if sampling.opacity_from_alpha {
+                sample.alpha as f32 / 255.0
+            } else {
+                1.0
+            };
+            state.particles.base_opacity[particle_index] = base_opacity;
+            state.particles.opacity[particle_index] = base_opacity;
+            state.particles.phases[particle_index] = ParticlePhase::Forming as u8;
+
+            // Size and rotation
+            let base_size = self.size.base_size(sample, particle_index);
+            state.particles.base_size[particle_index] = base_size;
+            state.particles.sizes[particle_index] = base_size;
+            if self.size.random_rotation {
+                state.particles.rotations[particle_index] =
+                    random_f32(ROTATION_STREAM, particle_index as u32) * std::f32::consts::PI * 2.0;
+            }
+        }
+
+        state.set_color_space(sampling.color_space);
+        state.set_color_format(self.color_format);
+
+        // Pre-calculate scatter velocities
+        state.init_scatter(&self.scatter);
+        state.drift = self.drift;
+        state.form_curve = self.form_curve;
+        state.size = self.size;
+        state.formation = self.formation;
+        state.set_trail_length(self.trail_length);
+
+        state.forming = true;
+        state.capture_form_start();
+
+        // Set target positions from the layout
+        state.apply_layout(&self.layout);
+        self.state = Some(state);
+        self.status = ScatterTextStatus::default();
+
+        particle_count
+    }
+
+    // Sample an 8-bit coverage mask as white pixels with coverage for alpha, so
+    // sampling and colour options apply as they do to `set_text_pixels`
+    fn set_coverage_pixels(
+        &mut self,
+        coverage: &[u8],
+        width: usize,
+        height: usize,
+        canvas_width: f32,
+        canvas_height: f32,
+        skip: u32,
+    ) -> usize {
+        self.sample_raster(width, height, canvas_width, canvas_height, skip, |i| {
+            ([1.0; 3], coverage.get(i).copied().unwrap_or(0))
+        })
+    }
+
+    // First pass shared by raster inputs: pick the pixels that become particles.
+    // `pixel` returns the colour and alpha at a row-major pixel index.
+    fn sample_raster(
+        &mut self,
+        width: usize,
+        height: usize,
+        canvas_width: f32,
+        canvas_height: f32,
+        skip: u32,
+        pixel: impl Fn(usize) -> ([f32; 3], u8),
+    ) -> usize {
+        let samples: Vec<SamplePoint> =
+            collect_sample_points(|i| pixel(i).1, width, height, skip as usize, &self.sampling)
+                .into_iter()
+                .map(|(x, y)| {
+                    let (rgb, alpha) = pixel(y * width + x);
+                    SamplePoint {
+                        x: x as f32,
+                        y: y as f32,
+                        rgb,
+                        alpha,
+                    }
+                })
+                .collect();
+
+        self.populate_particles(
+            samples,
+            width as f32,
+            height as f32,
+            canvas_width,
+            canvas_height,
+        )
+    }
+
+    fn set_vector_contours(
+        &mut self,
+        contours: Vec<Contour>,
+        scale: f32,
+        canvas_width: f32,
+        canvas_height: f32,
+        skip: u32,
+        outline: bool,
+    ) -> usize {
+        let Some((min_x, min_y, max_x, max_y)) = contour_bounds(&contours) else {
+            return 0;
+        };
+        let (extent_x, extent_y) = ((max_x - min_x) * scale, (max_y - min_y) * scale);
+        let finite = contours
+            .iter()
+            .flatten()
+            .all(|&(x, y)| x.is_finite() && y.is_finite());
+        if !(finite && scale > 0.0 && extent_x.is_finite() && extent_y.is_finite()) {
+            warn("ScatterText: shape coordinates or scale are out of range");
+            return 0;
+        }
+        // Shrink shapes that would rasterise beyond MAX_RASTER_SIDE
+        let scale = scale * fit_raster_scale(extent_x, extent_y, VECTOR_PADDING);
+
+        // Map shape space into a padded pixel box starting at the origin
+        let to_pixels = |&(x, y): &(f32, f32)| {
+            (
+                (x - min_x) * scale + VECTOR_PADDING,
+                (y - min_y) * scale + VECTOR_PADDING,
+            )
+        };
+        let contours: Vec<Contour> = contours
+            .iter()
+            .map(|contour| contour.iter().map(to_pixels).collect())
+            .collect();
+        let width = ((max_x - min_x) * scale + VECTOR_PADDING * 2.0).ceil() as usize;
+        let height = ((max_y - min_y) * scale + VECTOR_PADDING * 2.0).ceil() as usize;
+
+        if !outline {
+            let coverage = rasterize_coverage(&contours, width, height);
+            return self.set_coverage_pixels(
+                &coverage,
+                width,
+                height,
+                canvas_width,
+                canvas_height,
+                skip,
+            );
+        }
+
+        let samples = sample_contours(&contours, skip.max(1) as f32)
+            .into_iter()
+            .map(|(x, y)| SamplePoint {
+                x,
+                y,
+                rgb: [1.0, 1.0, 1.0],
+                alpha: 255,
+            })
+            .collect();
+
+        self.populate_particles(
+            samples,
+            width as f32,
+            height as f32,
+            canvas_width,
+            canvas_height,
+        )
+    }
+}
+
+#[wasm_bindgen]
+impl ScatterText {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ScatterText {
+        Self::empty()
+    }
+
+    pub fn set_text_pixels(
+        &mut self,
+        pixel_data: &[u8],
+        width: u32,
+        height: u32,
+        canvas_width: f32,
+        canvas_height: f32,
+        skip: u32,
+    ) -> usize {
+        // Pixels past the end of a short buffer count as transparent
+        self.sample_raster(
+            width as usize,
+            height as usize,
+            canvas_width,
+            canvas_height,
+            skip,
+            |i| match pixel_data.get(i * 4..i * 4 + 4) {
+                Some(&[r, g, b, a]) => ([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0], a),
+                _ => ([0.0; 3], 0),
+            },
+        )
+    }
+
+    /// Lay out and rasterise `text` from a TTF/OTF font blob in Rust, then build
+    /// particles from it exactly like `set_text_pixels`. Text wider or taller than
+    /// 2048 pixels is rasterised at a smaller size. Returns 0 if the font cannot be
+    /// parsed or `font_size` is not positive.
+    pub fn set_text_glyphs(
+        &mut self,
+        font_data: &[u8],
+        text: &str,
+        font_size: f32,
+        canvas_width: f32,
+        canvas_height: f32,
+        skip: u32,
+    ) -> usize {
+        let Some(raster) = rasterize_text(font_data, text, font_size) else {
+            warn("ScatterText: could not parse font data or font size");
+            return 0;
+        };
+
+        self.set_coverage_pixels(
+            &raster.coverage,
+            raster.width,
+            raster.height,
+            canvas_width,
+            canvas_height,
+            skip,
+        )
+    }
+
+    /// Build particles from SVG path data (M/L/H/V/C/S/Q/T/Z). The shape is scaled
+    /// by `scale` and laid out like text. With `outline` set, particles are spaced
+    /// `skip` pixels apart along the path; otherwise the filled shape is sampled.
+    /// Every subpath is treated as closed in both modes, with or without `Z`.
+    /// Shapes larger than 2048 pixels on a side are scaled down to fit. Returns 0
+    /// if the path data is invalid or its coordinates or `scale` are out of range.
+    pub fn set_svg_path(
+        &mut self,
+        path_data: &str,
+        scale: f32,
+        canvas_width: f32,
+        canvas_height: f32,
+        skip: u32,
+        outline: bool,
+    ) -> usize {
+        let mut builder = PathBuilder::new(VECTOR_FLATTEN_TOLERANCE / scale.max(f32::EPSILON));
+        if let Err(error) = parse_svg_path(path_data, &mut builder) {
+            warn(&format!("ScatterText: invalid SVG path: {}", error));
+            return 0;
+        }
+        self.set_vector_contours(
+            builder.finish(),
+            scale,
+            canvas_width,
+            canvas_height,
+            skip,
+            outline,
+        )
+    }
+
+    /// Build particles from polylines given as flattened `[x0, y0, x1, y1, ...]`
+    /// points, split into contours by `contour_lengths` (points per contour).
+    /// Each contour is closed back to its first point. Behaves like `set_svg_path`
+    /// otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_polylines(
+        &mut self,
+        points: &[f32],
+        contour_lengths: &[u32],
+        scale: f32,
+        canvas_width: f32,
+        canvas_height: f32,
+        skip: u32,
+        outline: bool,
+    ) -> usize {
+        let pairs = points.as_chunks::<2>().0;
+        let mut contours = Vec::with_capacity(contour_lengths.len());
+        let mut start = 0;
+        for &length in contour_lengths {
+            let end = (start + length as usize).min(pairs.len());
+            contours.push(pairs[start..end].iter().map(|&[x, y]| (x, y)).collect());
+            start = end;
+        }
+        self.set_vector_contours(contours, scale, canvas_width, canvas_height, skip, outline)
+    }
+
+    /// Column pointers, or None before any text has been set.
+    pub fn pointers(&self) -> Option<ScatterTextPointers> {
+        self.state.as_ref().map(|state| state.get_pointers())
+    }
+
+    /// Every particle column by name, for JS that builds its views from the
+    /// schema rather than `pointers`. None before any text has been set.
+    pub fn buffers(&self) -> Option<ParticleBuffers> {
+        self.state.as_ref().map(|state| state.particles.buffers())
+    }
+
+    pub fn start_forming(&mut self) {
+        if let Some(state) = self.state.as_mut() {
+            state.start_forming();
+        }
+    }
+
+    pub fn start_scattering(&mut self) {
+        if let Some(state) = self.state.as_mut() {
+            state.start_scattering(&self.scatter);
+        }
+    }
+
+    /// Advance one frame and report whether the transition has finished. The
+    /// rest of the progress is kept on the instance for the getters below, so
+    /// the per-frame call allocates nothing on the JS side. Without any text
+    /// there is nothing to animate, so this reports idle.
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        match self.state.as_mut() {
+            Some(state) => {
+                self.status = state.update(delta_time);
+                self.status.idle
+            }
+            None => true,
+        }
+    }
+
+    /// Progress from the last `update` as a new object; free it when done.
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> ScatterTextStatus {
+        self.status
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn settled_fraction(&self) -> f32 {
+        self.status.settled_fraction
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mean_distance(&self) -> f32 {
+        self.status.mean_distance
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn transparent_count(&self) -> usize {
+        self.status.transparent_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn trail_head(&self) -> usize {
+        self.status.trail_head
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn trail_filled(&self) -> usize {
+        self.status.trail_filled
+    }
+
+    pub fn set_easing_factor(&mut self, factor: f32) {
+        if let Some(state) = self.state.as_mut() {
+            state.easing_factor = factor.clamp(0.01, 0.5);
+        }
+    }
+
+    pub fn set_fade_rate(&mut self, rate: f32) {
+        if let Some(state) = self.state.as_mut() {
+            state.fade_rate = rate.clamp(0.001, 0.1);
+        }
+    }
+
+    pub fn set_scatter_speed(&mut self, speed: f32) {
+        if let Some(state) = self.state.as_mut() {
+            state.scatter_speed = speed.clamp(0.5, 10.0);
+        }
+    }
+
+    /// Keep the last `length` positions of every particle (up to 32, 0 turns
+    /// trails off) for motion-blur streaks or line trails. Reallocates the
+    /// trail buffers, so fetch the pointers again afterwards.
+    pub fn set_trail_length(&mut self, length: usize) {
+        self.trail_length = length.min(MAX_TRAIL_LENGTH);
+        if let Some(state) = self.state.as_mut() {
+            state.set_trail_length(self.trail_length);
+            self.status.trail_head = state.trail_head;
+            self.status.trail_filled = state.trail_filled;
+        }
+    }
+
+    /// Keep a packed copy of colours and opacity in `format` for cheaper upload,
+    /// refreshed every update. Reallocates the buffer, so fetch the pointers
+    /// again afterwards.
+    pub fn set_color_format(&mut self, format: ColorFormat) {
+        self.color_format = format;
+        if let Some(state) = self.state.as_mut() {
+            state.set_color_format(format);
+        }
+    }
+
+    /// Let formed particles drift up to `amplitude` pixels around their targets
+    /// in an evolving curl-noise flow. `frequency` is in noise cells per pixel
+    /// (smaller gives broader swirls) and `time_scale` speeds the flow up or down. An amplitude
+    /// of 0 turns drift off.
+    pub fn set_idle_drift(&mut self, amplitude: f32, frequency: f32, time_scale: f32) {
+        self.drift = DriftConfig {
+            amplitude: amplitude.max(0.0),
+            frequency: frequency.max(0.0),
+            time_scale: time_scale.max(0.0),
+        };
+        if let Some(state) = self.state.as_mut() {
+            state.drift = self.drift;
+        }
+    }
+
+    /// Form along `easing` over `duration` seconds per particle (after its start
+    /// delay) instead of the default exponential ease. A duration of 0 restores
+    /// the exponential ease.
+    pub fn set_form_easing(&mut self, easing: Easing, duration: f32) {
+        self.set_form_curve(easing.into(), duration);
+    }
+
+    /// Like `set_form_easing`, with a CSS `cubic-bezier(x1, y1, x2, y2)` timing.
+    pub fn set_form_cubic_bezier(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, duration: f32) {
+        self.set_form_curve(CubicBezier::new(x1, y1, x2, y2).into(), duration);
+    }
+
+    /// Give particles a base size between `min_size` and `max_size`, picked by
+    /// `source`. Sizes default to 1.0, so the buffer works as a point size
+    /// multiplier. Takes effect on the next input call.
+    pub fn set_particle_size(&mut self, source: SizeSource, min_size: f32, max_size: f32) {
+        let min_size = min_size.max(0.0);
+        self.size.source = source;
+        self.size.min_size = min_size;
+        self.size.max_size = max_size.max(min_size);
+    }
+
+    /// Animate sizes: particles far from their target grow by up to `swell`
+    /// (0.5 is 50% larger) while forming, and with `shrink` they shrink as they
+    /// fade out.
+    pub fn set_size_animation(&mut self, swell: f32, shrink: bool) {
+        self.size.swell = swell.max(0.0);
+        self.size.shrink = shrink;
+        if let Some(state) = self.state.as_mut() {
+            state.size.swell = self.size.swell;
+            state.size.shrink = shrink;
+        }
+    }
+
+    /// Start particles at a random rotation (from the next input call) and spin
+    /// them at `spin` radians per second while scattering.
+    pub fn set_particle_rotation(&mut self, random: bool, spin: f32) {
+        self.size.random_rotation = random;
+        self.size.spin = spin;
+        if let Some(state) = self.state.as_mut() {
+            state.size.spin = spin;
+        }
+    }
+
+    /// Scatter with the default random radial burst.
+    pub fn set_scatter_radial(&mut self) {
+        self.scatter.mode = ScatterMode::Radial;
+    }
+
+    /// Scatter away from (`x`, `y`), e.g. the click position, at roughly
+    /// `strength` pixels per second.
+    pub fn set_scatter_explode(&mut self, x: f32, y: f32, strength: f32) {
+        self.scatter.mode = ScatterMode::Explode;
+        self.scatter.origin_x = x;
+        self.scatter.origin_y = y;
+        self.scatter.strength = strength.max(0.0);
+    }
+
+    /// Sweep particles along (`direction_x`, `direction_y`) at `strength` pixels
+    /// per second with `turbulence` wobble. Upwind particles leave first; the
+    /// sweep crosses the text in `sweep_duration` seconds.
+    pub fn set_scatter_wind(
+        &mut self,
+        direction_x: f32,
+        direction_y: f32,
+        strength: f32,
+        turbulence: f32,
+        sweep_duration: f32,
+    ) {
+        let length = (direction_x * direction_x + direction_y * direction_y).sqrt();
+        let (direction_x, direction_y) = if length > 1e-6 {
+            (direction_x / length, direction_y / length)
+        } else {
+            (1.0, 0.0)
+        };
+        self.scatter.mode = ScatterMode::Wind;
+        self.scatter.direction_x = direction_x;
+        self.scatter.direction_y = direction_y;
+        self.scatter.strength = strength.max(0.0);
+        self.scatter.turbulence = turbulence.max(0.0);
+        self.scatter.duration = sweep_duration.max(0.0);
+    }
+
+    /// Pop particles up by up to `strength` pixels per second, then let them fall
+    /// with `gravity` (pixels per second squared) and bounce on `floor_y`, keeping
+    /// `restitution` (0..1) of their speed per bounce.
+    pub fn set_scatter_gravity(
+        &mut self,
+        strength: f32,
+        gravity: f32,
+        floor_y: f32,
+        restitution: f32,
+    ) {
+        self.scatter.mode = ScatterMode::Gravity;
+        self.scatter.strength = strength.max(0.0);
+        self.scatter.gravity = gravity;
+        self.scatter.floor_y = floor_y;
+        self.scatter.restitution = restitution.clamp(0.0, 1.0);
+    }
+
+    /// Fade particles out in a noise pattern spread over `duration` seconds, with
+    /// only a slight drift.
+    pub fn set_scatter_dissolve(&mut self, duration: f32) {
+        self.scatter.mode = ScatterMode::Dissolve;
+        self.scatter.duration = duration.max(0.0);
+    }
+
+    /// Choose where the next input call spawns its particles. `x`/`y` is the
+    /// emitter for `Point`; `radius` is the jitter for `Point` and the minimum ring
+    /// radius for `Ring`.
+    pub fn set_spawn_mode(&mut self, mode: SpawnMode, x: f32, y: f32, radius: f32) {
+        self.spawn.mode = mode;
+        self.spawn.origin_x = x;
+        self.spawn.origin_y = y;
+        self.spawn.radius = radius.max(0.0);
+    }
+
+    /// Supply spawn positions as interleaved `[x0, y0, x1, y1, ...]`, reused
+    /// cyclically if there are fewer than particles. Switches to `SpawnMode::Custom`.
+    pub fn set_spawn_positions(&mut self, positions: &[f32]) {
+        self.spawn.mode = SpawnMode::Custom;
+        self.spawn.custom_positions = positions.to_vec();
+    }
+
+    /// Pixels with alpha above `threshold` become particles. With `opacity_from_alpha`
+    /// each particle's formed opacity follows its source alpha instead of 1.0.
+    pub fn set_sampling_alpha(&mut self, threshold: u8, opacity_from_alpha: bool) {
+        self.sampling.alpha_threshold = threshold;
+        self.sampling.opacity_from_alpha = opacity_from_alpha;
+    }
+
+    /// Choose how opaque pixels are sampled. `outline_width` is the edge band (in
+    /// pixels) kept at full density by `Outline`; `interior_density` (0..1) is the
+    /// density it falls off to one more band width inside the glyphs, so 0 keeps
+    /// only the edge band.
+    pub fn set_sampling_mode(
+        &mut self,
+        mode: SamplingMode,
+        outline_width: f32,
+        interior_density: f32,
+    ) {
+        self.sampling.sampling_mode = mode;
+        self.sampling.outline_width = outline_width.max(1.0);
+        self.sampling.interior_density = interior_density.clamp(0.0, 1.0);
+    }
+
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.sampling.color_mode = mode;
+    }
+
+    /// Override colour used by `ColorMode::Flat`; switches to that mode.
+    pub fn set_flat_color(&mut self, r: f32, g: f32, b: f32) {
+        self.sampling.color_mode = ColorMode::Flat;
+        self.sampling.flat_color = [r, g, b];
+    }
+
+    /// Gradient stops as flattened `[t, r, g, b, ...]` with `t` in 0..1. Stops are
+    /// sorted by `t`; the gradient direction comes from `set_color_mode`.
+    pub fn set_gradient_stops(&mut self, stops: &[f32]) {
+        self.sampling.gradient = Gradient::from_flat(stops, self.sampling.gradient_space);
+    }
+
+    /// Space the gradient blends in between stops; `Srgb` by default.
+    pub fn set_gradient_space(&mut self, space: InterpolationSpace) {
+        self.sampling.gradient_space = space;
+        if let Some(gradient) = self.sampling.gradient.as_mut() {
+            gradient.set_space(space);
+        }
+    }
+
+    /// Encoding of the colour buffers handed to JS; `Srgb` by default. Current
+    /// particles are re-encoded in place.
+    pub fn set_color_space(&mut self, space: ColorSpace) {
+        self.sampling.color_space = space;
+        if let Some(state) = self.state.as_mut() {
+            state.set_color_space(space);
+        }
+    }
+
+    /// Palette for `ColorMode::LuminancePalette` as flattened `[r, g, b, ...]`,
+    /// ordered from darkest to brightest source luminance.
+    pub fn set_luminance_palette(&mut self, colors: &[f32]) {
+        self.sampling.palette = colors[..colors.len() - colors.len() % 3].to_vec();
+    }
+
+    /// Placement of the source image on the canvas. `scale` is used by `Fixed` and
+    /// `ScaleDown`; `padding` is a safe margin on every side. Both are multiplied by
+    /// `dpr`. Applies to the current text immediately and to later inputs.
+    pub fn set_layout(
+        &mut self,
+        h_align: HorizontalAlign,
+        v_align: VerticalAlign,
+        fit: FitMode,
+        scale: f32,
+        padding: f32,
+        dpr: f32,
+    ) {
+        self.layout = LayoutConfig {
+            h_align,
+            v_align,
+            fit,
+            scale: scale.max(0.01),
+            padding: padding.max(0.0),
+            dpr: dpr.max(0.1),
+        };
+        if let Some(state) = self.state.as_mut() {
+            state.apply_layout(&self.layout);
+        }
+    }
+
+    /// Recompute targets for a new canvas size from the stored samples, without
+    /// re-rasterising. Current positions are left alone and ease to the new targets.
+    pub fn update_canvas_size(&mut self, canvas_width: f32, canvas_height: f32) {
+        if canvas_width <= 0.0 || canvas_height <= 0.0 {
+            return;
+        }
+        if let Some(state) = self.state.as_mut() {
+            state.canvas_width = canvas_width;
+            state.canvas_height = canvas_height;
+            state.apply_layout(&self.layout);
+        }
+    }
+
+    /// Handle a viewport change: targets are re-laid out for the new canvas and
+    /// current positions are scaled proportionally, so every particle keeps its
+    /// identity and eases from where it was on screen into the new layout. The
+    /// gravity floor is scaled with the height, and trails restart from the
+    /// current positions.
+    pub fn resize(&mut self, new_width: f32, new_height: f32) {
+        if new_width <= 0.0 || new_height <= 0.0 {
+            return;
+        }
+        if let Some(state) = self.state.as_mut() {
+            // Keep the configured floor in step for the next `start_scattering`
+            if state.canvas_height > 0.0 {
+                self.scatter.floor_y *= new_height / state.canvas_height;
+            }
+            state.resize(new_width, new_height, &self.layout);
+            self.status.trail_head = state.trail_head;
+            self.status.trail_filled = state.trail_filled;
+        }
+    }
+
+    /// Stagger formation start times. `spread` is the delay, in seconds, between the
+    /// first and last particle to start moving; `seed` only affects `Random`.
+    pub fn set_formation_order(&mut self, order: FormationOrder, spread: f32, seed: u32) {
+        self.formation = FormationConfig {
+            order,
+            spread: spread.clamp(0.0, 10.0),
+            seed,
+        };
+        if let Some(state) = self.state.as_mut() {
+            state.formation = self.formation;
+            state.compute_start_delays();
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn particle_count(&self) -> usize {
+        self.state
+            .as_ref()
+            .map(|s| s.particles.count())
+            .unwrap_or(0)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_forming(&self) -> bool {
+        self.state.as_ref().map(|s| s.forming).unwrap_or(false)
+    }
+
+    /// Number of particles that have finished their current transition
+    /// (`Formed` while forming, `Dead` while scattering).
+    #[wasm_bindgen(getter)]
+    pub fn settled_count(&self) -> usize {
+        self.state.as_ref().map(|s| s.count_settled()).unwrap_or(0)
+    }
+
+    pub fn particle_phase(&self, index: usize) -> ParticlePhase {
+        match self.state.as_ref() {
+            Some(state) if index < state.particles.count() => {
+                ParticlePhase::from_u8(state.particles.phases[index])
+            }
+            _ => ParticlePhase::Idle,
+        }
+    }
+}
+
+fn move_particles(state: &mut ScatterTextState, frame: Frame) {
+    let mut stats = UpdateStats::default();
+    // The last batch is masked, so no scalar remainder is needed
+    for_each_batch(state.particles.count(), |batch| {
+        update_particle_batch_simd(state, batch, frame.delta_time, &mut stats);
+    });
+    state.stats = stats;
+}
+
+fn update_particle_batch_simd(
+    state: &mut ScatterTextState,
+    batch: Batch,
+    delta_time: f32,
+    stats: &mut UpdateStats,
+) {
+    // Load current positions
+    let pos_x = batch.load(&state.particles.positions_x);
+    let pos_y = batch.load(&state.particles.positions_y);
+
+    // Batches are lane-aligned, so all of a batch's flag bits share one u64 word
+    let flag_index = batch.flag_word();
+    let batch_bits = batch.flag_bits(LaneMask::splat(true));
+
+    // Load target positions
+    let target_x = batch.load(&state.particles.target_x);
+    let target_y = batch.load(&state.particles.target_y);
+    let settle_sq = F32s::splat(SETTLE_EPSILON * SETTLE_EPSILON);
+
+    if state.forming {
+        // Drift moves the target itself, so easing smooths the motion
+        let (target_x, target_y) = if state.drift.enabled() {
+            let (offset_x, offset_y) =
+                drift_offset_simd(&state.drift, target_x, target_y, state.drift_time);
+            (target_x + offset_x, target_y + offset_y)
+        } else {
+            (target_x, target_y)
+        };
+
+        // Calculate deltas
+        let dx = target_x - pos_x;
+        let dy = target_y - pos_y;
+
+        // Apply easing to particles whose start delay has elapsed
+        let delay = batch.load(&state.particles.start_delay);
+        let elapsed = F32s::splat(state.form_elapsed);
+        let active = delay.simd_le(elapsed);
+        let (eased_x, eased_y) = match state.form_curve {
+            Some(form) => {
+                let progress = (elapsed - delay) / F32s::splat(form.duration);
+                let t = form.curve.apply_lanes(progress);
+                let from_x = batch.load(&state.particles.form_from_x);
+                let from_y = batch.load(&state.particles.form_from_y);
+                (
+                    from_x + (target_x - from_x) * t,
+                    from_y + (target_y - from_y) * t,
+                )
+            }
+            None => {
+                let easing = F32s::splat(state.easing_factor);
+                (pos_x + dx * easing, pos_y + dy * easing)
+            }
+        };
+        let new_x = active.select(eased_x, pos_x);
+        let new_y = active.select(eased_y, pos_y);
+
+        // Store new positions
+        batch.store(&mut state.particles.positions_x, new_x);
+        batch.store(&mut state.particles.positions_y, new_y);
+
+        // Reset opacity when forming
+        let base_opacity = batch.load(&state.particles.base_opacity);
+        batch.store(&mut state.particles.opacity, base_opacity);
+
+        // Clear scattered flags
+        state.particles.scattered_flags[flag_index] &= !batch_bits;
+
+        // Update phases
+        let rem_x = target_x - new_x;
+        let rem_y = target_y - new_y;
+        let dist_sq = rem_x * rem_x + rem_y * rem_y;
+        let settled = dist_sq.simd_le(settle_sq) & active;
+        let phases = settled.cast::<i8>().select(
+            U8s::splat(ParticlePhase::Formed as u8),
+            active.cast::<i8>().select(
+                U8s::splat(ParticlePhase::Forming as u8),
+                U8s::splat(ParticlePhase::Idle as u8),
+            ),
+        );
+        batch.store_u8(&mut state.particles.phases, phases);
+
+        let distance = dist_sq.sqrt();
+        stats.settled += batch.count(settled);
+        stats.distance_sum += batch.sum(distance);
+
+        // Swell while far from the target
+        let base_size = batch.load(&state.particles.base_size);
+        let swell = (distance / F32s::splat(SWELL_DISTANCE)).simd_min(F32s::splat(1.0))
+            * F32s::splat(state.size.swell);
+        batch.store(
+            &mut state.particles.sizes,
+            base_size * (F32s::splat(1.0) + swell),
+        );
+    } else {
+        // Scattering - load scatter velocities
+        let vx = batch.load(&state.particles.scatter_vx);
+        let vy = batch.load(&state.particles.scatter_vy);
+        let dt = F32s::splat(delta_time);
+        let config = state.scatter;
+
+        // Particles wait out their scatter delay without moving or fading
+        let delay = batch.load(&state.particles.scatter_delay);
+        let active = delay.simd_le(F32s::splat(state.scatter_elapsed));
+
+        // Per-mode velocity update and the velocity used for this step
+        let (mut new_vx, mut new_vy, step_vx, step_vy) = match config.mode {
+            ScatterMode::Radial | ScatterMode::Explode | ScatterMode::Dissolve => {
+                let friction = F32s::splat(SCATTER_FRICTION);
+                (vx * friction, vy * friction, vx, vy)
+            }
+            ScatterMode::Wind => {
+                let frequency = F32s::splat(SCATTER_NOISE_FREQUENCY);
+                let turbulence = F32s::splat(config.turbulence);
+                let (wobble_x, wobble_y) = curl_field_simd(
+                    WIND_NOISE_SEED,
+                    pos_x * frequency,
+                    pos_y * frequency,
+                    state.scatter_elapsed * WIND_NOISE_RATE,
+                );
+                (
+                    vx,
+                    vy,
+                    vx + wobble_x * turbulence,
+                    vy + wobble_y * turbulence,
+                )
+            }
+            ScatterMode::Gravity => {
+                let fallen_vy = vy + F32s::splat(config.gravity) * dt;
+                (vx, fallen_vy, vx, fallen_vy)
+            }
+        };
+
+        // Update positions with scatter velocity
+        let mut new_x = pos_x + step_vx * dt;
+        let mut new_y = pos_y + step_vy * dt;
+
+        if config.mode == ScatterMode::Gravity {
+            // Bounce off the floor
+            let floor = F32s::splat(config.floor_y);
+            let below = new_y.simd_gt(floor) & new_vy.simd_gt(F32s::splat(0.0));
+            new_y = below.select(floor, new_y);
+            new_vy = below.select(-new_vy * F32s::splat(config.restitution), new_vy);
+        }
+
+        new_x = active.select(new_x, pos_x);
+        new_y = active.select(new_y, pos_y);
+        new_vx = active.select(new_vx, vx);
+        new_vy = active.select(new_vy, vy);
+
+        // Store new positions
+        batch.store(&mut state.particles.positions_x, new_x);
+        batch.store(&mut state.particles.positions_y, new_y);
+        batch.store(&mut state.particles.scatter_vx, new_vx);
+        batch.store(&mut state.particles.scatter_vy, new_vy);
+
+        // Update opacity (fade out)
+        let opacity = batch.load(&state.particles.opacity);
+        let fade = F32s::splat(state.fade_rate);
+        let zero = F32s::splat(0.0);
+        let new_opacity = active.select((opacity - fade).simd_max(zero), opacity);
+        batch.store(&mut state.particles.opacity, new_opacity);
+
+        // Shrink with opacity and spin in the direction of travel
+        let base_size = batch.load(&state.particles.base_size);
+        let size = if state.size.shrink {
+            let base_opacity = batch.load(&state.particles.base_opacity);
+            base_size * new_opacity / base_opacity.simd_max(F32s::splat(f32::EPSILON))
+        } else {
+            base_size
+        };
+        batch.store(&mut state.particles.sizes, size);
+
+        if state.size.spin != 0.0 {
+            let rotation = batch.load(&state.particles.rotations);
+            let step = F32s::splat(state.size.spin) * dt * new_vx.signum();
+            batch.store(
+                &mut state.particles.rotations,
+                active.select(rotation + step, rotation),
+            );
+        }
+
+        // Set scattered flags
+        state.particles.scattered_flags[flag_index] |= batch_bits;
+
+        // Update phases
+        let dead = new_opacity.simd_le(zero);
+        let phases = dead.cast::<i8>().select(
+            U8s::splat(ParticlePhase::Dead as u8),
+            U8s::splat(ParticlePhase::Scattering as u8),
+        );
+        batch.store_u8(&mut state.particles.phases, phases);
+
+        let rem_x = target_x - new_x;
+        let rem_y = target_y - new_y;
+        let dist_sq = rem_x * rem_x + rem_y * rem_y;
+        stats.settled += batch.count(dist_sq.simd_le(settle_sq));
+        stats.transparent += batch.count(dead);
+        stats.distance_sum += batch.sum(dist_sq.sqrt());
+    }
+}
+
+fn with_default_scatter_text<R>(f: impl FnOnce(&mut ScatterText) -> R) -> R {
+    DEFAULT_SCATTER_TEXT.with(|cell| f(&mut cell.borrow_mut()))
+}
+
+#[wasm_bindgen]
+pub fn set_text_pixels(
+    pixel_data: &[u8],
+    width: u32,
+    height: u32,
+    canvas_width: f32,
+    canvas_height: f32,
+    skip: u32,
+) -> usize {
+    with_default_scatter_text(|text| {
+        text.set_text_pixels(pixel_data, width, height, canvas_width, canvas_height, skip)
+    })
+}
+
+#[wasm_bindgen]
+pub fn set_text_glyphs(
+    font_data: &[u8],
+    text: &str,
+    font_size: f32,
+    canvas_width: f32,
+    canvas_height: f32,
+    skip: u32,
+) -> usize {
+    with_default_scatter_text(|scatter| {
+        scatter.set_text_glyphs(
+            font_data,
+            text,
+            font_size,
+            canvas_width,
+            canvas_height,
+            skip,
+        )
+    })
+}
+
+#[wasm_bindgen]
+pub fn set_svg_path(
+    path_data: &str,
+    scale: f32,
+    canvas_width: f32,
+    canvas_height: f32,
+    skip: u32,
+    outline: bool,
+) -> usize {
+    with_default_scatter_text(|text| {
+        text.set_svg_path(path_data, scale, canvas_width, canvas_height, skip, outline)
+    })
+}
+
+#[wasm_bindgen]
+pub fn set_polylines(
+    points: &[f32],
+    contour_lengths: &[u32],
+    scale: f32,
+    canvas_width: f32,
+    canvas_height: f32,
+    skip: u32,
+    outline: bool,
+) -> usize {
+    with_default_scatter_text(|text| {
+        text.set_polylines(
+            points,
+            contour_lengths,
+            scale,
+            canvas_width,
+            canvas_height,
+            skip,
+            outline,
+        )
+    })
+}
+
+#[wasm_bindgen]
+pub fn get_scatter_text_pointers() -> Option<ScatterTextPointers> {
+    with_default_scatter_text(|text| text.pointers())
+}
+
+#[wasm_bindgen]
+pub fn get_scatter_text_buffers() -> Option<ParticleBuffers> {
+    with_default_scatter_text(|text| text.buffers())
+}
+
+#[wasm_bindgen]
+pub fn start_forming() {
+    with_default_scatter_text(|text| text.start_forming());
+}
+
+#[wasm_bindgen]
+pub fn start_scattering() {
+    with_default_scatter_text(|text| text.start_scattering());
+}
+
+/// Advance the default instance one frame and return whether the transition has
+/// finished. Settled fraction, mean distance and transparent count are read
+/// separately through `get_scatter_text_status`, so the per-frame call returns a
+/// primitive instead of allocating a JS object.
+#[wasm_bindgen]
+pub fn update_particles(delta_time: f32) -> bool {
+    with_default_scatter_text(|text| text.update(delta_time))
+}
+
+/// Progress from the last `update_particles`; free the result when done.
+#[wasm_bindgen]
+pub fn get_scatter_text_status() -> ScatterTextStatus {
+    with_default_scatter_text(|text| text.status)
+}
+
+#[wasm_bindgen]
+pub fn set_easing_factor(factor: f32) {
+    with_default_scatter_text(|text| text.set_easing_factor(factor));
+}
+
+#[wasm_bindgen]
+pub fn set_trail_length(length: usize) {
+    with_default_scatter_text(|text| text.set_trail_length(length));
+}
+
+#[wasm_bindgen]
+pub fn set_idle_drift(amplitude: f32, frequency: f32, time_scale: f32) {
+    with_default_scatter_text(|text| text.set_idle_drift(amplitude, frequency, time_scale));
+}
+
+#[wasm_bindgen]
+pub fn set_form_easing(easing: Easing, duration: f32) {
+    with_default_scatter_text(|text| text.set_form_easing(easing, duration));
+}
+
+#[wasm_bindgen]
+pub fn set_form_cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, duration: f32) {
+    with_default_scatter_text(|text| text.set_form_cubic_bezier(x1, y1, x2, y2, duration));
+}
+
+#[wasm_bindgen]
+pub fn set_particle_size(source: SizeSource, min_size: f32, max_size: f32) {
+    with_default_scatter_text(|text| text.set_particle_size(source, min_size, max_size));
+}
+
+#[wasm_bindgen]
+pub fn set_size_animation(swell: f32, shrink: bool) {
+    with_default_scatter_text(|text| text.set_size_animation(swell, shrink));
+}
+
+#[wasm_bindgen]
+pub fn set_particle_rotation(random: bool, spin: f32) {
+    with_default_scatter_text(|text| text.set_particle_rotation(random, spin));
+}
+
+#[wasm_bindgen]
+pub fn set_scatter_radial() {
+    with_default_scatter_text(|text| text.set_scatter_radial());
+}
+
+#[wasm_bindgen]
+pub fn set_scatter_explode(x: f32, y: f32, strength: f32) {
+    with_default_scatter_text(|text| text.set_scatter_explode(x, y, strength));
+}
+
+#[wasm_bindgen]
+pub fn set_scatter_wind(
+    direction_x: f32,
+    direction_y: f32,
+    strength: f32,
+    turbulence: f32,
+    sweep_duration: f32,
+) {
+    with_default_scatter_text(|text| {
+        text.set_scatter_wind(
+            direction_x,
+            direction_y,
+            strength,
+            turbulence,
+            sweep_duration,
+        )
+    });
+}
+
+#[wasm_bindgen]
+pub fn set_scatter_gravity(strength: f32, gravity: f32, floor_y: f32, restitution: f32) {
+    with_default_scatter_text(|text| {
+        text.set_scatter_gravity(strength, gravity, floor_y, restitution)
+    });
+}
+
+#[wasm_bindgen]
+pub fn set_scatter_dissolve(duration: f32) {
+    with_default_scatter_text(|text| text.set_scatter_dissolve(duration));
+}
+
+#[wasm_bindgen]
+pub fn set_spawn_mode(mode: SpawnMode, x: f32, y: f32, radius: f32) {
+    with_default_scatter_text(|text| text.set_spawn_mode(mode, x, y, radius));
+}
+
+#[wasm_bindgen]
+pub fn set_spawn_positions(positions: &[f32]) {
+    with_default_scatter_text(|text| text.set_spawn_positions(positions));
+}
+
+#[wasm_bindgen]
+pub fn set_sampling_alpha(threshold: u8, opacity_from_alpha: bool) {
+    with_default_scatter_text(|text| text.set_sampling_alpha(threshold, opacity_from_alpha));
+}
+
+#[wasm_bindgen]
+pub fn set_sampling_mode(mode: SamplingMode, outline_width: f32, interior_density: f32) {
+    with_default_scatter_text(|text| text.set_sampling_mode(mode, outline_width, interior_density));
+}
+
+#[wasm_bindgen]
+pub fn set_color_mode(mode: ColorMode) {
+    with_default_scatter_text(|text| text.set_color_mode(mode));
+}
+
+#[wasm_bindgen]
+pub fn set_flat_color(r: f32, g: f32, b: f32) {
+    with_default_scatter_text(|text| text.set_flat_color(r, g, b));
+}
+
+#[wasm_bindgen]
+pub fn set_gradient_stops(stops: &[f32]) {
+    with_default_scatter_text(|text| text.set_gradient_stops(stops));
+}
+
+#[wasm_bindgen]
+pub fn set_gradient_space(space: InterpolationSpace) {
+    with_default_scatter_text(|text| text.set_gradient_space(space));
+}
+
+#[wasm_bindgen]
+pub fn set_color_space(space: ColorSpace) {
+    with_default_scatter_text(|text| text.set_color_space(space));
+}
+
+#[wasm_bindgen]
+pub fn set_color_format(format: ColorFormat) {
+    with_default_scatter_text(|text| text.set_color_format(format));
+}
+
+#[wasm_bindgen]
+pub fn set_luminance_palette(colors: &[f32]) {
+    with_default_scatter_text(|text| text.set_luminance_palette(colors));
+}
+
+#[wasm_bindgen]
+pub fn set_layout(
+    h_align: HorizontalAlign,
+    v_align: VerticalAlign,
+    fit: FitMode,
+    scale: f32,
+    padding: f32,
+    dpr: f32,
+) {
+    with_default_scatter_text(|text| text.set_layout(h_align, v_align, fit, scale, padding, dpr));
+}
+
+#[wasm_bindgen]
+pub fn update_canvas_size(canvas_width: f32, canvas_height: f32) {
+    with_default_scatter_text(|text| text.update_canvas_size(canvas_width, canvas_height));
+}
+
+#[wasm_bindgen]
+pub fn resize_scatter_text(new_width: f32, new_height: f32) {
+    with_default_scatter_text(|text| text.resize(new_width, new_height));
+}
+
+#[wasm_bindgen]
+pub fn set_formation_order(order: FormationOrder, spread: f32, seed: u32) {
+    with_default_scatter_text(|text| text.set_formation_order(order, spread, seed));
+}
+
+#[wasm_bindgen]
+pub fn set_fade_rate(rate: f32) {
+    with_default_scatter_text(|text| text.set_fade_rate(rate));
+}
+
+#[wasm_bindgen]
+pub fn set_scatter_speed(speed: f32) {
+    with_default_scatter_text(|text| text.set_scatter_speed(speed));
+}
+
+#[wasm_bindgen]
+pub fn get_particle_count() -> usize {
+    with_default_scatter_text(|text| text.particle_count())
+}
+
+#[wasm_bindgen]
+pub fn is_forming() -> bool {
+    with_default_scatter_text(|text| text.is_forming())
+}
+
+#[wasm_bindgen]
+pub fn get_settled_count() -> usize {
+    with_default_scatter_text(|text| text.settled_count())
+}
+
+#[wasm_bindgen]
+pub fn get_particle_phase(index: usize) -> ParticlePhase {
+    with_default_scatter_text(|text| text.particle_phase(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::MAX_RASTER_SIDE;
+
+    // Opaque white block of `width` x `height` pixels, one particle per pixel,
+    // on a 100 x 100 canvas
+    fn block_text(width: u32, height: u32) -> ScatterText {
+        let mut text = ScatterText::new();
+        let pixels = vec![255u8; (width * height * 4) as usize];
+        text.set_text_pixels(&pixels, width, height, 100.0, 100.0, 1);
+        text
+    }
+
+    fn state(text: &ScatterText) -> &ScatterTextState {
+        text.state.as_ref().unwrap()
+    }
+
+    // Frames until `update` reports idle, or None if it never does
+    fn run_until_idle(text: &mut ScatterText, max_frames: usize) -> Option<usize> {
+        (1..=max_frames).find(|_| text.update(1.0 / 60.0))
+    }
+
+    fn phases(text: &ScatterText) -> Vec<ParticlePhase> {
+        (0..text.particle_count())
+            .map(|i| text.particle_phase(i))
+            .collect()
+    }
+
+    #[test]
+    fn phases_follow_form_and_scatter() {
+        // 21 particles, so the last batch is masked at every lane width
+        let mut text = block_text(7, 3);
+        let count = text.particle_count();
+        assert_eq!(count, 21);
+        assert!(phases(&text).iter().all(|&p| p == ParticlePhase::Forming));
+
+        run_until_idle(&mut text, 1000).expect("text never formed");
+        assert!(phases(&text).iter().all(|&p| p == ParticlePhase::Formed));
+        assert_eq!(text.settled_count(), count);
+        assert!(state(&text)
+            .particles
+            .scattered_flags
+            .iter()
+            .all(|&w| w == 0));
+
+        text.start_scattering();
+        assert!(!text.is_forming());
+        assert!(phases(&text)
+            .iter()
+            .all(|&p| p == ParticlePhase::Scattering));
+        assert_eq!(text.settled_count(), 0);
+
+        text.update(1.0 / 60.0);
+        // Only live particles are flagged, never the padding
+        let flags = &state(&text).particles.scattered_flags;
+        assert_eq!(flags[0], (1u64 << count) - 1);
+        assert!(flags[1..].iter().all(|&w| w == 0));
+
+        run_until_idle(&mut text, 1000).expect("text never faded");
+        assert!(phases(&text).iter().all(|&p| p == ParticlePhase::Dead));
+        assert_eq!(text.settled_count(), count);
+
+        text.start_forming();
+        assert!(phases(&text).iter().all(|&p| p == ParticlePhase::Forming));
+        assert!(state(&text)
+            .particles
+            .scattered_flags
+            .iter()
+            .all(|&w| w == 0));
+        assert_eq!(text.particle_phase(count), ParticlePhase::Idle);
+    }
+
+    #[test]
+    fn status_reports_form_and_scatter_progress() {
+        let mut text = block_text(6, 4);
+        let count = text.particle_count();
+        assert!(!text.status.idle);
+
+        let mut last_distance = f32::INFINITY;
+        let mut frames = 0;
+        while !text.update(1.0 / 60.0) {
+            let status = text.status();
+            assert!(!status.idle);
+            assert_eq!(status.particle_count, count);
+            assert!(status.mean_distance < last_distance);
+            assert!(status.settled_fraction < 1.0);
+            last_distance = status.mean_distance;
+            frames += 1;
+            assert!(frames < 1000, "text never formed");
+        }
+
+        let status = text.status();
+        assert_eq!(status.settled_fraction, 1.0);
+        assert!(status.mean_distance <= SETTLE_EPSILON);
+        assert_eq!(status.transparent_count, 0);
+        let formed = state(&text);
+        for i in 0..count {
+            let dx = formed.particles.positions_x[i] - formed.particles.target_x[i];
+            let dy = formed.particles.positions_y[i] - formed.particles.target_y[i];
+            assert!((dx * dx + dy * dy).sqrt() <= SETTLE_EPSILON);
+        }
+
+        text.start_scattering();
+        let mut last_transparent = 0;
+        while !text.update(1.0 / 60.0) {
+            assert!(text.transparent_count() < count);
+            assert!(text.transparent_count() >= last_transparent);
+            last_transparent = text.transparent_count();
+            frames += 1;
+            assert!(frames < 2000, "text never faded");
+        }
+        assert_eq!(text.transparent_count(), count);
+        assert!(text.mean_distance() > SETTLE_EPSILON);
+        assert!(state(&text).particles.opacity[..count]
+            .iter()
+            .all(|&o| o == 0.0));
+    }
+
+    #[test]
+    fn drifting_text_never_reports_idle() {
+        let mut text = block_text(6, 4);
+        text.set_idle_drift(2.0, 0.05, 1.0);
+        assert_eq!(run_until_idle(&mut text, 600), None);
+        // Particles stay within the drift amplitude of their targets
+        assert!(text.mean_distance() < 2.0, "{}", text.mean_distance());
+    }
+
+    #[test]
+    fn empty_text_is_idle() {
+        let mut text = ScatterText::new();
+        text.set_text_pixels(&[0; 16], 2, 2, 100.0, 100.0, 1);
+        assert_eq!(text.particle_count(), 0);
+        assert!(text.update(1.0 / 60.0));
+    }
+
+    fn start_delays(text: &ScatterText) -> Vec<f32> {
+        state(text).particles.start_delay[..text.particle_count()].to_vec()
+    }
+
+    #[test]
+    fn left_to_right_and_rows_stagger_by_target() {
+        let mut text = block_text(8, 4);
+        text.set_formation_order(FormationOrder::LeftToRight, 1.0, 0);
+        let delays = start_delays(&text);
+        let particles = &state(&text).particles;
+        for (i, &delay) in delays.iter().enumerate() {
+            // Source pixels run 0..7 along x
+            assert!((delay - particles.source_x[i] / 7.0).abs() < 1e-6);
+        }
+
+        let spawn_x = particles.positions_x.clone();
+        text.update(0.1);
+        let particles = &state(&text).particles;
+        for (i, &delay) in delays.iter().enumerate() {
+            let phase = text.particle_phase(i);
+            if delay > 0.1 {
+                assert_eq!(phase, ParticlePhase::Idle);
+                assert_eq!(particles.positions_x[i], spawn_x[i]);
+            } else {
+                assert_eq!(phase, ParticlePhase::Forming);
+                assert_ne!(particles.positions_x[i], spawn_x[i]);
+            }
+        }
+
+        text.set_formation_order(FormationOrder::Rows, 2.0, 0);
+        let particles = &state(&text).particles;
+        for (i, &delay) in start_delays(&text).iter().enumerate() {
+            assert!((delay - particles.source_y[i] / 3.0 * 2.0).abs() < 1e-6);
+        }
+
+        text.set_formation_order(FormationOrder::Simultaneous, 2.0, 0);
+        assert!(start_delays(&text).iter().all(|&d| d == 0.0));
+    }
+
+    #[test]
+    fn radial_stagger_blooms_from_the_centre() {
+        let mut text = block_text(5, 5);
+        text.set_formation_order(FormationOrder::Radial, 1.0, 0);
+        let delays = start_delays(&text);
+        // Row-major 5 x 5: the centre is index 12, the corners are furthest out
+        assert_eq!(delays[12], 0.0);
+        for corner in [0, 4, 20, 24] {
+            assert!((delays[corner] - 1.0).abs() < 1e-6);
+        }
+        assert!(delays[7] < delays[2]);
+    }
+
+    #[test]
+    fn random_stagger_is_seeded_and_survives_new_input() {
+        let mut text = ScatterText::new();
+        text.set_formation_order(FormationOrder::Random, 1.5, 7);
+        let pixels = vec![255u8; 6 * 6 * 4];
+        text.set_text_pixels(&pixels, 6, 6, 100.0, 100.0, 1);
+        let first = start_delays(&text);
+        assert!(first.iter().all(|&d| (0.0..1.5).contains(&d)));
+        assert!(first.windows(2).any(|pair| pair[0] != pair[1]));
+
+        // The order is kept for the next input call
+        text.set_text_pixels(&pixels, 6, 6, 100.0, 100.0, 1);
+        assert_eq!(start_delays(&text), first);
+
+        text.set_formation_order(FormationOrder::Random, 1.5, 8);
+        let other = start_delays(&text);
+        assert!(first.iter().zip(&other).filter(|(a, b)| a == b).count() < 2);
+    }
+
+    fn positions(text: &ScatterText) -> Vec<(f32, f32)> {
+        let particles = &state(text).particles;
+        (0..text.particle_count())
+            .map(|i| (particles.positions_x[i], particles.positions_y[i]))
+            .collect()
+    }
+
+    fn spawned_block(mode: SpawnMode, x: f32, y: f32, radius: f32) -> Vec<(f32, f32)> {
+        let mut text = ScatterText::new();
+        text.set_spawn_mode(mode, x, y, radius);
+        let pixels = vec![255u8; 20 * 20 * 4];
+        text.set_text_pixels(&pixels, 20, 20, 100.0, 100.0, 1);
+        positions(&text)
+    }
+
+    #[test]
+    fn canvas_spawn_is_uniform_and_uncorrelated() {
+        let points = spawned_block(SpawnMode::Canvas, 0.0, 0.0, 0.0);
+        assert!(points
+            .iter()
+            .all(|&(x, y)| (0.0..100.0).contains(&x) && (0.0..100.0).contains(&y)));
+
+        let n = points.len() as f32;
+        let mean_x = points.iter().map(|p| p.0).sum::<f32>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f32>() / n;
+        let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+        for &(x, y) in &points {
+            cov += (x - mean_x) * (y - mean_y);
+            var_x += (x - mean_x) * (x - mean_x);
+            var_y += (y - mean_y) * (y - mean_y);
+        }
+        let correlation = cov / (var_x * var_y).sqrt();
+        assert!(correlation.abs() < 0.15, "correlation {correlation}");
+        assert!((mean_x - 50.0).abs() < 5.0 && (mean_y - 50.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn point_edge_and_ring_spawns_stay_on_their_shape() {
+        for (x, y) in spawned_block(SpawnMode::Point, 30.0, 70.0, 10.0) {
+            assert!(((x - 30.0).powi(2) + (y - 70.0).powi(2)).sqrt() <= 10.0 + 1e-4);
+        }
+
+        for (x, y) in spawned_block(SpawnMode::Edges, 0.0, 0.0, 0.0) {
+            let on_edge = |v: f32| v.abs() < 1e-3 || (v - 100.0).abs() < 1e-3;
+            assert!(on_edge(x) || on_edge(y), "({x}, {y}) is not on an edge");
+        }
+
+        // The ring never cuts through the text: at least half its diagonal
+        let half_diagonal = (20.0f32 * 20.0 * 2.0).sqrt() / 2.0;
+        for (radius, expected) in [(0.0, half_diagonal), (30.0, 30.0)] {
+            for (x, y) in spawned_block(SpawnMode::Ring, 0.0, 0.0, radius) {
+                let distance = ((x - 50.0).powi(2) + (y - 50.0).powi(2)).sqrt();
+                assert!((distance - expected).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn previous_and_custom_spawns_reuse_positions() {
+        let mut text = block_text(4, 4);
+        run_until_idle(&mut text, 1000).expect("text never formed");
+        let formed = positions(&text);
+
+        // Fewer particles than before: each starts where its predecessor was
+        text.set_spawn_mode(SpawnMode::Previous, 0.0, 0.0, 0.0);
+        text.set_text_pixels(&[255; 2 * 3 * 4], 2, 3, 100.0, 100.0, 1);
+        assert_eq!(positions(&text), formed[..6]);
+
+        text.set_spawn_positions(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        text.set_text_pixels(&[255; 5 * 4], 5, 1, 100.0, 100.0, 1);
+        assert_eq!(
+            positions(&text),
+            [(1.0, 2.0), (3.0, 4.0), (1.0, 2.0), (3.0, 4.0), (1.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn glyph_input_matches_its_coverage() {
+        let font = include_bytes!("../testdata/test_font.ttf");
+        let mut text = ScatterText::new();
+        // 'A' at 100px is a 40 x 70 box of fully covered pixels
+        assert_eq!(
+            text.set_text_glyphs(font, "A", 100.0, 200.0, 200.0, 1),
+            40 * 70
+        );
+        let state = state(&text);
+        assert_eq!((state.source_width, state.source_height), (64.0, 104.0));
+        assert_eq!(
+            (state.particles.source_x[0], state.particles.source_y[0]),
+            (12.0, 12.0)
+        );
+
+        assert_eq!(
+            text.set_text_glyphs(&[0; 8], "A", 100.0, 200.0, 200.0, 1),
+            0
+        );
+    }
+
+    #[test]
+    fn oversized_shapes_are_scaled_to_the_raster_limit() {
+        let mut text = ScatterText::new();
+        let count = text.set_svg_path("M0 0 H10 V10 H0 Z", 1e6, 100.0, 100.0, 64, false);
+        assert!(count > 0);
+        let state = state(&text);
+        assert!(state.source_width <= MAX_RASTER_SIDE && state.source_height <= MAX_RASTER_SIDE);
+
+        // 1e50 overflows f32 and must not reach the rasteriser
+        assert_eq!(
+            text.set_svg_path("M0 0 L1e50 0 L0 1 Z", 1.0, 100.0, 100.0, 1, false),
+            0
+        );
+        assert_eq!(
+            text.set_svg_path("M0 0 L1e30 0 L0 1 Z", 1e30, 100.0, 100.0, 1, false),
+            0
+        );
+        assert_eq!(
+            text.set_polylines(
+                &[0.0, 0.0, 4.0, 0.0, 0.0, 4.0],
+                &[3],
+                -1.0,
+                100.0,
+                100.0,
+                1,
+                true
+            ),
+            0
+        );
+        assert_eq!(
+            text.set_polylines(
+                &[0.0, f32::NAN, 4.0, 0.0, 0.0, 4.0],
+                &[3],
+                1.0,
+                100.0,
+                100.0,
+                1,
+                true
+            ),
+            0
+        );
+    }
+
+    fn targets(text: &ScatterText) -> Vec<(f32, f32)> {
+        let particles = &state(text).particles;
+        (0..text.particle_count())
+            .map(|i| (particles.target_x[i], particles.target_y[i]))
+            .collect()
+    }
+
+    #[test]
+    fn canvas_size_updates_recentre_targets() {
+        let mut text = block_text(10, 4);
+        // Centred on the 100 x 100 canvas
+        assert_eq!(targets(&text)[0], (45.0, 48.0));
+
+        text.update_canvas_size(200.0, 50.0);
+        assert_eq!(targets(&text)[0], (95.0, 23.0));
+
+        // Degenerate sizes are ignored rather than collapsing the layout
+        for (width, height) in [(0.0, 50.0), (200.0, -1.0)] {
+            text.update_canvas_size(width, height);
+            assert_eq!(targets(&text)[0], (95.0, 23.0));
+        }
+    }
+
+    #[test]
+    fn resize_scales_positions_and_relays_targets() {
+        let mut text = block_text(10, 4);
+        text.set_trail_length(3);
+        text.update(1.0 / 60.0);
+        let before = positions(&text);
+        let from_before = state(&text).particles.form_from_y.clone();
+
+        text.resize(200.0, 50.0);
+        let state_after = state(&text);
+        assert_eq!(text.particle_count(), before.len());
+        for (&(x, y), (new_x, new_y)) in before.iter().zip(positions(&text)) {
+            assert_eq!((new_x, new_y), (x * 2.0, y * 0.5));
+        }
+        // The trail restarts from the scaled positions
+        let count = before.len();
+        assert_eq!(state_after.trail_filled, 1);
+        assert_eq!(
+            &state_after.trail_x[..count],
+            &state_after.particles.positions_x[..count]
+        );
+        for (&old, &new) in from_before.iter().zip(&state_after.particles.form_from_y) {
+            assert_eq!(new, old * 0.5);
+        }
+        assert_eq!(targets(&text)[0], (95.0, 23.0));
+
+        run_until_idle(&mut text, 1000).expect("text never re-formed");
+        let (x, y) = positions(&text)[0];
+        assert!((x - 95.0).abs() <= SETTLE_EPSILON && (y - 23.0).abs() <= SETTLE_EPSILON);
+
+        // A collapsed viewport is ignored
+        text.resize(0.0, 50.0);
+        assert_eq!(state(&text).canvas_width, 200.0);
+        assert_eq!(targets(&text)[0], (95.0, 23.0));
+    }
+
+    fn formed_block(width: u32, height: u32) -> ScatterText {
+        let mut text = block_text(width, height);
+        run_until_idle(&mut text, 1000).expect("text never formed");
+        text
+    }
+
+    fn velocities(text: &ScatterText) -> Vec<(f32, f32)> {
+        let particles = &state(text).particles;
+        (0..text.particle_count())
+            .map(|i| (particles.scatter_vx[i], particles.scatter_vy[i]))
+            .collect()
+    }
+
+    #[test]
+    fn radial_and_explode_burst_outward() {
+        let mut text = formed_block(8, 4);
+        text.set_scatter_speed(4.0);
+        text.start_scattering();
+        for (vx, vy) in velocities(&text) {
+            let speed = (vx * vx + vy * vy).sqrt();
+            assert!((1.0..=5.0 + 1e-4).contains(&speed), "speed {speed}");
+        }
+        run_until_idle(&mut text, 1000).expect("radial scatter never faded");
+
+        let mut text = formed_block(8, 4);
+        text.set_scatter_explode(0.0, 50.0, 100.0);
+        text.start_scattering();
+        for ((x, y), (vx, vy)) in positions(&text).into_iter().zip(velocities(&text)) {
+            let speed = (vx * vx + vy * vy).sqrt();
+            assert!((50.0..=150.0).contains(&speed), "speed {speed}");
+            // Parallel to the offset from the origin, pointing away
+            let (dx, dy) = (x, y - 50.0);
+            let along = (dx * vx + dy * vy) / (dx * dx + dy * dy).sqrt();
+            assert!((along - speed).abs() < 1e-3);
+        }
+
+        let before = positions(&text);
+        text.update(0.1);
+        for ((x0, _), (x1, _)) in before.into_iter().zip(positions(&text)) {
+            assert!(x1 > x0);
+        }
+    }
+
+    #[test]
+    fn wind_sweeps_from_the_upwind_edge() {
+        let mut text = formed_block(8, 4);
+        text.set_scatter_wind(1.0, 0.0, 200.0, 0.0, 1.0);
+        text.start_scattering();
+
+        let state_now = state(&text);
+        let xs = &state_now.particles.positions_x[..text.particle_count()];
+        let delays = &state_now.particles.scatter_delay[..text.particle_count()];
+        let min_x = xs.iter().copied().fold(f32::INFINITY, f32::min);
+        let max_x = xs.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        for (&x, &delay) in xs.iter().zip(delays) {
+            let expected = (x - min_x) / (max_x - min_x);
+            assert!((delay - expected).abs() < 1e-4);
+        }
+        for (vx, vy) in velocities(&text) {
+            assert!((150.0..=250.0).contains(&vx) && vy == 0.0);
+        }
+
+        // Only the upwind column has started moving and fading
+        let delays = delays.to_vec();
+        let before = positions(&text);
+        text.update(0.05);
+        let particles = &state(&text).particles;
+        for (i, &delay) in delays.iter().enumerate() {
+            let moved = particles.positions_x[i] != before[i].0;
+            assert_eq!(moved, delay <= 0.05);
+            assert_eq!(particles.opacity[i] < 1.0, delay <= 0.05);
+        }
+        run_until_idle(&mut text, 1000).expect("wind scatter never faded");
+    }
+
+    #[test]
+    fn gravity_bounces_on_a_floor_that_follows_resize() {
+        let mut text = formed_block(8, 4);
+        text.set_fade_rate(0.001);
+        text.set_scatter_gravity(50.0, 2000.0, 90.0, 0.5);
+        text.start_scattering();
+        assert!(velocities(&text).iter().all(|&(_, vy)| vy <= 0.0));
+
+        let mut bounced = false;
+        for _ in 0..30 {
+            text.update(1.0 / 60.0);
+            assert!(positions(&text).iter().all(|&(_, y)| y <= 90.0));
+            // A bounce clamps to the floor and sends the particle back up
+            bounced |= positions(&text)
+                .iter()
+                .zip(velocities(&text))
+                .any(|(&(_, y), (_, vy))| y == 90.0 && vy < 0.0);
+        }
+        assert!(bounced);
+
+        text.resize(100.0, 50.0);
+        assert_eq!(state(&text).scatter.floor_y, 45.0);
+        assert_eq!(text.scatter.floor_y, 45.0);
+        for _ in 0..120 {
+            text.update(1.0 / 60.0);
+            assert!(positions(&text).iter().all(|&(_, y)| y <= 45.0));
+        }
+        let lowest = positions(&text).iter().map(|p| p.1).fold(0.0, f32::max);
+        assert!(lowest > 44.0, "lowest particle at {lowest}");
+    }
+
+    #[test]
+    fn dissolve_fades_in_noise_order_with_little_motion() {
+        let mut text = formed_block(8, 4);
+        text.set_scatter_dissolve(2.0);
+        text.start_scattering();
+        let count = text.particle_count();
+        let delays = state(&text).particles.scatter_delay[..count].to_vec();
+        assert!(delays.iter().all(|&d| (0.0..=2.0).contains(&d)));
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+        for (vx, vy) in velocities(&text) {
+            assert!((vx * vx + vy * vy).sqrt() <= (3.0 + 1.0) * 0.25 + 1e-4);
+        }
+
+        let frames = run_until_idle(&mut text, 1000).expect("dissolve never faded");
+        // The last particle starts fading at its delay and needs 50 frames to vanish
+        let last = delays.iter().copied().fold(0.0, f32::max);
+        assert!(frames as f32 / 60.0 >= last);
+    }
+
+    // RGBA image of `width` x `height` pixels from a per-pixel function
+    fn image(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 4]) -> Vec<u8> {
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| pixel(x, y))
+            .collect()
+    }
+
+    fn colors(text: &ScatterText) -> Vec<[f32; 3]> {
+        let particles = &state(text).particles;
+        (0..text.particle_count())
+            .map(|i| {
+                [
+                    particles.colors_r[i],
+                    particles.colors_g[i],
+                    particles.colors_b[i],
+                ]
+            })
+            .collect()
+    }
+
+    fn assert_colors(actual: &[[f32; 3]], expected: &[[f32; 3]]) {
+        assert_eq!(actual.len(), expected.len());
+        for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+            for c in 0..3 {
+                assert!((a[c] - e[c]).abs() < 1e-3, "particle {i}: {a:?} vs {e:?}");
+            }
+        }
+    }
+
+    // Source pixels sampled from a 12x12 opaque square inside a 20x20 image
+    fn sample_square(mode: SamplingMode, interior_density: f32, skip: u32) -> Vec<(i32, i32)> {
+        let data = image(20, 20, |x, y| {
+            let inside = (4..16).contains(&x) && (4..16).contains(&y);
+            [255, 255, 255, if inside { 255 } else { 0 }]
+        });
+        let mut text = ScatterText::new();
+        text.set_sampling_mode(mode, 1.0, interior_density);
+        text.set_text_pixels(&data, 20, 20, 100.0, 100.0, skip);
+        let particles = &state(&text).particles;
+        (0..text.particle_count())
+            .map(|i| (particles.source_x[i] as i32, particles.source_y[i] as i32))
+            .collect()
+    }
+
+    fn in_square((x, y): (i32, i32)) -> bool {
+        (4..16).contains(&x) && (4..16).contains(&y)
+    }
+
+    fn on_edge((x, y): (i32, i32)) -> bool {
+        in_square((x, y))
+            && ![(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .into_iter()
+                .all(in_square)
+    }
+
+    #[test]
+    fn fill_sampling_keeps_the_interior() {
+        let points = sample_square(SamplingMode::Fill, 0.0, 1);
+        assert_eq!(points.len(), 144);
+        assert!(points.iter().all(|&p| in_square(p)));
+        assert!(points.iter().any(|&p| !on_edge(p)));
+
+        let points = sample_square(SamplingMode::Fill, 0.0, 2);
+        assert_eq!(points.len(), 36);
+        assert!(points.iter().all(|&(x, y)| x % 2 == 0 && y % 2 == 0));
+    }
+
+    #[test]
+    fn outline_sampling_keeps_only_the_edge() {
+        let points = sample_square(SamplingMode::Outline, 0.0, 1);
+        assert_eq!(points.len(), 44);
+        assert!(points.iter().all(|&p| on_edge(p)), "{points:?}");
+
+        // Full interior density is the same as Fill
+        assert_eq!(sample_square(SamplingMode::Outline, 1.0, 1).len(), 144);
+    }
+
+    #[test]
+    fn contour_sampling_spaces_points_along_the_edge() {
+        let spacing = 3;
+        let points = sample_square(SamplingMode::Contour, 0.0, spacing);
+        assert!(points.iter().all(|&p| on_edge(p)), "{points:?}");
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+                assert!(
+                    dx * dx + dy * dy >= (spacing * spacing) as i32,
+                    "{a:?} {b:?}"
+                );
+            }
+        }
+        // No gaps: every edge pixel has a sample nearby
+        for x in 4..16 {
+            for y in 4..16 {
+                if on_edge((x, y)) {
+                    assert!(points
+                        .iter()
+                        .any(|&(px, py)| (px - x).pow(2) + (py - y).pow(2)
+                            <= 2 * spacing.pow(2) as i32));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn alpha_threshold_and_opacity_follow_the_source() {
+        let pixels = [
+            [10, 20, 30, 0],
+            [255, 0, 0, 100],
+            [0, 255, 0, 200],
+            [0, 0, 255, 255],
+        ];
+        let data = image(4, 1, |x, _| pixels[x as usize]);
+
+        // Default threshold 128, full opacity
+        let mut text = ScatterText::new();
+        text.set_text_pixels(&data, 4, 1, 100.0, 100.0, 1);
+        assert_eq!(text.particle_count(), 2);
+        assert_eq!(&state(&text).particles.source_x[..2], &[2.0, 3.0]);
+        assert_eq!(&state(&text).particles.opacity[..2], &[1.0, 1.0]);
+        assert_colors(&colors(&text), &[[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
+        text.set_sampling_alpha(50, true);
+        text.set_text_pixels(&data, 4, 1, 100.0, 100.0, 1);
+        assert_eq!(text.particle_count(), 3);
+        let expected = [100.0 / 255.0, 200.0 / 255.0, 1.0];
+        let particles = &state(&text).particles;
+        assert_eq!(&particles.opacity[..3], &expected);
+        assert_eq!(&particles.base_opacity[..3], &expected);
+
+        // Forming restores the source alpha rather than 1.0
+        text.update(1.0 / 60.0);
+        assert_eq!(&state(&text).particles.opacity[..3], &expected);
+
+        // Nothing passes a threshold of 255
+        text.set_sampling_alpha(255, false);
+        assert_eq!(text.set_text_pixels(&data, 4, 1, 100.0, 100.0, 1), 0);
+    }
+
+    #[test]
+    fn colour_modes_write_the_expected_colours() {
+        // Grey ramp along x: luminance 0, 1/3, 2/3, 1
+        let data = image(4, 4, |x, _| {
+            let grey = (x * 85) as u8;
+            [grey, grey, grey, 255]
+        });
+        let load = |text: &mut ScatterText| {
+            text.set_text_pixels(&data, 4, 4, 100.0, 100.0, 1);
+            colors(text)
+        };
+        let pixels: Vec<(f32, f32)> = (0..16).map(|i| ((i % 4) as f32, (i / 4) as f32)).collect();
+        let red_to_blue = |t: f32| [1.0 - t, 0.0, t];
+
+        let mut text = ScatterText::new();
+        let source: Vec<[f32; 3]> = pixels.iter().map(|&(x, _)| [x / 3.0; 3]).collect();
+        assert_colors(&load(&mut text), &source);
+
+        text.set_flat_color(0.2, 0.4, 0.6);
+        assert_colors(&load(&mut text), &[[0.2, 0.4, 0.6]; 16]);
+
+        text.set_gradient_stops(&[1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0]);
+        text.set_color_mode(ColorMode::HorizontalGradient);
+        let expected: Vec<_> = pixels.iter().map(|&(x, _)| red_to_blue(x / 4.0)).collect();
+        assert_colors(&load(&mut text), &expected);
+
+        text.set_color_mode(ColorMode::VerticalGradient);
+        let expected: Vec<_> = pixels.iter().map(|&(_, y)| red_to_blue(y / 4.0)).collect();
+        assert_colors(&load(&mut text), &expected);
+
+        text.set_color_mode(ColorMode::RadialGradient);
+        let expected: Vec<_> = pixels
+            .iter()
+            .map(|&(x, y)| {
+                let (du, dv) = (x / 4.0 - 0.5, y / 4.0 - 0.5);
+                red_to_blue((du * du + dv * dv).sqrt() * std::f32::consts::SQRT_2)
+            })
+            .collect();
+        let radial = load(&mut text);
+        assert_colors(&radial, &expected);
+        assert_colors(&radial[10..11], &[[1.0, 0.0, 0.0]]);
+        assert_colors(&radial[..1], &[[0.0, 0.0, 1.0]]);
+
+        // Dark half to the first entry, bright half to the second
+        text.set_luminance_palette(&[0.1, 0.1, 0.3, 0.9, 0.8, 0.2]);
+        text.set_color_mode(ColorMode::LuminancePalette);
+        let expected: Vec<_> = pixels
+            .iter()
+            .map(|&(x, _)| {
+                if x < 2.0 {
+                    [0.1, 0.1, 0.3]
+                } else {
+                    [0.9, 0.8, 0.2]
+                }
+            })
+            .collect();
+        assert_colors(&load(&mut text), &expected);
+    }
+
+    #[test]
+    fn max_particles_drops_the_excess() {
+        let mut text = block_text(101, 100);
+        assert_eq!(text.particle_count(), MAX_PARTICLES);
+        text.update(1.0 / 60.0);
+        assert_eq!(text.status.particle_count, MAX_PARTICLES);
+    }
+
+    #[test]
+    fn layout_transform_places_the_bounding_box() {
+        // 100 x 100 source on a 400 x 200 canvas; padding 10 at dpr 2 leaves a
+        // 360 x 160 area from (20, 20) to (380, 180)
+        let (source, canvas_w, canvas_h) = (100.0, 400.0, 200.0);
+        let fits = [
+            (FitMode::Fixed, 0.5, 100.0),
+            (FitMode::Contain, 1.0, 160.0),
+            (FitMode::Cover, 1.0, 360.0),
+            (FitMode::ScaleDown, 0.5, 100.0),
+            (FitMode::ScaleDown, 3.0, 160.0),
+        ];
+        let h_aligns = [
+            HorizontalAlign::Left,
+            HorizontalAlign::Center,
+            HorizontalAlign::Right,
+        ];
+        let v_aligns = [
+            VerticalAlign::Top,
+            VerticalAlign::Middle,
+            VerticalAlign::Bottom,
+        ];
+
+        for (fit, scale, size) in fits {
+            for h_align in h_aligns {
+                for v_align in v_aligns {
+                    let layout = LayoutConfig {
+                        h_align,
+                        v_align,
+                        fit,
+                        scale,
+                        padding: 10.0,
+                        dpr: 2.0,
+                    };
+                    let (s, ox, oy) = layout.transform(source, source, canvas_w, canvas_h);
+                    let (left, top) = (ox, oy);
+                    let (right, bottom) = (ox + source * s, oy + source * s);
+                    let case = format!("{fit:?} x{scale} {h_align:?} {v_align:?}");
+
+                    assert_eq!(right - left, size, "{case}");
+                    assert_eq!(bottom - top, size, "{case}");
+                    match h_align {
+                        HorizontalAlign::Left => assert_eq!(left, 20.0, "{case}"),
+                        HorizontalAlign::Center => assert_eq!(left + right, 400.0, "{case}"),
+                        HorizontalAlign::Right => assert_eq!(right, 380.0, "{case}"),
+                    }
+                    match v_align {
+                        VerticalAlign::Top => assert_eq!(top, 20.0, "{case}"),
+                        VerticalAlign::Middle => assert_eq!(top + bottom, 200.0, "{case}"),
+                        VerticalAlign::Bottom => assert_eq!(bottom, 180.0, "{case}"),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fresh_instance_has_no_columns_and_is_idle() {
+        let mut text = ScatterText::new();
+        assert!(text.pointers().is_none());
+        assert!(text.buffers().is_none());
+        assert!(text.update(1.0 / 60.0));
+        assert_eq!(text.status.particle_count, 0);
+        text.start_forming();
+        text.start_scattering();
+        assert_eq!(text.particle_count(), 0);
+
+        assert!(get_scatter_text_pointers().is_none());
+        assert!(get_scatter_text_buffers().is_none());
+        assert!(update_particles(1.0 / 60.0));
+    }
+
+    // Positions and status after each frame, for comparing instances
+    fn snapshot(text: &ScatterText) -> (Vec<f32>, Vec<f32>, usize, f32, bool) {
+        let count = text.particle_count();
+        let particles = &state(text).particles;
+        (
+            particles.positions_x[..count].to_vec(),
+            particles.positions_y[..count].to_vec(),
+            text.status.particle_count,
+            text.status.mean_distance,
+            text.status.idle,
+        )
+    }
+
+    #[test]
+    fn instances_do_not_share_state() {
+        // Each instance runs next to an untouched twin fed the same calls
+        let (mut forming, mut forming_twin) = (block_text(5, 3), block_text(5, 3));
+        let (mut scattering, mut scattering_twin) = (block_text(4, 8), block_text(4, 8));
+        run_until_idle(&mut scattering, 1000).expect("text never formed");
+        run_until_idle(&mut scattering_twin, 1000).expect("text never formed");
+        scattering.start_scattering();
+        scattering_twin.start_scattering();
+
+        // The default instance behind the free functions is a third one
+        let pixels = vec![255u8; 2 * 2 * 4];
+        assert_eq!(set_text_pixels(&pixels, 2, 2, 100.0, 100.0, 1), 4);
+
+        for _ in 0..30 {
+            forming.update(1.0 / 60.0);
+            scattering.update(1.0 / 60.0);
+            update_particles(1.0 / 60.0);
+            start_scattering();
+            forming_twin.update(1.0 / 60.0);
+            scattering_twin.update(1.0 / 60.0);
+
+            assert_eq!(snapshot(&forming), snapshot(&forming_twin));
+            assert_eq!(snapshot(&scattering), snapshot(&scattering_twin));
+        }
+
+        assert_eq!(forming.particle_count(), 15);
+        assert!(forming.is_forming());
+        assert_eq!(scattering.particle_count(), 32);
+        assert!(!scattering.is_forming());
+        assert_eq!(get_particle_count(), 4);
+        assert!(!is_forming());
+        assert_eq!(get_scatter_text_status().particle_count, 4);
+    }
+
+    #[test]
+    fn base_size_follows_the_size_source() {
+        // Black opaque, white opaque, white at alpha 192
+        let pixels = [[0, 0, 0, 255], [255, 255, 255, 255], [255, 255, 255, 192]];
+        let data = image(3, 1, |x, _| pixels[x as usize]);
+        let sizes = |source: SizeSource| {
+            let mut text = ScatterText::new();
+            text.set_particle_size(source, 2.0, 4.0);
+            text.set_text_pixels(&data, 3, 1, 100.0, 100.0, 1);
+            let particles = &state(&text).particles;
+            assert_eq!(&particles.sizes[..3], &particles.base_size[..3]);
+            particles.base_size[..3].to_vec()
+        };
+
+        assert_eq!(sizes(SizeSource::Uniform), [4.0, 4.0, 4.0]);
+        assert_eq!(
+            sizes(SizeSource::Alpha),
+            [4.0, 4.0, 2.0 + 2.0 * 192.0 / 255.0]
+        );
+        let luminance = sizes(SizeSource::Luminance);
+        assert_eq!(luminance[0], 2.0);
+        assert!((luminance[1] - 4.0).abs() < 1e-4 && (luminance[2] - 4.0).abs() < 1e-4);
+        let random = sizes(SizeSource::Random);
+        for (i, size) in random.into_iter().enumerate() {
+            assert_eq!(size, 2.0 + 2.0 * random_f32(SIZE_STREAM, i as u32));
+        }
+    }
+
+    #[test]
+    fn size_swells_while_forming_and_shrinks_while_scattering() {
+        let mut text = ScatterText::new();
+        text.set_particle_size(SizeSource::Uniform, 2.0, 2.0);
+        text.set_size_animation(0.5, true);
+        text.set_text_pixels(&[255u8; 6 * 4 * 4], 6, 4, 100.0, 100.0, 1);
+        let count = text.particle_count();
+
+        let mut swelled = false;
+        for _ in 0..10 {
+            text.update(1.0 / 60.0);
+            let particles = &state(&text).particles;
+            for i in 0..count {
+                let dx = particles.target_x[i] - particles.positions_x[i];
+                let dy = particles.target_y[i] - particles.positions_y[i];
+                let distance = (dx * dx + dy * dy).sqrt();
+                let expected = 2.0 * (1.0 + (distance / SWELL_DISTANCE).min(1.0) * 0.5);
+                assert!((particles.sizes[i] - expected).abs() < 1e-4);
+                swelled |= particles.sizes[i] > 2.0;
+            }
+        }
+        assert!(swelled);
+
+        // Back to the base size once formed, give or take the settle distance
+        run_until_idle(&mut text, 1000).expect("text never formed");
+        let settled_size = 2.0 * (1.0 + SETTLE_EPSILON / SWELL_DISTANCE * 0.5);
+        assert!(state(&text).particles.sizes[..count]
+            .iter()
+            .all(|&s| (2.0..=settled_size).contains(&s)));
+
+        text.start_scattering();
+        let mut previous = vec![2.0; count];
+        for _ in 0..10 {
+            text.update(1.0 / 60.0);
+            let particles = &state(&text).particles;
+            for i in 0..count {
+                let expected = 2.0 * particles.opacity[i] / particles.base_opacity[i];
+                assert!((particles.sizes[i] - expected).abs() < 1e-4);
+                assert!(particles.sizes[i] <= previous[i]);
+                previous[i] = particles.sizes[i];
+            }
+        }
+        assert!(previous.iter().all(|&s| s < 2.0));
+    }
+
+    #[test]
+    fn rotation_advances_by_spin_while_scattering() {
+        let mut text = block_text(5, 4);
+        text.set_particle_rotation(false, 2.0);
+        run_until_idle(&mut text, 1000).expect("text never formed");
+        let count = text.particle_count();
+        assert!(state(&text).particles.rotations[..count]
+            .iter()
+            .all(|&r| r == 0.0));
+
+        text.start_scattering();
+        let dt = 0.1;
+        for _ in 0..5 {
+            let before = state(&text).particles.rotations[..count].to_vec();
+            text.update(dt);
+            let state = state(&text);
+            let particles = &state.particles;
+            for i in 0..count {
+                let expected = if particles.scatter_delay[i] <= state.scatter_elapsed {
+                    2.0 * dt * particles.scatter_vx[i].signum()
+                } else {
+                    0.0
+                };
+                assert!((particles.rotations[i] - before[i] - expected).abs() < 1e-5);
+            }
+        }
+        assert!(state(&text).particles.rotations[..count]
+            .iter()
+            .all(|&r| r != 0.0));
+    }
+
+    #[test]
+    fn trail_ring_wraps_and_restarts() {
+        let mut text = block_text(3, 3);
+        let count = text.particle_count();
+        text.set_trail_length(4);
+        assert_eq!((text.trail_head(), text.trail_filled()), (0, 1));
+
+        // Positions recorded each frame, oldest first
+        let mut history = vec![state(&text).particles.positions_x[..count].to_vec()];
+        for frame in 1..=10 {
+            text.update(1.0 / 60.0);
+            history.push(state(&text).particles.positions_x[..count].to_vec());
+
+            assert_eq!(text.trail_head(), frame % 4);
+            assert_eq!(text.trail_filled(), (frame + 1).min(4));
+
+            // Slot for each age holds that frame's positions
+            let state = state(&text);
+            let stride = state.particles.positions_x.len();
+            for age in 0..text.trail_filled() {
+                let slot = (text.trail_head() + 4 - age) % 4;
+                let recorded = &state.trail_x[slot * stride..slot * stride + count];
+                assert_eq!(recorded, &history[frame - age][..]);
+            }
+        }
+        // Frame 6 was in slot 2 and has been overwritten by frame 10
+        let stride = state(&text).particles.positions_x.len();
+        assert_eq!(
+            &state(&text).trail_x[2 * stride..2 * stride + count],
+            &history[10][..]
+        );
+        assert_ne!(history[6], history[10]);
+
+        text.resize(200.0, 100.0);
+        assert_eq!((text.trail_head(), text.trail_filled()), (0, 1));
+        assert_eq!(
+            &state(&text).trail_x[..count],
+            &state(&text).particles.positions_x[..count]
+        );
+        text.update(1.0 / 60.0);
+        assert_eq!((text.trail_head(), text.trail_filled()), (1, 2));
+
+        text.set_trail_length(4);
+        assert_eq!((text.trail_head(), text.trail_filled()), (0, 1));
+        text.set_trail_length(0);
+        text.update(1.0 / 60.0);
+        assert_eq!((text.trail_head(), text.trail_filled()), (0, 0));
+    }
+
+    #[test]
+    fn form_easing_places_particles_on_the_curve() {
+        for (easing, expected_t) in [(Easing::QuadIn, 0.25), (Easing::Linear, 0.5)] {
+            let mut text = block_text(5, 3);
+            text.set_form_easing(easing, 1.0);
+            text.start_forming();
+            let count = text.particle_count();
+            let from = positions(&text);
+            let to = targets(&text);
+            assert!(from.iter().zip(&to).any(|(a, b)| a != b));
+
+            // Half the duration, over two frames
+            text.update(0.25);
+            text.update(0.25);
+            for (i, ((x, y), (fx, fy))) in positions(&text).into_iter().zip(&from).enumerate() {
+                let (tx, ty) = to[i];
+                let (ex, ey) = (fx + (tx - fx) * expected_t, fy + (ty - fy) * expected_t);
+                assert!(
+                    (x - ex).abs() < 1e-3 && (y - ey).abs() < 1e-3,
+                    "{easing:?} particle {i}: ({x}, {y}) vs ({ex}, {ey})"
+                );
+            }
+            assert_eq!(text.settled_count(), 0);
+
+            text.update(0.5);
+            assert_eq!(text.settled_count(), count);
+        }
+    }
 }
@@ -1,14 +1,25 @@
 use std::cell::RefCell;
-use std::f32::consts::PI;
 use wasm_bindgen::prelude::*;
 
-use crate::math::{fast_sin_lookup_simd_16, seed_random, seed_random_simd_batch_16};
+use crate::batch::{for_each_batch, padded_len, LaneMask, F32s};
+use crate::color::{ColorFormat, ColorSpace, PackedColors};
+use crate::engine::stages::{Frame, Pipeline, Simulation};
+use crate::engine::{particle_attributes, Flags, ParticleBuffers, ParticleStorage};
+use crate::math::linalg::{Frustum, Mat4};
+use crate::math::sin_approx_simd;
+use crate::rng::{random_f32, random_f32_lanes, unit_vector_3d_lanes};
 
 use std::simd::cmp::SimdPartialOrd;
-use std::simd::f32x16;
 use std::simd::Select;
 
-const SIMD_BATCH_SIZE: usize = 16;
+// Random streams, one per generated star attribute
+const RADIUS_STREAM: u32 = 1;
+const DIRECTION_STREAM: u32 = 2;
+const COLOR_STREAM: u32 = 3;
+const SIZE_CLASS_STREAM: u32 = 4;
+const SMALL_SIZE_STREAM: u32 = 5;
+const LARGE_SIZE_STREAM: u32 = 6;
+const TWINKLE_STREAM: u32 = 7;
 
 // SAFETY: thread_local safe in WASM single-threaded
 thread_local! {
@@ -19,67 +30,78 @@ thread_local! {
     static ROTATION_DELTA_BUFFER: RefCell<[f32; 2]> = const { RefCell::new([0.0; 2]) };
 }
 
-#[repr(C)]
+particle_attributes! {
+    /// Per-star columns shared with JS.
+    struct Stars {
+        positions_x: Vec<f32> = 0.0,
+        positions_y: Vec<f32> = 0.0,
+        positions_z: Vec<f32> = 0.0,
+        colors_r: Vec<f32> = 1.0,
+        colors_g: Vec<f32> = 1.0,
+        colors_b: Vec<f32> = 1.0,
+        sizes: Vec<f32> = 1.0,
+        twinkles: Vec<f32> = 1.0,
+        sparkles: Vec<f32> = 0.0,
+        visibility_mask: Flags = true,
+    }
+}
+
 pub struct StarMemoryPool {
-    positions_x: Vec<f32>,
-    positions_y: Vec<f32>,
-    positions_z: Vec<f32>,
-    colors_r: Vec<f32>,
-    colors_g: Vec<f32>,
-    colors_b: Vec<f32>,
-    sizes: Vec<f32>,
-    twinkles: Vec<f32>,
-    sparkles: Vec<f32>,
-    visibility_mask: Vec<u64>, // Bitpacked: 64 stars per u64
-    count: usize,
+    stars: Stars,
+    color_space: ColorSpace,
+    packed_colors: PackedColors,
+    // Camera for the current frame, None when culling is skipped
+    frustum: Option<Frustum>,
+    visible_count: usize,
+    pipeline: Pipeline<StarMemoryPool>,
 }
 
-impl StarMemoryPool {
-    fn new(count: usize) -> Self {
-        let aligned_count = count.div_ceil(SIMD_BATCH_SIZE) * SIMD_BATCH_SIZE;
+impl Simulation for StarMemoryPool {
+    fn pipeline_mut(&mut self) -> &mut Pipeline<Self> {
+        &mut self.pipeline
+    }
+}
 
+impl StarMemoryPool {
+    fn new(count: usize, color_space: ColorSpace, color_format: ColorFormat) -> Self {
         Self {
-            positions_x: Self::create_aligned_vec(aligned_count, 0.0),
-            positions_y: Self::create_aligned_vec(aligned_count, 0.0),
-            positions_z: Self::create_aligned_vec(aligned_count, 0.0),
-            colors_r: Self::create_aligned_vec(aligned_count, 1.0),
-            colors_g: Self::create_aligned_vec(aligned_count, 1.0),
-            colors_b: Self::create_aligned_vec(aligned_count, 1.0),
-            sizes: Self::create_aligned_vec(aligned_count, 1.0),
-            twinkles: Self::create_aligned_vec(aligned_count, 1.0),
-            sparkles: Self::create_aligned_vec(aligned_count, 0.0),
-            visibility_mask: vec![u64::MAX; aligned_count.div_ceil(64)],
-            count,
+            stars: Stars::with_count(count),
+            color_space,
+            packed_colors: PackedColors::new(color_format, padded_len(count)),
+            frustum: None,
+            visible_count: count,
+            pipeline: Pipeline::new().with(update_effects).with(cull),
         }
     }
 
-    fn create_aligned_vec(size: usize, default_value: f32) -> Vec<f32> {
-        vec![default_value; size]
-    }
-
     fn get_pointers(&mut self) -> StarMemoryPointers {
+        let stars = &mut self.stars;
         StarMemoryPointers {
-            positions_x_ptr: self.positions_x.as_mut_ptr() as u32,
-            positions_y_ptr: self.positions_y.as_mut_ptr() as u32,
-            positions_z_ptr: self.positions_z.as_mut_ptr() as u32,
-            colors_r_ptr: self.colors_r.as_mut_ptr() as u32,
-            colors_g_ptr: self.colors_g.as_mut_ptr() as u32,
-            colors_b_ptr: self.colors_b.as_mut_ptr() as u32,
-            sizes_ptr: self.sizes.as_mut_ptr() as u32,
-            twinkles_ptr: self.twinkles.as_mut_ptr() as u32,
-            sparkles_ptr: self.sparkles.as_mut_ptr() as u32,
-            visibility_ptr: self.visibility_mask.as_mut_ptr() as u32,
-            count: self.count,
-            positions_x_length: self.positions_x.len(),
-            positions_y_length: self.positions_y.len(),
-            positions_z_length: self.positions_z.len(),
-            colors_r_length: self.colors_r.len(),
-            colors_g_length: self.colors_g.len(),
-            colors_b_length: self.colors_b.len(),
-            sizes_length: self.sizes.len(),
-            twinkles_length: self.twinkles.len(),
-            sparkles_length: self.sparkles.len(),
-            visibility_length: self.visibility_mask.len(),
+            positions_x_ptr: stars.positions_x.as_mut_ptr() as u32,
+            positions_y_ptr: stars.positions_y.as_mut_ptr() as u32,
+            positions_z_ptr: stars.positions_z.as_mut_ptr() as u32,
+            colors_r_ptr: stars.colors_r.as_mut_ptr() as u32,
+            colors_g_ptr: stars.colors_g.as_mut_ptr() as u32,
+            colors_b_ptr: stars.colors_b.as_mut_ptr() as u32,
+            sizes_ptr: stars.sizes.as_mut_ptr() as u32,
+            twinkles_ptr: stars.twinkles.as_mut_ptr() as u32,
+            sparkles_ptr: stars.sparkles.as_mut_ptr() as u32,
+            visibility_ptr: stars.visibility_mask.as_mut_ptr() as u32,
+            color_space: self.color_space,
+            color_format: self.packed_colors.format(),
+            packed_colors_ptr: self.packed_colors.ptr(),
+            count: stars.count(),
+            positions_x_length: stars.positions_x.len(),
+            positions_y_length: stars.positions_y.len(),
+            positions_z_length: stars.positions_z.len(),
+            colors_r_length: stars.colors_r.len(),
+            colors_g_length: stars.colors_g.len(),
+            colors_b_length: stars.colors_b.len(),
+            sizes_length: stars.sizes.len(),
+            twinkles_length: stars.twinkles.len(),
+            sparkles_length: stars.sparkles.len(),
+            visibility_length: stars.visibility_mask.len(),
+            packed_colors_length: self.packed_colors.view_length(),
         }
     }
 }
@@ -96,6 +118,11 @@ pub struct StarMemoryPointers {
     pub twinkles_ptr: u32,
     pub sparkles_ptr: u32,
     pub visibility_ptr: u32,
+    /// Encoding of the colour buffers
+    pub color_space: ColorSpace,
+    /// Optional packed copy of the colours, 0 when `color_format` is `None`
+    pub color_format: ColorFormat,
+    pub packed_colors_ptr: u32,
     pub count: usize,
     pub positions_x_length: usize,
     pub positions_y_length: usize,
@@ -107,10 +134,7 @@ pub struct StarMemoryPointers {
     pub twinkles_length: usize,
     pub sparkles_length: usize,
     pub visibility_length: usize,
-}
-
-fn simd_sin_lookup_batch_16(values: f32x16) -> f32x16 {
-    fast_sin_lookup_simd_16(values)
+    pub packed_colors_length: usize,
 }
 
 fn generate_star_colors_simd_direct(
@@ -118,36 +142,31 @@ fn generate_star_colors_simd_direct(
     colors_g: &mut [f32],
     colors_b: &mut [f32],
     count: usize,
+    color_space: ColorSpace,
 ) {
-    use std::simd::f32x16;
-
-    let white_r = f32x16::splat(1.0);
-    let white_g = f32x16::splat(1.0);
-    let white_b = f32x16::splat(1.0);
+    // Palette in sRGB, encoded for the pool on store
+    let white_r = F32s::splat(1.0);
+    let white_g = F32s::splat(1.0);
+    let white_b = F32s::splat(1.0);
 
-    let blue_r = f32x16::splat(0.6);
-    let blue_g = f32x16::splat(0.8);
-    let blue_b = f32x16::splat(1.0);
+    let blue_r = F32s::splat(0.6);
+    let blue_g = F32s::splat(0.8);
+    let blue_b = F32s::splat(1.0);
 
-    let yellow_r = f32x16::splat(1.0);
-    let yellow_g = f32x16::splat(0.8);
-    let yellow_b = f32x16::splat(0.4);
+    let yellow_r = F32s::splat(1.0);
+    let yellow_g = F32s::splat(0.8);
+    let yellow_b = F32s::splat(0.4);
 
-    let purple_r = f32x16::splat(0.8);
-    let purple_g = f32x16::splat(0.6);
-    let purple_b = f32x16::splat(1.0);
+    let purple_r = F32s::splat(0.8);
+    let purple_g = F32s::splat(0.6);
+    let purple_b = F32s::splat(1.0);
 
-    let threshold_50 = f32x16::splat(0.5);
-    let threshold_70 = f32x16::splat(0.7);
-    let threshold_85 = f32x16::splat(0.85);
+    let threshold_50 = F32s::splat(0.5);
+    let threshold_70 = F32s::splat(0.7);
+    let threshold_85 = F32s::splat(0.85);
 
-    let chunks = count / SIMD_BATCH_SIZE;
-
-    for chunk in 0..chunks {
-        let base_idx = chunk * SIMD_BATCH_SIZE;
-        let start_index = base_idx as i32;
-
-        let color_choice = seed_random_simd_batch_16(start_index + 3000);
+    for_each_batch(count, |batch| {
+        let color_choice = random_f32_lanes(COLOR_STREAM, batch.indices());
 
         let is_white = color_choice.simd_lt(threshold_50);
         let is_blue = color_choice.simd_ge(threshold_50) & color_choice.simd_lt(threshold_70);
@@ -168,78 +187,36 @@ fn generate_star_colors_simd_direct(
         result_g = is_white.select(white_g, result_g);
         result_b = is_white.select(white_b, result_b);
 
-        result_r.copy_to_slice(&mut colors_r[base_idx..base_idx + SIMD_BATCH_SIZE]);
-        result_g.copy_to_slice(&mut colors_g[base_idx..base_idx + SIMD_BATCH_SIZE]);
-        result_b.copy_to_slice(&mut colors_b[base_idx..base_idx + SIMD_BATCH_SIZE]);
-    }
-
-    let remaining = count % SIMD_BATCH_SIZE;
-    if remaining > 0 {
-        let base_idx = chunks * SIMD_BATCH_SIZE;
-        for i in 0..remaining {
-            let global_index = (base_idx + i) as i32;
-            let color_choice = seed_random(global_index + 3000);
-
-            let (r, g, b) = if color_choice < 0.5 {
-                (1.0, 1.0, 1.0)
-            } else if color_choice < 0.7 {
-                (0.6, 0.8, 1.0)
-            } else if color_choice < 0.85 {
-                (1.0, 0.8, 0.4)
-            } else {
-                (0.8, 0.6, 1.0)
-            };
-
-            colors_r[base_idx + i] = r;
-            colors_g[base_idx + i] = g;
-            colors_b[base_idx + i] = b;
-        }
-    }
+        let [result_r, result_g, result_b] =
+            color_space.encode_lanes([result_r, result_g, result_b]);
+        batch.store(colors_r, result_r);
+        batch.store(colors_g, result_g);
+        batch.store(colors_b, result_b);
+    });
 }
 
 fn generate_star_sizes_simd_direct(sizes: &mut [f32], count: usize, size_multiplier: f32) {
-    use std::simd::f32x16;
-
-    let threshold_70 = f32x16::splat(0.7);
-    let small_base = f32x16::splat(1.0);
-    let small_range = f32x16::splat(1.5);
-    let large_base = f32x16::splat(2.5);
-    let large_range = f32x16::splat(2.0);
-    let multiplier = f32x16::splat(size_multiplier);
-
-    let chunks = count / SIMD_BATCH_SIZE;
+    let threshold_70 = F32s::splat(0.7);
+    let small_base = F32s::splat(1.0);
+    let small_range = F32s::splat(1.5);
+    let large_base = F32s::splat(2.5);
+    let large_range = F32s::splat(2.0);
+    let multiplier = F32s::splat(size_multiplier);
 
-    for chunk in 0..chunks {
-        let base_idx = chunk * SIMD_BATCH_SIZE;
-        let start_index = base_idx as i32;
+    for_each_batch(count, |batch| {
+        let indices = batch.indices();
 
-        let size_random = seed_random_simd_batch_16(start_index + 4000);
-        let small_random = seed_random_simd_batch_16(start_index + 5000);
-        let large_random = seed_random_simd_batch_16(start_index + 6000);
+        let size_random = random_f32_lanes(SIZE_CLASS_STREAM, indices);
+        let small_random = random_f32_lanes(SMALL_SIZE_STREAM, indices);
+        let large_random = random_f32_lanes(LARGE_SIZE_STREAM, indices);
 
         let small_sizes = small_base + small_random * small_range;
         let large_sizes = large_base + large_random * large_range;
         let is_small = size_random.simd_lt(threshold_70);
         let base_sizes = is_small.select(small_sizes, large_sizes);
-        let final_sizes = base_sizes * multiplier;
 
-        final_sizes.copy_to_slice(&mut sizes[base_idx..base_idx + SIMD_BATCH_SIZE]);
-    }
-
-    let remaining = count % SIMD_BATCH_SIZE;
-    if remaining > 0 {
-        let base_idx = chunks * SIMD_BATCH_SIZE;
-        for i in 0..remaining {
-            let global_index = (base_idx + i) as i32;
-            let size_random = seed_random(global_index + 4000);
-            let base_size = if size_random < 0.7 {
-                1.0 + seed_random(global_index + 5000) * 1.5
-            } else {
-                2.5 + seed_random(global_index + 6000) * 2.0
-            };
-            sizes[base_idx + i] = base_size * size_multiplier;
-        }
-    }
+        batch.store(sizes, base_sizes * multiplier);
+    });
 }
 
 fn generate_star_positions_simd_direct(
@@ -250,105 +227,63 @@ fn generate_star_positions_simd_direct(
     min_radius: f32,
     max_radius: f32,
 ) {
-    let radius_range = max_radius - min_radius;
-    let min_radius_vec = f32x16::splat(min_radius);
-    let radius_range_vec = f32x16::splat(radius_range);
-    let pi2_vec = f32x16::splat(PI * 2.0);
-    let two_vec = f32x16::splat(2.0);
-    let one_vec = f32x16::splat(1.0);
-
-    // Process 16-star batches
-    let chunks = count / SIMD_BATCH_SIZE;
+    let min_radius_vec = F32s::splat(min_radius);
+    let radius_range_vec = F32s::splat(max_radius - min_radius);
 
-    for chunk in 0..chunks {
-        let base_idx = chunk * SIMD_BATCH_SIZE;
-        let start_index = base_idx as i32;
+    for_each_batch(count, |batch| {
+        let indices = batch.indices();
 
-        let radius_rand = seed_random_simd_batch_16(start_index);
+        let radius_rand = random_f32_lanes(RADIUS_STREAM, indices);
         let radius_vec = min_radius_vec + radius_rand * radius_range_vec;
 
-        let theta_rand = seed_random_simd_batch_16(start_index + 1000);
-        let theta_vec = theta_rand * pi2_vec;
-
-        let phi_rand = seed_random_simd_batch_16(start_index + 2000);
-        let phi_input = two_vec * phi_rand - one_vec;
-
-        let phi_values = f32x16::from_array([
-            phi_input.as_array()[0].acos(),
-            phi_input.as_array()[1].acos(),
-            phi_input.as_array()[2].acos(),
-            phi_input.as_array()[3].acos(),
-            phi_input.as_array()[4].acos(),
-            phi_input.as_array()[5].acos(),
-            phi_input.as_array()[6].acos(),
-            phi_input.as_array()[7].acos(),
-            phi_input.as_array()[8].acos(),
-            phi_input.as_array()[9].acos(),
-            phi_input.as_array()[10].acos(),
-            phi_input.as_array()[11].acos(),
-            phi_input.as_array()[12].acos(),
-            phi_input.as_array()[13].acos(),
-            phi_input.as_array()[14].acos(),
-            phi_input.as_array()[15].acos(),
-        ]);
-
-        let sin_phi = fast_sin_lookup_simd_16(phi_values);
-        let cos_phi = fast_sin_lookup_simd_16(phi_values + f32x16::splat(PI / 2.0));
-        let sin_theta = fast_sin_lookup_simd_16(theta_vec);
-        let cos_theta = fast_sin_lookup_simd_16(theta_vec + f32x16::splat(PI / 2.0));
-
-        let x_vec = radius_vec * sin_phi * cos_theta;
-        let y_vec = radius_vec * sin_phi * sin_theta;
-        let z_vec = radius_vec * cos_phi;
-
-        x_vec.copy_to_slice(&mut positions_x[base_idx..base_idx + SIMD_BATCH_SIZE]);
-        y_vec.copy_to_slice(&mut positions_y[base_idx..base_idx + SIMD_BATCH_SIZE]);
-        z_vec.copy_to_slice(&mut positions_z[base_idx..base_idx + SIMD_BATCH_SIZE]);
-    }
+        // Uniform direction on the sphere
+        let (dir_x, dir_y, dir_z) = unit_vector_3d_lanes(DIRECTION_STREAM, indices);
 
-    let remaining = count % SIMD_BATCH_SIZE;
-    if remaining > 0 {
-        let base_idx = chunks * SIMD_BATCH_SIZE;
-        for i in 0..remaining {
-            let global_index = (base_idx + i) as i32;
-            let radius = min_radius + seed_random(global_index) * radius_range;
-            let theta = seed_random(global_index + 1000) * PI * 2.0;
-            let phi = (2.0 * seed_random(global_index + 2000) - 1.0).acos();
-
-            positions_x[base_idx + i] = radius * phi.sin() * theta.cos();
-            positions_y[base_idx + i] = radius * phi.sin() * theta.sin();
-            positions_z[base_idx + i] = radius * phi.cos();
-        }
-    }
+        batch.store(positions_x, radius_vec * dir_x);
+        batch.store(positions_y, radius_vec * dir_y);
+        batch.store(positions_z, radius_vec * dir_z);
+    });
 }
 
 #[wasm_bindgen]
-pub fn initialize_star_memory_pool(count: usize) -> StarMemoryPointers {
-    let mut pool = StarMemoryPool::new(count);
+pub fn initialize_star_memory_pool(
+    count: usize,
+    color_space: ColorSpace,
+    color_format: ColorFormat,
+) -> StarMemoryPointers {
+    let mut pool = StarMemoryPool::new(count, color_space, color_format);
+    let stars = &mut pool.stars;
     generate_star_positions_simd_direct(
-        &mut pool.positions_x,
-        &mut pool.positions_y,
-        &mut pool.positions_z,
+        &mut stars.positions_x,
+        &mut stars.positions_y,
+        &mut stars.positions_z,
         count,
         20.0,
         150.0,
     );
 
     generate_star_colors_simd_direct(
-        &mut pool.colors_r,
-        &mut pool.colors_g,
-        &mut pool.colors_b,
+        &mut stars.colors_r,
+        &mut stars.colors_g,
+        &mut stars.colors_b,
+        count,
+        color_space,
+    );
+    // Star colours never change, so the packed copy is filled once
+    pool.packed_colors.pack(
+        [&stars.colors_r, &stars.colors_g, &stars.colors_b],
+        None,
         count,
     );
 
     generate_star_sizes_simd_direct(
-        &mut pool.sizes,
+        &mut stars.sizes,
         count,
         1.0,
     );
 
-    for i in 0..count {
-        pool.twinkles[i] = 0.8 + seed_random(i as i32 + 7000) * 0.2;
+    for (i, twinkle) in stars.twinkles.iter_mut().enumerate().take(count) {
+        *twinkle = 0.8 + random_f32(TWINKLE_STREAM, i as u32) * 0.2;
     }
 
     let pointers = pool.get_pointers();
@@ -360,6 +295,12 @@ pub fn initialize_star_memory_pool(count: usize) -> StarMemoryPointers {
     pointers
 }
 
+/// Every star column by name, or None before the pool is initialized.
+#[wasm_bindgen]
+pub fn get_star_buffers() -> Option<ParticleBuffers> {
+    STAR_MEMORY_POOL.with(|pool_cell| pool_cell.borrow().as_ref().map(|pool| pool.stars.buffers()))
+}
+
 #[wasm_bindgen]
 pub fn destroy_star_memory_pool() {
     STAR_MEMORY_POOL.with(|pool_cell| {
@@ -375,144 +316,80 @@ fn calculate_effects_into_buffers_simd(
     count: usize,
     time: f32,
 ) {
-    let time_3 = time * 3.0;
-    let time_15 = time * 15.0;
-    let chunks = count / SIMD_BATCH_SIZE;
-    let unrolled_chunks = chunks / 2;
-    let remaining_chunks = chunks % 2;
-    for unroll_idx in 0..unrolled_chunks {
-        let chunk = unroll_idx * 2;
-        let base_idx = chunk * SIMD_BATCH_SIZE;
-
-        let x_slice = &positions_x[base_idx..base_idx + SIMD_BATCH_SIZE];
-        let y_slice = &positions_y[base_idx..base_idx + SIMD_BATCH_SIZE];
-
-        let x_vec = f32x16::from_slice(x_slice);
-        let y_vec = f32x16::from_slice(y_slice);
-
-        let time_3_vec = f32x16::splat(time_3);
-        let factor_10 = f32x16::splat(10.0);
-        let twinkle_arg = time_3_vec + x_vec * factor_10 + y_vec * factor_10;
-        let twinkle_sin = simd_sin_lookup_batch_16(twinkle_arg);
-        let twinkle_scale = f32x16::splat(0.3);
-        let twinkle_offset = f32x16::splat(0.7);
-        let twinkle_base = twinkle_sin * twinkle_scale + twinkle_offset;
-
-        let time_15_vec = f32x16::splat(time_15);
-        let factor_20 = f32x16::splat(20.0);
-        let factor_30 = f32x16::splat(30.0);
-        let sparkle_arg = time_15_vec + x_vec * factor_20 + y_vec * factor_30;
-        let sparkle_phase = simd_sin_lookup_batch_16(sparkle_arg);
-
-        let sparkle_threshold = f32x16::splat(0.98);
-        let sparkle_scale = f32x16::splat(50.0);
-        let sparkle_mask = sparkle_phase.simd_gt(sparkle_threshold);
-        let sparkle_values = sparkle_mask.select(
-            (sparkle_phase - sparkle_threshold) * sparkle_scale,
-            f32x16::splat(0.0),
-        );
-
-        let final_twinkle = twinkle_base + sparkle_values;
-
-        let twinkle_array: [f32; SIMD_BATCH_SIZE] = final_twinkle.to_array();
-        let sparkle_array: [f32; SIMD_BATCH_SIZE] = sparkle_values.to_array();
-
-        twinkles[base_idx..(SIMD_BATCH_SIZE + base_idx)]
-            .copy_from_slice(&twinkle_array[..SIMD_BATCH_SIZE]);
-        sparkles[base_idx..(SIMD_BATCH_SIZE + base_idx)]
-            .copy_from_slice(&sparkle_array[..SIMD_BATCH_SIZE]);
-
-        let chunk2 = chunk + 1;
-        let base_idx2 = chunk2 * SIMD_BATCH_SIZE;
+    let time_3_vec = F32s::splat(time * 3.0);
+    let time_15_vec = F32s::splat(time * 15.0);
+    let factor_10 = F32s::splat(10.0);
+    let factor_20 = F32s::splat(20.0);
+    let factor_30 = F32s::splat(30.0);
+    let twinkle_scale = F32s::splat(0.3);
+    let twinkle_offset = F32s::splat(0.7);
+    let sparkle_threshold = F32s::splat(0.98);
+    let sparkle_scale = F32s::splat(50.0);
+
+    for_each_batch(count, |batch| {
+        let x_vec = batch.load(positions_x);
+        let y_vec = batch.load(positions_y);
 
-        let x_slice2 = &positions_x[base_idx2..base_idx2 + SIMD_BATCH_SIZE];
-        let y_slice2 = &positions_y[base_idx2..base_idx2 + SIMD_BATCH_SIZE];
-
-        let x_vec2 = f32x16::from_slice(x_slice2);
-        let y_vec2 = f32x16::from_slice(y_slice2);
-
-        let twinkle_arg2 = time_3_vec + x_vec2 * factor_10 + y_vec2 * factor_10;
-        let twinkle_sin2 = simd_sin_lookup_batch_16(twinkle_arg2);
-        let twinkle_base2 = twinkle_sin2 * twinkle_scale + twinkle_offset;
-
-        let sparkle_arg2 = time_15_vec + x_vec2 * factor_20 + y_vec2 * factor_30;
-        let sparkle_phase2 = simd_sin_lookup_batch_16(sparkle_arg2);
-        let sparkle_mask2 = sparkle_phase2.simd_gt(sparkle_threshold);
-        let sparkle_values2 = sparkle_mask2.select(
-            (sparkle_phase2 - sparkle_threshold) * sparkle_scale,
-            f32x16::splat(0.0),
-        );
-
-        let final_twinkle2 = twinkle_base2 + sparkle_values2;
-
-        let twinkle_array2: [f32; SIMD_BATCH_SIZE] = final_twinkle2.to_array();
-        let sparkle_array2: [f32; SIMD_BATCH_SIZE] = sparkle_values2.to_array();
-
-        twinkles[base_idx2..(SIMD_BATCH_SIZE + base_idx2)]
-            .copy_from_slice(&twinkle_array2[..SIMD_BATCH_SIZE]);
-        sparkles[base_idx2..(SIMD_BATCH_SIZE + base_idx2)]
-            .copy_from_slice(&sparkle_array2[..SIMD_BATCH_SIZE]);
-    }
-
-    for chunk in (unrolled_chunks * 2)..(unrolled_chunks * 2 + remaining_chunks) {
-        let base_idx = chunk * SIMD_BATCH_SIZE;
-
-        let x_slice = &positions_x[base_idx..base_idx + SIMD_BATCH_SIZE];
-        let y_slice = &positions_y[base_idx..base_idx + SIMD_BATCH_SIZE];
-
-        let x_vec = f32x16::from_slice(x_slice);
-        let y_vec = f32x16::from_slice(y_slice);
-
-        let time_3_vec = f32x16::splat(time_3);
-        let factor_10 = f32x16::splat(10.0);
         let twinkle_arg = time_3_vec + x_vec * factor_10 + y_vec * factor_10;
-        let twinkle_sin = simd_sin_lookup_batch_16(twinkle_arg);
-        let twinkle_scale = f32x16::splat(0.3);
-        let twinkle_offset = f32x16::splat(0.7);
-        let twinkle_base = twinkle_sin * twinkle_scale + twinkle_offset;
-
-        let time_15_vec = f32x16::splat(time_15);
-        let factor_20 = f32x16::splat(20.0);
-        let factor_30 = f32x16::splat(30.0);
+        let twinkle_base = sin_approx_simd(twinkle_arg) * twinkle_scale + twinkle_offset;
+
         let sparkle_arg = time_15_vec + x_vec * factor_20 + y_vec * factor_30;
-        let sparkle_phase = simd_sin_lookup_batch_16(sparkle_arg);
+        let sparkle_phase = sin_approx_simd(sparkle_arg);
 
-        let sparkle_threshold = f32x16::splat(0.98);
-        let sparkle_scale = f32x16::splat(50.0);
         let sparkle_mask = sparkle_phase.simd_gt(sparkle_threshold);
         let sparkle_values = sparkle_mask.select(
             (sparkle_phase - sparkle_threshold) * sparkle_scale,
-            f32x16::splat(0.0),
+            F32s::splat(0.0),
         );
 
-        let final_twinkle = twinkle_base + sparkle_values;
-
-        let twinkle_array: [f32; SIMD_BATCH_SIZE] = final_twinkle.to_array();
-        let sparkle_array: [f32; SIMD_BATCH_SIZE] = sparkle_values.to_array();
+        batch.store(twinkles, twinkle_base + sparkle_values);
+        batch.store(sparkles, sparkle_values);
+    });
+}
 
-        twinkles[base_idx..(SIMD_BATCH_SIZE + base_idx)]
-            .copy_from_slice(&twinkle_array[..SIMD_BATCH_SIZE]);
-        sparkles[base_idx..(SIMD_BATCH_SIZE + base_idx)]
-            .copy_from_slice(&sparkle_array[..SIMD_BATCH_SIZE]);
-    }
+fn update_effects(pool: &mut StarMemoryPool, frame: Frame) {
+    let count = pool.stars.count();
+    let stars = &mut pool.stars;
+    calculate_effects_into_buffers_simd(
+        &stars.positions_x,
+        &stars.positions_y,
+        &mut stars.twinkles,
+        &mut stars.sparkles,
+        count,
+        frame.time,
+    );
+}
 
-    let remaining_start = chunks * SIMD_BATCH_SIZE;
-    for i in remaining_start..count {
-        let x = positions_x[i];
-        let y = positions_y[i];
+fn cull(pool: &mut StarMemoryPool, _frame: Frame) {
+    let count = pool.stars.count();
+    let Some(frustum) = pool.frustum else {
+        pool.stars.visibility_mask.fill(u64::MAX);
+        pool.visible_count = count;
+        return;
+    };
 
-        let twinkle_base =
-            crate::math::fast_sin_lookup(time * 3.0 + x * 10.0 + y * 10.0) * 0.3 + 0.7;
-        let sparkle_phase = crate::math::fast_sin_lookup(time * 15.0 + x * 20.0 + y * 30.0);
-        let sparkle = if sparkle_phase > 0.98 {
-            (sparkle_phase - 0.98) / 0.02
-        } else {
-            0.0
-        };
+    // Mark and count visible stars
+    let stars = &mut pool.stars;
+    let mut visible = 0;
+    for_each_batch(count, |batch| {
+        let x = batch.load(&stars.positions_x);
+        let y = batch.load(&stars.positions_y);
+        let z = batch.load(&stars.positions_z);
+
+        let mut inside = LaneMask::splat(true);
+        for plane in &frustum.planes {
+            let distance = x * F32s::splat(plane.normal.x)
+                + y * F32s::splat(plane.normal.y)
+                + z * F32s::splat(plane.normal.z)
+                + F32s::splat(plane.d);
+            inside &= distance.simd_ge(F32s::splat(0.0));
+        }
+        visible += batch.count(inside);
 
-        twinkles[i] = twinkle_base + sparkle;
-        sparkles[i] = sparkle;
-    }
+        let word = &mut stars.visibility_mask[batch.flag_word()];
+        *word = (*word & !batch.flag_bits(LaneMask::splat(true))) | batch.flag_bits(inside);
+    });
+    pool.visible_count = visible;
 }
 
 #[wasm_bindgen]
@@ -561,33 +438,11 @@ pub struct FrameUpdateResult {
     pub culling_dirty: bool,
 }
 
-fn extract_frustum_planes(vp: &[f32]) -> [[f32; 4]; 6] {
-    // Column-major layout: vp[col * 4 + row]
-    let (r0, r1, r2, r3) = (vp[0], vp[4], vp[8], vp[12]);
-    let (r4, r5, r6, r7) = (vp[1], vp[5], vp[9], vp[13]);
-    let (r8, r9, r10, r11) = (vp[2], vp[6], vp[10], vp[14]);
-    let (r12, r13, r14, r15) = (vp[3], vp[7], vp[11], vp[15]);
-
-    [
-        // Left:   row3 + row0
-        [r3 + r0, r7 + r4, r11 + r8, r15 + r12],
-        // Right:  row3 - row0
-        [r3 - r0, r7 - r4, r11 - r8, r15 - r12],
-        // Bottom: row3 + row1
-        [r3 + r1, r7 + r5, r11 + r9, r15 + r13],
-        // Top:    row3 - row1
-        [r3 - r1, r7 - r5, r11 - r9, r15 - r13],
-        // Near:   row3 + row2
-        [r3 + r2, r7 + r6, r11 + r10, r15 + r14],
-        // Far:    row3 - row2
-        [r3 - r2, r7 - r6, r11 - r10, r15 - r14],
-    ]
-}
-
 #[wasm_bindgen]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn update_frame_simd(
     time: f32,
-    _delta_time: f32,
+    delta_time: f32,
     camera_matrix_ptr: *const f32,
     _is_moving: bool,
     _click_time: f32,
@@ -595,49 +450,15 @@ pub fn update_frame_simd(
 ) -> FrameUpdateResult {
     STAR_MEMORY_POOL.with(|pool_cell| {
         if let Some(pool) = pool_cell.borrow_mut().as_mut() {
-            let count = pool.count;
-
-            calculate_effects_into_buffers_simd(
-                &pool.positions_x,
-                &pool.positions_y,
-                &mut pool.twinkles,
-                &mut pool.sparkles,
-                count,
-                time,
-            );
-
-            let visible_count = if !camera_matrix_ptr.is_null() {
+            pool.frustum = (!camera_matrix_ptr.is_null()).then(|| {
                 // Read view-projection matrix (column-major 4x4)
                 let vp = unsafe { std::slice::from_raw_parts(camera_matrix_ptr, 16) };
-
-                // Extract frustum planes from VP matrix (Gribb-Hartmann method)
-                let planes = extract_frustum_planes(vp);
-
-                // Count visible stars
-                let mut visible = 0;
-                for i in 0..count {
-                    let x = pool.positions_x[i];
-                    let y = pool.positions_y[i];
-                    let z = pool.positions_z[i];
-
-                    let mut inside = true;
-                    for plane in &planes {
-                        if x * plane[0] + y * plane[1] + z * plane[2] + plane[3] < 0.0 {
-                            inside = false;
-                            break;
-                        }
-                    }
-                    if inside {
-                        visible += 1;
-                    }
-                }
-                visible
-            } else {
-                count
-            };
+                Frustum::from_view_projection(&Mat4::from_cols_slice(vp))
+            });
+            pool.step(Frame { time, delta_time });
 
             FrameUpdateResult {
-                visible_count,
+                visible_count: pool.visible_count,
                 positions_dirty: true,
                 effects_dirty: true,
                 culling_dirty: false,
@@ -652,3 +473,102 @@ pub fn update_frame_simd(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::linalg::{Vec3, Vec4};
+
+    fn pool_with_stars(stars: &[Vec3]) -> StarMemoryPool {
+        let mut pool = StarMemoryPool::new(stars.len(), ColorSpace::Srgb, ColorFormat::None);
+        for (i, star) in stars.iter().enumerate() {
+            pool.stars.positions_x[i] = star.x;
+            pool.stars.positions_y[i] = star.y;
+            pool.stars.positions_z[i] = star.z;
+        }
+        pool
+    }
+
+    // Camera at (0, 0, 10) looking at the origin: 90 degree square view, far plane 100
+    fn camera() -> Mat4 {
+        let projection = Mat4::perspective(std::f32::consts::FRAC_PI_2, 1.0, 0.1, 100.0);
+        let view = Mat4::look_at(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO, Vec3::Y);
+        projection * view
+    }
+
+    fn cull_with(pool: &mut StarMemoryPool, vp: &Mat4) -> usize {
+        // Same round trip as the column-major matrix handed over from JS
+        let vp = Mat4::from_cols_slice(&vp.to_cols_array());
+        pool.frustum = Some(Frustum::from_view_projection(&vp));
+        cull(pool, Frame::default());
+        pool.visible_count
+    }
+
+    #[test]
+    fn cull_counts_stars_inside_a_known_camera() {
+        let visible = [
+            Vec3::ZERO,
+            Vec3::new(9.0, 0.0, 0.0),
+            Vec3::new(0.0, -9.0, 0.0),
+            Vec3::new(-50.0, 50.0, -80.0),
+        ];
+        let hidden = [
+            // Past the sides at the origin's depth, where the view is 10 wide each way
+            Vec3::new(11.0, 0.0, 0.0),
+            Vec3::new(0.0, -11.0, 0.0),
+            // Behind the camera, and beyond the far plane
+            Vec3::new(0.0, 0.0, 20.0),
+            Vec3::new(0.0, 0.0, -95.0),
+        ];
+        let stars: Vec<Vec3> = visible.iter().chain(&hidden).copied().collect();
+        let mut pool = pool_with_stars(&stars);
+        assert_eq!(cull_with(&mut pool, &camera()), visible.len());
+        for i in 0..stars.len() {
+            assert_eq!(
+                pool.stars.visibility_mask.get(i),
+                i < visible.len(),
+                "star {i}"
+            );
+        }
+
+        // Without a camera every star counts and is marked visible
+        pool.frustum = None;
+        cull(&mut pool, Frame::default());
+        assert_eq!(pool.visible_count, stars.len());
+        assert!((0..stars.len()).all(|i| pool.stars.visibility_mask.get(i)));
+    }
+
+    #[test]
+    fn cull_agrees_with_clip_space() {
+        // Off-axis camera, so transposed plane extraction would disagree
+        let projection = Mat4::perspective(1.0, 1.6, 0.5, 60.0);
+        let view = Mat4::look_at(
+            Vec3::new(12.0, 5.0, 20.0),
+            Vec3::new(-3.0, 1.0, 0.0),
+            Vec3::Y,
+        );
+        let vp = projection * view;
+
+        let stars: Vec<Vec3> = (0..500u32)
+            .map(|i| {
+                let r = |stream| random_f32(stream, i) * 80.0 - 40.0;
+                Vec3::new(r(101), r(102), r(103))
+            })
+            .collect();
+        let in_view: Vec<bool> = stars
+            .iter()
+            .map(|star| {
+                let clip = vp * Vec4::new(star.x, star.y, star.z, 1.0);
+                [clip.x, clip.y, clip.z].iter().all(|c| c.abs() <= clip.w)
+            })
+            .collect();
+        let expected = in_view.iter().filter(|&&inside| inside).count();
+        assert!(expected > 20 && expected < 480, "{expected} stars in view");
+
+        let mut pool = pool_with_stars(&stars);
+        assert_eq!(cull_with(&mut pool, &vp), expected);
+        for (i, &inside) in in_view.iter().enumerate() {
+            assert_eq!(pool.stars.visibility_mask.get(i), inside, "star {i}");
+        }
+    }
+}
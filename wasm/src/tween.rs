@@ -0,0 +1,700 @@
+// Easing curves, CSS-style cubic beziers and keyframe tracks.
+//
+// Every curve maps progress in [0, 1] to an eased value (0 at the start, 1 at
+// the end, possibly overshooting in between). Like the noise module, each
+// curve is written once over N lanes and the scalar forms run it on one lane.
+
+use std::f32::consts::{LN_2, TAU};
+use std::simd::cmp::SimdPartialOrd;
+use std::simd::num::SimdFloat;
+use std::simd::{f32x16, Select, Simd};
+
+use wasm_bindgen::prelude::*;
+
+use crate::math::{exp_approx_simd, sin_approx_simd};
+
+// Overshoot of the back curves (about 10%)
+const BACK_OVERSHOOT: f32 = 1.701_58;
+
+// Elastic curves ring at a period of 1/3 of the duration
+const ELASTIC_FREQUENCY: f32 = TAU / 3.0;
+
+// Bounce segments: parabola scale and breakpoints at 1, 2 and 2.5 over 2.75
+const BOUNCE_SCALE: f32 = 7.5625;
+const BOUNCE_DIVISOR: f32 = 2.75;
+
+// Bisection then Newton steps used to invert a bezier's x(t)
+const BEZIER_BISECTION_STEPS: usize = 16;
+const BEZIER_NEWTON_STEPS: usize = 2;
+
+type Lanes<const N: usize> = Simd<f32, N>;
+
+#[inline]
+fn one(x: f32) -> Lanes<1> {
+    Simd::splat(x)
+}
+
+#[inline]
+fn unit<const N: usize>(t: Lanes<N>) -> Lanes<N> {
+    t.simd_clamp(Simd::splat(0.0), Simd::splat(1.0))
+}
+
+/// Named easing curves, after Robert Penner's families.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear = 0,
+    /// Hermite 3t^2 - 2t^3
+    SmoothStep = 1,
+    QuadIn = 2,
+    QuadOut = 3,
+    QuadInOut = 4,
+    CubicIn = 5,
+    CubicOut = 6,
+    CubicInOut = 7,
+    ExpoIn = 8,
+    ExpoOut = 9,
+    ExpoInOut = 10,
+    /// Pulls back slightly before moving
+    BackIn = 11,
+    BackOut = 12,
+    BackInOut = 13,
+    /// Springs past the end and rings down
+    ElasticIn = 14,
+    ElasticOut = 15,
+    ElasticInOut = 16,
+    /// Bounces like a ball dropped onto the end value
+    BounceIn = 17,
+    BounceOut = 18,
+    BounceInOut = 19,
+}
+
+#[derive(Clone, Copy)]
+enum Family {
+    Quad,
+    Cubic,
+    Expo,
+    Back,
+    Elastic,
+    Bounce,
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    In,
+    Out,
+    InOut,
+}
+
+impl Easing {
+    fn split(self) -> Option<(Family, Direction)> {
+        use Direction::*;
+        use Family::*;
+        Some(match self {
+            Easing::Linear | Easing::SmoothStep => return None,
+            Easing::QuadIn => (Quad, In),
+            Easing::QuadOut => (Quad, Out),
+            Easing::QuadInOut => (Quad, InOut),
+            Easing::CubicIn => (Cubic, In),
+            Easing::CubicOut => (Cubic, Out),
+            Easing::CubicInOut => (Cubic, InOut),
+            Easing::ExpoIn => (Expo, In),
+            Easing::ExpoOut => (Expo, Out),
+            Easing::ExpoInOut => (Expo, InOut),
+            Easing::BackIn => (Back, In),
+            Easing::BackOut => (Back, Out),
+            Easing::BackInOut => (Back, InOut),
+            Easing::ElasticIn => (Elastic, In),
+            Easing::ElasticOut => (Elastic, Out),
+            Easing::ElasticInOut => (Elastic, InOut),
+            Easing::BounceIn => (Bounce, In),
+            Easing::BounceOut => (Bounce, Out),
+            Easing::BounceInOut => (Bounce, InOut),
+        })
+    }
+
+    /// Eased value at progress `t`, clamped to [0, 1].
+    pub fn apply(self, t: f32) -> f32 {
+        self.apply_lanes(one(t))[0]
+    }
+
+    pub fn apply_x16(self, t: f32x16) -> f32x16 {
+        self.apply_lanes(t)
+    }
+
+    /// `apply` for each lane.
+    pub fn apply_lanes<const N: usize>(self, t: Lanes<N>) -> Lanes<N> {
+        let t = unit(t);
+        let Some((family, direction)) = self.split() else {
+            return match self {
+                Easing::SmoothStep => t * t * (Simd::splat(3.0) - Simd::splat(2.0) * t),
+                _ => t,
+            };
+        };
+
+        // Out and in-out are reflections of the in curve
+        let one = Simd::splat(1.0);
+        match direction {
+            Direction::In => ease_in(family, t),
+            Direction::Out => one - ease_in(family, one - t),
+            Direction::InOut => {
+                let first_half = t.simd_lt(Simd::splat(0.5));
+                let two_t = t * Simd::splat(2.0);
+                let u = first_half.select(two_t, Simd::splat(2.0) - two_t);
+                let half = ease_in(family, u) * Simd::splat(0.5);
+                first_half.select(half, one - half)
+            }
+        }
+    }
+
+    /// Parses the `repr(u8)` value, falling back to `Linear`.
+    pub fn from_u8(value: u8) -> Self {
+        use Easing::*;
+        const ALL: [Easing; 20] = [
+            Linear,
+            SmoothStep,
+            QuadIn,
+            QuadOut,
+            QuadInOut,
+            CubicIn,
+            CubicOut,
+            CubicInOut,
+            ExpoIn,
+            ExpoOut,
+            ExpoInOut,
+            BackIn,
+            BackOut,
+            BackInOut,
+            ElasticIn,
+            ElasticOut,
+            ElasticInOut,
+            BounceIn,
+            BounceOut,
+            BounceInOut,
+        ];
+        ALL.get(value as usize).copied().unwrap_or(Linear)
+    }
+}
+
+// 2^(10t - 10)
+#[inline]
+fn expo_ramp<const N: usize>(t: Lanes<N>) -> Lanes<N> {
+    exp_approx_simd((t * Simd::splat(10.0) - Simd::splat(10.0)) * Simd::splat(LN_2))
+}
+
+#[inline]
+fn bounce_out<const N: usize>(t: Lanes<N>) -> Lanes<N> {
+    let segment = |center: f32, floor: f32| {
+        let d = t - Simd::splat(center / BOUNCE_DIVISOR);
+        Simd::splat(BOUNCE_SCALE) * d * d + Simd::splat(floor)
+    };
+    let first = Simd::splat(BOUNCE_SCALE) * t * t;
+    let second = segment(1.5, 0.75);
+    let third = segment(2.25, 0.9375);
+    let fourth = segment(2.625, 0.984_375);
+
+    let edge = |x: f32| t.simd_lt(Simd::splat(x / BOUNCE_DIVISOR));
+    edge(1.0).select(
+        first,
+        edge(2.0).select(second, edge(2.5).select(third, fourth)),
+    )
+}
+
+// The in form of each family; t is already in [0, 1]
+#[inline]
+fn ease_in<const N: usize>(family: Family, t: Lanes<N>) -> Lanes<N> {
+    let zero = Simd::splat(0.0);
+    let one = Simd::splat(1.0);
+    match family {
+        Family::Quad => t * t,
+        Family::Cubic => t * t * t,
+        // Pinned to exactly 0 and 1 at the ends
+        Family::Expo => t
+            .simd_le(zero)
+            .select(zero, t.simd_ge(one).select(one, expo_ramp(t))),
+        Family::Back => {
+            let c = Simd::splat(BACK_OVERSHOOT);
+            t * t * ((c + one) * t - c)
+        }
+        Family::Elastic => {
+            let phase =
+                (t * Simd::splat(10.0) - Simd::splat(10.75)) * Simd::splat(ELASTIC_FREQUENCY);
+            let ring = -expo_ramp(t) * sin_approx_simd(phase);
+            t.simd_le(zero)
+                .select(zero, t.simd_ge(one).select(one, ring))
+        }
+        Family::Bounce => one - bounce_out(one - t),
+    }
+}
+
+/// Timing function matching CSS `cubic-bezier(x1, y1, x2, y2)`: a bezier from
+/// (0, 0) to (1, 1), read as y for a given x.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+}
+
+impl CubicBezier {
+    pub const EASE: Self = Self::new(0.25, 0.1, 0.25, 1.0);
+    pub const EASE_IN: Self = Self::new(0.42, 0.0, 1.0, 1.0);
+    pub const EASE_OUT: Self = Self::new(0.0, 0.0, 0.58, 1.0);
+    pub const EASE_IN_OUT: Self = Self::new(0.42, 0.0, 0.58, 1.0);
+
+    /// x control values are clamped to [0, 1], as in CSS, so x(t) is monotonic.
+    pub const fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Self {
+            x1: x1.clamp(0.0, 1.0),
+            y1,
+            x2: x2.clamp(0.0, 1.0),
+            y2,
+        }
+    }
+
+    pub fn apply(&self, x: f32) -> f32 {
+        self.apply_lanes(one(x))[0]
+    }
+
+    pub fn apply_x16(&self, x: f32x16) -> f32x16 {
+        self.apply_lanes(x)
+    }
+
+    /// `apply` for each lane.
+    pub fn apply_lanes<const N: usize>(&self, x: Lanes<N>) -> Lanes<N> {
+        let x = unit(x);
+        let t = self.solve_t(x);
+        // The end points are exact, however flat x(t) gets near them
+        let t = x.simd_ge(Simd::splat(1.0)).select(Simd::splat(1.0), t);
+        bezier(self.y1, self.y2, t)
+    }
+
+    // Inverts x(t): bisection to get close on any valid curve, then Newton
+    // steps, which converge quickly from there
+    fn solve_t<const N: usize>(&self, x: Lanes<N>) -> Lanes<N> {
+        let mut low = Simd::splat(0.0);
+        let mut high = Simd::splat(1.0);
+        for _ in 0..BEZIER_BISECTION_STEPS {
+            let mid = (low + high) * Simd::splat(0.5);
+            let below = bezier(self.x1, self.x2, mid).simd_lt(x);
+            low = below.select(mid, low);
+            high = below.select(high, mid);
+        }
+
+        let mut t = (low + high) * Simd::splat(0.5);
+        for _ in 0..BEZIER_NEWTON_STEPS {
+            let error = bezier(self.x1, self.x2, t) - x;
+            let slope = bezier_slope(self.x1, self.x2, t);
+            // Flat spots keep the bisection estimate
+            let step = slope
+                .abs()
+                .simd_gt(Simd::splat(1e-9))
+                .select(error / slope, Simd::splat(0.0));
+            t = (t - step).simd_clamp(low, high);
+        }
+        t
+    }
+}
+
+// One coordinate of the bezier with end points 0 and 1: 3(1-t)^2 t p1 + 3(1-t) t^2 p2 + t^3
+#[inline]
+fn bezier<const N: usize>(p1: f32, p2: f32, t: Lanes<N>) -> Lanes<N> {
+    let a = Simd::splat(1.0 + 3.0 * p1 - 3.0 * p2);
+    let b = Simd::splat(3.0 * p2 - 6.0 * p1);
+    let c = Simd::splat(3.0 * p1);
+    ((a * t + b) * t + c) * t
+}
+
+#[inline]
+fn bezier_slope<const N: usize>(p1: f32, p2: f32, t: Lanes<N>) -> Lanes<N> {
+    let a = Simd::splat(3.0 * (1.0 + 3.0 * p1 - 3.0 * p2));
+    let b = Simd::splat(2.0 * (3.0 * p2 - 6.0 * p1));
+    let c = Simd::splat(3.0 * p1);
+    (a * t + b) * t + c
+}
+
+/// Either a named easing or a bezier timing function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Curve {
+    Easing(Easing),
+    Bezier(CubicBezier),
+}
+
+impl Curve {
+    pub fn apply(&self, t: f32) -> f32 {
+        self.apply_lanes(one(t))[0]
+    }
+
+    pub fn apply_x16(&self, t: f32x16) -> f32x16 {
+        self.apply_lanes(t)
+    }
+
+    pub fn apply_lanes<const N: usize>(&self, t: Lanes<N>) -> Lanes<N> {
+        match self {
+            Curve::Easing(easing) => easing.apply_lanes(t),
+            Curve::Bezier(bezier) => bezier.apply_lanes(t),
+        }
+    }
+}
+
+impl From<Easing> for Curve {
+    fn from(easing: Easing) -> Self {
+        Curve::Easing(easing)
+    }
+}
+
+impl From<CubicBezier> for Curve {
+    fn from(bezier: CubicBezier) -> Self {
+        Curve::Bezier(bezier)
+    }
+}
+
+/// A value at a point in time; `curve` shapes the segment to the next key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub value: f32,
+    pub curve: Curve,
+}
+
+impl Keyframe {
+    pub fn new(time: f32, value: f32, curve: impl Into<Curve>) -> Self {
+        Self {
+            time,
+            value,
+            curve: curve.into(),
+        }
+    }
+}
+
+/// Piecewise curve through keyframes, holding the end values outside them.
+#[derive(Clone, Debug)]
+pub struct Track {
+    keys: Vec<Keyframe>,
+}
+
+impl Track {
+    /// Sorts the keys by time; returns `None` when there are none.
+    pub fn new(keys: &[Keyframe]) -> Option<Self> {
+        if keys.is_empty() || keys.iter().any(|key| !key.time.is_finite()) {
+            return None;
+        }
+        let mut keys = keys.to_vec();
+        keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Some(Self { keys })
+    }
+
+    /// Builds a track from flat (time, value, easing) triples, the easing
+    /// being an `Easing` discriminant, as passed from JS.
+    pub fn from_flat(values: &[f32]) -> Option<Self> {
+        let keys: Vec<Keyframe> = values
+            .as_chunks::<3>()
+            .0
+            .iter()
+            .map(|&[time, value, easing]| Keyframe::new(time, value, Easing::from_u8(easing as u8)))
+            .collect();
+        Self::new(&keys)
+    }
+
+    pub fn keys(&self) -> &[Keyframe] {
+        &self.keys
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keys[self.keys.len() - 1].time - self.keys[0].time
+    }
+
+    pub fn sample(&self, time: f32) -> f32 {
+        self.sample_lanes(one(time))[0]
+    }
+
+    pub fn sample_x16(&self, time: f32x16) -> f32x16 {
+        self.sample_lanes(time)
+    }
+
+    /// `sample` for each lane. Tracks are short, so every segment is evaluated
+    /// and lanes pick theirs by mask rather than gathering.
+    pub fn sample_lanes<const N: usize>(&self, time: Lanes<N>) -> Lanes<N> {
+        let first = self.keys[0];
+        let mut result = Simd::splat(first.value);
+
+        for pair in self.keys.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let span = to.time - from.time;
+            let reached = time.simd_ge(Simd::splat(from.time));
+            let value = if span > 0.0 {
+                let t = (time - Simd::splat(from.time)) * Simd::splat(1.0 / span);
+                let eased = from.curve.apply_lanes(t);
+                Simd::splat(from.value) + Simd::splat(to.value - from.value) * eased
+            } else {
+                // Coincident keys step straight to the later value
+                Simd::splat(to.value)
+            };
+            result = reached.select(value, result);
+        }
+        result
+    }
+}
+
+/// Hermite interpolation between 0 at `edge0` and 1 at `edge1`, as GLSL's smoothstep.
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    smoothstep_lanes(edge0, edge1, one(x))[0]
+}
+
+pub fn smoothstep_lanes<const N: usize>(edge0: f32, edge1: f32, x: Lanes<N>) -> Lanes<N> {
+    let t = (x - Simd::splat(edge0)) / Simd::splat(edge1 - edge0);
+    Easing::SmoothStep.apply_lanes(t)
+}
+
+/// Opacity envelope over a lifetime: linear ramps over the first `fade_in`
+/// and last `fade_out` seconds of `lifetime`, 1 in between.
+pub fn fade_envelope(age: f32, lifetime: f32, fade_in: f32, fade_out: f32) -> f32 {
+    fade_envelope_lanes(one(age), lifetime, fade_in, fade_out)[0]
+}
+
+pub fn fade_envelope_lanes<const N: usize>(
+    age: Lanes<N>,
+    lifetime: f32,
+    fade_in: f32,
+    fade_out: f32,
+) -> Lanes<N> {
+    let ramp = |distance: Lanes<N>, length: f32| {
+        if length > 0.0 {
+            distance * Simd::splat(1.0 / length)
+        } else {
+            Simd::splat(1.0)
+        }
+    };
+    let rise = ramp(age, fade_in);
+    let fall = ramp(Simd::splat(lifetime) - age, fade_out);
+    unit(rise.simd_min(fall))
+}
+
+pub fn fade_envelope_x16(age: f32x16, lifetime: f32, fade_in: f32, fade_out: f32) -> f32x16 {
+    fade_envelope_lanes(age, lifetime, fade_in, fade_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Reference = fn(f64) -> f64;
+
+    const ALL_EASINGS: std::ops::Range<u8> = 0..20;
+
+    fn samples() -> impl Iterator<Item = f32> {
+        (0..=1000).map(|i| i as f32 / 1000.0)
+    }
+
+    // Reference bezier: solve x(t) = x by bisection in f64
+    fn reference_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+        let curve = |p1: f64, p2: f64, t: f64| {
+            3.0 * (1.0 - t).powi(2) * t * p1 + 3.0 * (1.0 - t) * t * t * p2 + t.powi(3)
+        };
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..60 {
+            let mid = 0.5 * (low + high);
+            if curve(x1, x2, mid) < x {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        curve(y1, y2, 0.5 * (low + high))
+    }
+
+    #[test]
+    fn easings_hit_their_end_points() {
+        for id in ALL_EASINGS {
+            let easing = Easing::from_u8(id);
+            assert!(easing.apply(0.0).abs() < 1e-6, "{easing:?} at 0");
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{easing:?} at 1");
+            // Progress outside [0, 1] is clamped
+            assert_eq!(easing.apply(-0.5), easing.apply(0.0));
+            assert_eq!(easing.apply(1.5), easing.apply(1.0));
+        }
+        assert_eq!(Easing::from_u8(200), Easing::Linear);
+    }
+
+    #[test]
+    fn easings_match_reference_formulas() {
+        use std::f64::consts::PI;
+        let c1 = 1.70158;
+        let bounce = |t: f64| {
+            let (n, d) = (7.5625, 2.75);
+            if t < 1.0 / d {
+                n * t * t
+            } else if t < 2.0 / d {
+                n * (t - 1.5 / d).powi(2) + 0.75
+            } else if t < 2.5 / d {
+                n * (t - 2.25 / d).powi(2) + 0.9375
+            } else {
+                n * (t - 2.625 / d).powi(2) + 0.984375
+            }
+        };
+        let cases: [(Easing, Reference); 8] = [
+            (Easing::QuadOut, |t| 1.0 - (1.0 - t).powi(2)),
+            (Easing::CubicInOut, |t| {
+                if t < 0.5 {
+                    4.0 * t.powi(3)
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }),
+            (Easing::ExpoIn, |t| {
+                if t == 0.0 {
+                    0.0
+                } else {
+                    2f64.powf(10.0 * t - 10.0)
+                }
+            }),
+            (Easing::BackIn, |t| {
+                (1.70158 + 1.0) * t.powi(3) - 1.70158 * t * t
+            }),
+            (Easing::ElasticOut, |t| {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            }),
+            (Easing::BounceOut, |t| {
+                let (n, d) = (7.5625, 2.75);
+                if t < 1.0 / d {
+                    n * t * t
+                } else if t < 2.0 / d {
+                    n * (t - 1.5 / d).powi(2) + 0.75
+                } else if t < 2.5 / d {
+                    n * (t - 2.25 / d).powi(2) + 0.9375
+                } else {
+                    n * (t - 2.625 / d).powi(2) + 0.984375
+                }
+            }),
+            (Easing::SmoothStep, |t| t * t * (3.0 - 2.0 * t)),
+            (Easing::Linear, |t| t),
+        ];
+        for (easing, reference) in cases {
+            for t in samples() {
+                let error = (easing.apply(t) as f64 - reference(t as f64)).abs();
+                assert!(error < 2e-6, "{easing:?} at {t}: {error}");
+            }
+        }
+        for t in samples() {
+            let expected = 1.0 - bounce(1.0 - t as f64);
+            assert!((Easing::BounceIn.apply(t) as f64 - expected).abs() < 2e-6);
+            let back_out =
+                1.0 + (c1 + 1.0) * (t as f64 - 1.0).powi(3) + c1 * (t as f64 - 1.0).powi(2);
+            assert!((Easing::BackOut.apply(t) as f64 - back_out).abs() < 2e-6);
+        }
+    }
+
+    #[test]
+    fn in_out_is_symmetric() {
+        for id in ALL_EASINGS {
+            let easing = Easing::from_u8(id);
+            for t in samples() {
+                let mirrored = 1.0 - easing.apply(1.0 - t);
+                if matches!(easing, Easing::Linear | Easing::SmoothStep)
+                    || format!("{easing:?}").ends_with("InOut")
+                {
+                    assert!(
+                        (easing.apply(t) - mirrored).abs() < 1e-5,
+                        "{easing:?} at {t}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        let t = f32x16::from_array(std::array::from_fn(|i| i as f32 / 15.0 * 1.2 - 0.1));
+        let bezier = CubicBezier::EASE;
+        let track = Track::from_flat(&[0.0, 0.0, 4.0, 0.5, 10.0, 15.0, 1.0, -3.0, 0.0]).unwrap();
+        for id in ALL_EASINGS {
+            let easing = Easing::from_u8(id);
+            let lanes = easing.apply_x16(t);
+            for (lane, &value) in t.as_array().iter().enumerate() {
+                assert_eq!(lanes[lane].to_bits(), easing.apply(value).to_bits());
+            }
+        }
+        let (curve, sampled) = (bezier.apply_x16(t), track.sample_x16(t));
+        for (lane, &value) in t.as_array().iter().enumerate() {
+            assert_eq!(curve[lane].to_bits(), bezier.apply(value).to_bits());
+            assert_eq!(sampled[lane].to_bits(), track.sample(value).to_bits());
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_matches_css() {
+        let curves = [
+            CubicBezier::EASE,
+            CubicBezier::EASE_IN,
+            CubicBezier::EASE_OUT,
+            CubicBezier::EASE_IN_OUT,
+            // Overshooting y and a flat start
+            CubicBezier::new(0.68, -0.55, 0.265, 1.55),
+            CubicBezier::new(0.0, 0.0, 0.0, 1.0),
+            CubicBezier::new(1.0, 0.0, 1.0, 0.0),
+        ];
+        for bezier in curves {
+            for x in samples() {
+                let expected = reference_bezier(
+                    bezier.x1 as f64,
+                    bezier.y1 as f64,
+                    bezier.x2 as f64,
+                    bezier.y2 as f64,
+                    x as f64,
+                );
+                let error = (bezier.apply(x) as f64 - expected).abs();
+                assert!(error < 1e-4, "{bezier:?} at {x}: {error}");
+            }
+        }
+        // Linear control points give the identity
+        let linear = CubicBezier::new(0.25, 0.25, 0.75, 0.75);
+        assert!(samples().all(|x| (linear.apply(x) - x).abs() < 1e-5));
+    }
+
+    #[test]
+    fn track_interpolates_between_keys() {
+        let track = Track::new(&[
+            Keyframe::new(1.0, 10.0, Easing::Linear),
+            Keyframe::new(0.0, 0.0, Easing::QuadIn),
+            Keyframe::new(2.0, 20.0, Easing::Linear),
+            Keyframe::new(2.0, 5.0, Easing::Linear),
+        ])
+        .unwrap();
+
+        // Keys are sorted; values hold outside the track
+        assert_eq!(track.keys()[0].time, 0.0);
+        assert_eq!(track.duration(), 2.0);
+        assert_eq!(track.sample(-1.0), 0.0);
+        assert_eq!(track.sample(3.0), 5.0);
+
+        // Each segment uses the curve of its starting key
+        assert_eq!(track.sample(0.5), 10.0 * 0.25);
+        assert_eq!(track.sample(1.5), 15.0);
+        assert_eq!(track.sample(1.0), 10.0);
+        // Coincident keys jump straight to the later one
+        assert_eq!(track.sample(2.0), 5.0);
+
+        assert!(Track::new(&[]).is_none());
+        assert!(Track::from_flat(&[f32::NAN, 1.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn smoothstep_and_fade_envelope() {
+        assert_eq!(smoothstep(2.0, 4.0, 1.0), 0.0);
+        assert_eq!(smoothstep(2.0, 4.0, 3.0), 0.5);
+        assert_eq!(smoothstep(2.0, 4.0, 5.0), 1.0);
+
+        assert_eq!(fade_envelope(0.5, 10.0, 1.0, 2.0), 0.5);
+        assert_eq!(fade_envelope(5.0, 10.0, 1.0, 2.0), 1.0);
+        assert_eq!(fade_envelope(9.0, 10.0, 1.0, 2.0), 0.5);
+        assert_eq!(fade_envelope(12.0, 10.0, 1.0, 2.0), 0.0);
+        // Zero-length fades switch instantly
+        assert_eq!(fade_envelope(0.0, 10.0, 0.0, 0.0), 1.0);
+    }
+}
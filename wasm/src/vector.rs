@@ -0,0 +1,644 @@
+// Vector path flattening and scanline coverage rasterisation
+
+// Sub-scanlines per pixel row for anti-aliased coverage
+const SUBSAMPLES: usize = 4;
+
+// Upper bound on segments emitted for a single curve
+const MAX_CURVE_SEGMENTS: usize = 64;
+
+// Longest side, in pixels, of a raster built from vector input
+pub(crate) const MAX_RASTER_SIDE: f32 = 2048.0;
+
+pub(crate) type Contour = Vec<(f32, f32)>;
+
+// Accumulates move/line/curve commands into polylines, each implicitly closed
+pub(crate) struct PathBuilder {
+    contours: Vec<Contour>,
+    current: Contour,
+    tolerance: f32,
+}
+
+impl PathBuilder {
+    pub(crate) fn new(tolerance: f32) -> Self {
+        Self {
+            contours: Vec::new(),
+            current: Vec::new(),
+            tolerance: tolerance.max(0.01),
+        }
+    }
+
+    fn last_point(&self) -> (f32, f32) {
+        self.current.last().copied().unwrap_or((0.0, 0.0))
+    }
+
+    pub(crate) fn move_to(&mut self, x: f32, y: f32) {
+        self.flush();
+        self.current.push((x, y));
+    }
+
+    pub(crate) fn line_to(&mut self, x: f32, y: f32) {
+        self.current.push((x, y));
+    }
+
+    pub(crate) fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last_point();
+
+        // Max flattening error for n segments is |p0 - 2p1 + p2| / (8n^2)
+        let ddx = x0 - 2.0 * x1 + x;
+        let ddy = y0 - 2.0 * y1 + y;
+        let deviation = (ddx * ddx + ddy * ddy).sqrt();
+        let segments = segment_count(deviation / (8.0 * self.tolerance));
+
+        for i in 1..=segments {
+            let t = i as f32 / segments as f32;
+            let mt = 1.0 - t;
+            self.current.push((
+                mt * mt * x0 + 2.0 * mt * t * x1 + t * t * x,
+                mt * mt * y0 + 2.0 * mt * t * y1 + t * t * y,
+            ));
+        }
+    }
+
+    pub(crate) fn cubic_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last_point();
+
+        // Max flattening error for n segments is 3/4 * max|second difference| / n^2
+        let dd1 = ((x0 - 2.0 * x1 + x2).powi(2) + (y0 - 2.0 * y1 + y2).powi(2)).sqrt();
+        let dd2 = ((x1 - 2.0 * x2 + x).powi(2) + (y1 - 2.0 * y2 + y).powi(2)).sqrt();
+        let segments = segment_count(0.75 * dd1.max(dd2) / self.tolerance);
+
+        for i in 1..=segments {
+            let t = i as f32 / segments as f32;
+            let mt = 1.0 - t;
+            let a = mt * mt * mt;
+            let b = 3.0 * mt * mt * t;
+            let c = 3.0 * mt * t * t;
+            let d = t * t * t;
+            self.current.push((
+                a * x0 + b * x1 + c * x2 + d * x,
+                a * y0 + b * y1 + c * y2 + d * y,
+            ));
+        }
+    }
+
+    // Contours are closed implicitly by their consumers
+    pub(crate) fn close(&mut self) {
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        if self.current.len() > 1 {
+            self.contours.push(std::mem::take(&mut self.current));
+        } else {
+            self.current.clear();
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> Vec<Contour> {
+        self.flush();
+        self.contours
+    }
+}
+
+fn segment_count(squared_segments: f32) -> usize {
+    (squared_segments.sqrt().ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS)
+}
+
+// Factor (at most 1) shrinking `width` x `height` pixels of content so that it
+// plus `padding` on every side fits within MAX_RASTER_SIDE
+pub(crate) fn fit_raster_scale(width: f32, height: f32, padding: f32) -> f32 {
+    let room = MAX_RASTER_SIDE - padding * 2.0;
+    (room / width.max(height)).min(1.0)
+}
+
+// Rasterise closed contours into an 8-bit coverage mask using the non-zero
+// winding rule. Contours are implicitly closed back to their first point.
+pub(crate) fn rasterize_coverage(contours: &[Contour], width: usize, height: usize) -> Vec<u8> {
+    let mut coverage = vec![0u8; width * height];
+    let mut row_accum = vec![0.0f32; width + 1];
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+
+    for row in 0..height {
+        row_accum.fill(0.0);
+
+        for sub in 0..SUBSAMPLES {
+            let scan_y = row as f32 + (sub as f32 + 0.5) / SUBSAMPLES as f32;
+            crossings.clear();
+
+            for contour in contours {
+                let n = contour.len();
+                for i in 0..n {
+                    let (x0, y0) = contour[i];
+                    let (x1, y1) = contour[(i + 1) % n];
+                    if y0 == y1 {
+                        continue;
+                    }
+                    let (winding, top, bottom) = if y0 < y1 { (1, y0, y1) } else { (-1, y1, y0) };
+                    if scan_y < top || scan_y >= bottom {
+                        continue;
+                    }
+                    let t = (scan_y - y0) / (y1 - y0);
+                    crossings.push((x0 + (x1 - x0) * t, winding));
+                }
+            }
+
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                if winding != 0 {
+                    accumulate_span(&mut row_accum, pair[0].0, pair[1].0);
+                }
+            }
+        }
+
+        let out = &mut coverage[row * width..(row + 1) * width];
+        for (pixel, &acc) in out.iter_mut().zip(row_accum.iter()) {
+            *pixel = ((acc / SUBSAMPLES as f32).clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+
+    coverage
+}
+
+// Add fractional horizontal coverage of [x0, x1) to the row accumulator
+fn accumulate_span(row: &mut [f32], x0: f32, x1: f32) {
+    let width = (row.len() - 1) as f32;
+    let x0 = x0.clamp(0.0, width);
+    let x1 = x1.clamp(0.0, width);
+    if x1 <= x0 {
+        return;
+    }
+
+    let first = x0.floor() as usize;
+    let last = x1.floor() as usize;
+    if first == last {
+        row[first] += x1 - x0;
+        return;
+    }
+
+    row[first] += (first + 1) as f32 - x0;
+    for cell in &mut row[first + 1..last] {
+        *cell += 1.0;
+    }
+    row[last] += x1 - last as f32;
+}
+
+// Parse SVG path data (M, L, H, V, C, S, Q, T, Z and their relative forms)
+// into the builder. Elliptical arcs are not supported.
+pub(crate) fn parse_svg_path(data: &str, builder: &mut PathBuilder) -> Result<(), String> {
+    let mut tokens = PathTokens::new(data);
+    let mut command = None;
+    let mut current = (0.0f32, 0.0f32);
+    let mut start = (0.0f32, 0.0f32);
+    // Reflected control point for S/T; None after any other command
+    let mut last_cubic_ctrl: Option<(f32, f32)> = None;
+    let mut last_quad_ctrl: Option<(f32, f32)> = None;
+
+    while let Some(token) = tokens.peek_command_or_number() {
+        let cmd = match token {
+            Token::Command(c) => {
+                tokens.advance_command();
+                c
+            }
+            // Implicit repeat of the previous command
+            Token::Number => match command {
+                Some('M') => 'L',
+                Some('m') => 'l',
+                Some('Z') | Some('z') => return Err("unexpected number after 'Z'".into()),
+                Some(c) => c,
+                None => return Err("path data must start with a command".into()),
+            },
+        };
+        command = Some(cmd);
+
+        let relative = cmd.is_ascii_lowercase();
+        let offset = |p: (f32, f32), cur: (f32, f32)| {
+            if relative {
+                (p.0 + cur.0, p.1 + cur.1)
+            } else {
+                p
+            }
+        };
+
+        match cmd.to_ascii_uppercase() {
+            'M' => {
+                let p = offset(tokens.pair()?, current);
+                builder.move_to(p.0, p.1);
+                current = p;
+                start = p;
+                last_cubic_ctrl = None;
+                last_quad_ctrl = None;
+            }
+            'L' => {
+                let p = offset(tokens.pair()?, current);
+                builder.line_to(p.0, p.1);
+                current = p;
+                last_cubic_ctrl = None;
+                last_quad_ctrl = None;
+            }
+            'H' => {
+                let x = tokens.number()?;
+                current.0 = if relative { current.0 + x } else { x };
+                builder.line_to(current.0, current.1);
+                last_cubic_ctrl = None;
+                last_quad_ctrl = None;
+            }
+            'V' => {
+                let y = tokens.number()?;
+                current.1 = if relative { current.1 + y } else { y };
+                builder.line_to(current.0, current.1);
+                last_cubic_ctrl = None;
+                last_quad_ctrl = None;
+            }
+            'C' | 'S' => {
+                let c1 = if cmd.eq_ignore_ascii_case(&'C') {
+                    offset(tokens.pair()?, current)
+                } else {
+                    reflect(last_cubic_ctrl, current)
+                };
+                let c2 = offset(tokens.pair()?, current);
+                let p = offset(tokens.pair()?, current);
+                builder.cubic_to(c1.0, c1.1, c2.0, c2.1, p.0, p.1);
+                current = p;
+                last_cubic_ctrl = Some(c2);
+                last_quad_ctrl = None;
+            }
+            'Q' | 'T' => {
+                let c = if cmd.eq_ignore_ascii_case(&'Q') {
+                    offset(tokens.pair()?, current)
+                } else {
+                    reflect(last_quad_ctrl, current)
+                };
+                let p = offset(tokens.pair()?, current);
+                builder.quad_to(c.0, c.1, p.0, p.1);
+                current = p;
+                last_quad_ctrl = Some(c);
+                last_cubic_ctrl = None;
+            }
+            'Z' => {
+                builder.close();
+                current = start;
+                last_cubic_ctrl = None;
+                last_quad_ctrl = None;
+                // Drawing after Z continues from the subpath start
+                builder.move_to(start.0, start.1);
+            }
+            other => return Err(format!("unsupported path command '{}'", other)),
+        }
+    }
+    Ok(())
+}
+
+fn reflect(control: Option<(f32, f32)>, current: (f32, f32)) -> (f32, f32) {
+    match control {
+        Some((cx, cy)) => (2.0 * current.0 - cx, 2.0 * current.1 - cy),
+        None => current,
+    }
+}
+
+enum Token {
+    Command(char),
+    Number,
+}
+
+struct PathTokens<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PathTokens<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            bytes: data.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.bytes.len()
+            && (self.bytes[self.pos].is_ascii_whitespace() || self.bytes[self.pos] == b',')
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek_command_or_number(&mut self) -> Option<Token> {
+        self.skip_separators();
+        let &byte = self.bytes.get(self.pos)?;
+        if byte.is_ascii_alphabetic() && byte != b'e' && byte != b'E' {
+            Some(Token::Command(byte as char))
+        } else {
+            Some(Token::Number)
+        }
+    }
+
+    fn advance_command(&mut self) {
+        self.pos += 1;
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        self.skip_separators();
+        let start = self.pos;
+        let bytes = self.bytes;
+        let mut end = start;
+        if end < bytes.len() && (bytes[end] == b'-' || bytes[end] == b'+') {
+            end += 1;
+        }
+        let mut seen_dot = false;
+        while end < bytes.len() {
+            match bytes[end] {
+                b'0'..=b'9' => end += 1,
+                b'.' if !seen_dot => {
+                    seen_dot = true;
+                    end += 1;
+                }
+                b'e' | b'E' => {
+                    end += 1;
+                    if end < bytes.len() && (bytes[end] == b'-' || bytes[end] == b'+') {
+                        end += 1;
+                    }
+                    while end < bytes.len() && bytes[end].is_ascii_digit() {
+                        end += 1;
+                    }
+                    break;
+                }
+                _ => break,
+            }
+        }
+
+        let text = std::str::from_utf8(&bytes[start..end]).unwrap_or("");
+        let value = text
+            .parse::<f32>()
+            .map_err(|_| format!("expected a number at offset {}", start))?;
+        self.pos = end;
+        Ok(value)
+    }
+
+    fn pair(&mut self) -> Result<(f32, f32), String> {
+        Ok((self.number()?, self.number()?))
+    }
+}
+
+// Points spaced `spacing` apart along each contour's outline. Like
+// `rasterize_coverage`, contours are implicitly closed back to their first point.
+pub(crate) fn sample_contours(contours: &[Contour], spacing: f32) -> Vec<(f32, f32)> {
+    let spacing = spacing.max(0.5);
+    let mut points = Vec::new();
+
+    for contour in contours {
+        let Some(&first) = contour.first() else {
+            continue;
+        };
+        let contour_start = points.len();
+        points.push(first);
+        // Distance still to travel before the next sample
+        let mut remaining = spacing;
+
+        let n = contour.len();
+        for i in 0..n {
+            let (x0, y0) = contour[i];
+            let (x1, y1) = contour[(i + 1) % n];
+            let length = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+            let mut travelled = 0.0;
+
+            while length - travelled >= remaining {
+                travelled += remaining;
+                let t = travelled / length;
+                points.push((x0 + (x1 - x0) * t, y0 + (y1 - y0) * t));
+                remaining = spacing;
+            }
+            remaining -= length - travelled;
+        }
+
+        // A perimeter that is a multiple of `spacing` ends back on the first point
+        if points.len() - contour_start > 1 {
+            let (x, y) = points[points.len() - 1];
+            if (x - first.0).powi(2) + (y - first.1).powi(2) < (spacing * 0.5).powi(2) {
+                points.pop();
+            }
+        }
+    }
+    points
+}
+
+// Axis-aligned bounds (min_x, min_y, max_x, max_y) of all contour points
+pub(crate) fn contour_bounds(contours: &[Contour]) -> Option<(f32, f32, f32, f32)> {
+    let mut points = contours.iter().flatten();
+    let &(x, y) = points.next()?;
+    Some(
+        points.fold((x, y, x, y), |(min_x, min_y, max_x, max_y), &(px, py)| {
+            (min_x.min(px), min_y.min(py), max_x.max(px), max_y.max(py))
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> Contour {
+        vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+    }
+
+    fn reversed(mut contour: Contour) -> Contour {
+        contour.reverse();
+        contour
+    }
+
+    fn total(coverage: &[u8]) -> u32 {
+        coverage.iter().map(|&c| c as u32).sum()
+    }
+
+    #[test]
+    fn rectangle_covers_whole_and_partial_pixels() {
+        let coverage = rasterize_coverage(&[rect(2.0, 1.0, 6.0, 4.0)], 8, 6);
+        for y in 0..6 {
+            for x in 0..8 {
+                let inside = (2..6).contains(&x) && (1..4).contains(&y);
+                assert_eq!(
+                    coverage[y * 8 + x],
+                    if inside { 255 } else { 0 },
+                    "({x}, {y})"
+                );
+            }
+        }
+
+        // Half-pixel edges give half coverage
+        let coverage = rasterize_coverage(&[rect(1.5, 0.0, 3.0, 2.0)], 4, 2);
+        assert_eq!(&coverage[..4], &[0, 128, 255, 0]);
+    }
+
+    #[test]
+    fn triangle_coverage_matches_its_area() {
+        let triangle = vec![(0.0, 0.0), (16.0, 0.0), (0.0, 16.0)];
+        let coverage = rasterize_coverage(&[triangle], 16, 16);
+        assert_eq!(coverage[0], 255);
+        assert_eq!(coverage[15 * 16 + 15], 0);
+        // 128 px^2 of area, within sub-scanline error along the hypotenuse
+        let area = total(&coverage) as f32 / 255.0;
+        assert!((area - 128.0).abs() < 2.0, "area {area}");
+    }
+
+    #[test]
+    fn non_zero_winding_fills_overlaps_and_cuts_holes() {
+        let outer = rect(0.0, 0.0, 8.0, 8.0);
+        let inner = rect(2.0, 2.0, 6.0, 6.0);
+
+        // Same direction: winding 2 inside the overlap still counts once
+        let coverage = rasterize_coverage(&[outer.clone(), inner.clone()], 8, 8);
+        assert!(coverage.iter().all(|&c| c == 255));
+
+        // Opposite direction: winding 0 in the middle is a hole
+        let coverage = rasterize_coverage(&[outer, reversed(inner)], 8, 8);
+        assert_eq!(coverage[3 * 8 + 3], 0);
+        assert_eq!(coverage[8 + 1], 255);
+        assert_eq!(total(&coverage), (64 - 16) * 255);
+    }
+
+    #[test]
+    fn contours_are_closed_and_clipped_to_the_raster() {
+        // No repeated first point, and partly outside the raster
+        let coverage = rasterize_coverage(&[rect(-2.0, -2.0, 3.0, 3.0)], 4, 4);
+        assert_eq!(total(&coverage), 9 * 255);
+        assert_eq!(coverage[2 * 4 + 2], 255);
+        assert_eq!(coverage[3 * 4 + 3], 0);
+    }
+
+    #[test]
+    fn outline_sampling_walks_the_closing_edge() {
+        let square = rect(0.0, 0.0, 10.0, 10.0);
+        // Corners only: the closing edge's end sample would repeat the start
+        assert_eq!(
+            sample_contours(std::slice::from_ref(&square), 10.0),
+            [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]
+        );
+
+        // An explicitly repeated first point samples the same outline
+        let mut closed = square.clone();
+        closed.push(closed[0]);
+        assert_eq!(
+            sample_contours(&[closed], 5.0),
+            sample_contours(&[square], 5.0)
+        );
+
+        // Open polyline: the left edge back to (0, 0) is sampled too
+        let open = vec![(0.0, 0.0), (8.0, 0.0), (0.0, 6.0)];
+        let points = sample_contours(&[open], 2.0);
+        assert_eq!(points.len(), 12);
+        assert!(points.contains(&(0.0, 2.0)));
+        assert!(points.contains(&(0.0, 4.0)));
+    }
+
+    fn parse(data: &str) -> Result<Vec<Contour>, String> {
+        let mut builder = PathBuilder::new(0.2);
+        parse_svg_path(data, &mut builder)?;
+        Ok(builder.finish())
+    }
+
+    fn assert_same_path(a: &str, b: &str) {
+        assert_eq!(parse(a).unwrap(), parse(b).unwrap(), "{a:?} vs {b:?}");
+    }
+
+    #[test]
+    fn repeated_coordinates_repeat_the_command() {
+        // Pairs after a move are line-tos, in the move's relative or absolute form
+        assert_eq!(
+            parse("M0 0 10 0 10 10").unwrap(),
+            [vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]]
+        );
+        assert_eq!(
+            parse("m1 1 2 0 0 2").unwrap(),
+            [vec![(1.0, 1.0), (3.0, 1.0), (3.0, 3.0)]]
+        );
+        assert_same_path("M0 0 H5 10 V5", "M0 0 H5 H10 V5");
+        assert_same_path("M0 0 q5 5 10 0 5 -5 10 0", "M0 0 q5 5 10 0 q5 -5 10 0");
+        // Compact number syntax: signs and second dots start a new number
+        assert_eq!(
+            parse("M0,0L.5.5-1-1e1").unwrap(),
+            [vec![(0.0, 0.0), (0.5, 0.5), (-1.0, -10.0)]]
+        );
+    }
+
+    #[test]
+    fn relative_commands_offset_from_the_current_point() {
+        assert_same_path("M10 10 l5 0 v5 h-5 z", "M10 10 L15 10 V15 H10 Z");
+        assert_same_path(
+            "M10 10 c0 5 5 5 5 0 q5 -5 10 0",
+            "M10 10 C10 15 15 15 15 10 Q20 5 25 10",
+        );
+    }
+
+    #[test]
+    fn smooth_curves_reflect_the_previous_control_point() {
+        assert_same_path(
+            "M0 0 C0 10 10 10 10 0 S20 -10 20 0",
+            "M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0",
+        );
+        assert_same_path("M0 0 Q5 10 10 0 T20 0", "M0 0 Q5 10 10 0 Q15 -10 20 0");
+        assert_same_path(
+            "M0 0 c0 10 10 10 10 0 s10 -10 10 0",
+            "M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0",
+        );
+        // Without a preceding curve of the same kind the control is the current point
+        assert_same_path("M0 0 L5 0 S15 10 20 0", "M0 0 L5 0 C5 0 15 10 20 0");
+        assert_same_path("M0 0 C0 5 5 5 5 0 T20 0", "M0 0 C0 5 5 5 5 0 Q5 0 20 0");
+    }
+
+    #[test]
+    fn drawing_after_close_starts_at_the_subpath_start() {
+        let contours = parse("M0 0 H10 V10 Z L20 20 L0 20").unwrap();
+        assert_eq!(contours.len(), 2);
+        assert_eq!(contours[0], [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        assert_eq!(contours[1], [(0.0, 0.0), (20.0, 20.0), (0.0, 20.0)]);
+
+        // A relative move after Z is measured from the closed subpath's start
+        let contours = parse("M10 10 h5 v5 z m1 1 h2 v2 z").unwrap();
+        assert_eq!(contours[1], [(11.0, 11.0), (13.0, 11.0), (13.0, 13.0)]);
+
+        // A lone Z or move draws nothing
+        assert!(parse("M5 5 Z").unwrap().is_empty());
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_path_data_is_rejected() {
+        for data in [
+            "10 10",
+            "M0 0 L10 10 Z 5 5",
+            "M0 0 A5 5 0 0 1 10 10",
+            "M0 0 L10",
+            "M0 0 Lx 10",
+            "M0 0 C1 1 2 2",
+        ] {
+            assert!(parse(data).is_err(), "{data:?} parsed");
+        }
+    }
+
+    #[test]
+    fn curve_flattening_is_capped() {
+        let mut builder = PathBuilder::new(0.01);
+        builder.move_to(0.0, 0.0);
+        builder.cubic_to(0.0, 1e6, 1e6, 1e6, 1e6, 0.0);
+        builder.move_to(0.0, 0.0);
+        builder.quad_to(5e5, 1e6, 1e6, 0.0);
+        let contours = builder.finish();
+        assert_eq!(contours[0].len(), 1 + MAX_CURVE_SEGMENTS);
+        assert_eq!(contours[1].len(), 1 + MAX_CURVE_SEGMENTS);
+        assert_eq!(contours[0][MAX_CURVE_SEGMENTS], (1e6, 0.0));
+
+        // Flat curves collapse to a single segment
+        let contours = parse("M0 0 C1 0 2 0 3 0").unwrap();
+        assert_eq!(contours[0], [(0.0, 0.0), (3.0, 0.0)]);
+
+        // Finer tolerance, more segments, up to the cap
+        let segments = |tolerance| {
+            let mut builder = PathBuilder::new(tolerance);
+            parse_svg_path("M0 0 Q50 100 100 0", &mut builder).unwrap();
+            builder.finish()[0].len() - 1
+        };
+        assert!(segments(1.0) < segments(0.1));
+        assert!(segments(0.01) <= MAX_CURVE_SEGMENTS);
+    }
+}
@@ -0,0 +1,88 @@
+#!/usr/bin/env python3
+"""Writes test_font.ttf: a minimal TrueType font for the glyph layout tests.
+
+1000 units per em, ascender 800, descender -200, no line gap.
+  'A'  rectangle x 100..500, y 0..700, advance 600
+  'V'  triangle (0, 700) (300, 0) (600, 700), advance 600
+  kern A V = -200
+"""
+
+import struct
+from pathlib import Path
+
+GLYPHS = [
+    # (advance, contour points in font units)
+    (500, []),  # .notdef
+    (600, [(100, 0), (100, 700), (500, 700), (500, 0)]),
+    (600, [(0, 700), (600, 700), (300, 0)]),
+]
+CMAP = {ord("A"): 1, ord("V"): 2}
+KERNING = {(1, 2): -200}
+
+
+def simple_glyph(points):
+    if not points:
+        return b""
+    xs = [x for x, _ in points]
+    ys = [y for _, y in points]
+    data = struct.pack(">hhhhh", 1, min(xs), min(ys), max(xs), max(ys))
+    data += struct.pack(">HH", len(points) - 1, 0)
+    data += bytes([1] * len(points))  # on-curve, 16-bit deltas
+    prev = 0
+    for x in xs:
+        data += struct.pack(">h", x - prev)
+        prev = x
+    prev = 0
+    for y in ys:
+        data += struct.pack(">h", y - prev)
+        prev = y
+    return data + b"\0" * (len(data) % 2)
+
+
+def build():
+    glyf = b""
+    offsets = []
+    for _, points in GLYPHS:
+        offsets.append(len(glyf))
+        glyf += simple_glyph(points)
+    offsets.append(len(glyf))
+    loca = b"".join(struct.pack(">H", o // 2) for o in offsets)
+
+    head = struct.pack(
+        ">IIIIHHqqhhhhHHhhh",
+        0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0, 1000,
+        0, 0, 0, 0, 600, 700, 0, 8, 2, 0, 0,
+    )
+    hhea = struct.pack(
+        ">Ihhh H hhh hhh hhhh h H",
+        0x00010000, 800, -200, 0, 600, 0, 0, 600, 1, 0, 0, 0, 0, 0, 0, 0, len(GLYPHS),
+    )
+    maxp = struct.pack(">IH", 0x00005000, len(GLYPHS))
+    hmtx = b"".join(
+        struct.pack(">Hh", advance, min((x for x, _ in points), default=0))
+        for advance, points in GLYPHS
+    )
+
+    groups = b"".join(struct.pack(">III", c, c, g) for c, g in sorted(CMAP.items()))
+    cmap12 = struct.pack(">HHIII", 12, 0, 16 + len(groups), 0, len(CMAP)) + groups
+    cmap = struct.pack(">HHHHI", 0, 1, 3, 10, 12) + cmap12
+
+    pairs = b"".join(struct.pack(">HHh", l, r, v) for (l, r), v in sorted(KERNING.items()))
+    subtable = struct.pack(">HHHHHHH", 0, 14 + len(pairs), 0x0001, len(KERNING), 6, 0, 0)
+    kern = struct.pack(">HH", 0, 1) + subtable + pairs
+
+    tables = {
+        b"cmap": cmap, b"glyf": glyf, b"head": head, b"hhea": hhea,
+        b"hmtx": hmtx, b"kern": kern, b"loca": loca, b"maxp": maxp,
+    }
+    offset = 12 + 16 * len(tables)
+    directory = struct.pack(">IHHHH", 0x00010000, len(tables), 128, 3, 0)
+    body = b""
+    for tag, data in tables.items():
+        directory += struct.pack(">4sIII", tag, 0, offset + len(body), len(data))
+        body += data + b"\0" * (-len(data) % 4)
+    return directory + body
+
+
+if __name__ == "__main__":
+    Path(__file__).with_name("test_font.ttf").write_bytes(build())